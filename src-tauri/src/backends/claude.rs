@@ -1,7 +1,10 @@
 // Claude (Anthropic) Backend Implementation
 
+use axum::http::HeaderMap;
+
 use crate::backends::custom::CustomBackendSettings;
 use crate::backends::Backend;
+use crate::dlp::{DlpPatternScope, DlpScanScope};
 use crate::requestresponsemetadata::{RequestMetadata, ResponseMetadata, ToolCall};
 use std::collections::HashMap;
 
@@ -221,6 +224,24 @@ impl Backend for ClaudeBackend {
         self.settings.dlp_enabled
     }
 
+    fn dlp_scope(&self) -> DlpPatternScope {
+        self.settings.dlp_scope()
+    }
+
+    fn dlp_scan_scope(&self) -> DlpScanScope {
+        self.settings.dlp_scan_scope()
+    }
+
+    fn session_key(&self, body: &str, _headers: &HeaderMap) -> Option<String> {
+        // Claude Code sends metadata.user_id, which embeds the session id
+        let json = serde_json::from_str::<serde_json::Value>(body).ok()?;
+        json.get("metadata")
+            .and_then(|m| m.get("user_id"))
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    }
+
     fn get_rate_limit(&self) -> (u32, u32) {
         (self.settings.rate_limit_requests, self.settings.rate_limit_minutes.max(1))
     }
@@ -5,6 +5,7 @@ use serde_json::json;
 
 use crate::backends::custom::CustomBackendSettings;
 use crate::backends::Backend;
+use crate::dlp::{DlpPatternScope, DlpScanScope};
 use crate::requestresponsemetadata::{RequestMetadata, ResponseMetadata, ToolCall};
 use std::collections::HashMap;
 
@@ -157,9 +158,7 @@ impl Backend for CodexBackend {
                                             let name = item.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string();
                                             let arguments = item.get("arguments").and_then(|v| v.as_str()).unwrap_or("").to_string();
                                             // Only add if not already tracked via streaming
-                                            if !function_calls_map.contains_key(&item_id) {
-                                                function_calls_map.insert(item_id, (call_id, name, arguments));
-                                            }
+                                            function_calls_map.entry(item_id).or_insert((call_id, name, arguments));
                                         }
                                     }
                                 }
@@ -310,6 +309,30 @@ impl Backend for CodexBackend {
         self.settings.dlp_enabled
     }
 
+    fn dlp_scope(&self) -> DlpPatternScope {
+        self.settings.dlp_scope()
+    }
+
+    fn dlp_scan_scope(&self) -> DlpScanScope {
+        self.settings.dlp_scan_scope()
+    }
+
+    fn session_key(&self, body: &str, headers: &HeaderMap) -> Option<String> {
+        // Codex sends the session id as a header; prompt_cache_key is per conversation too
+        for header in ["session_id", "conversation_id"] {
+            if let Some(id) = headers.get(header).and_then(|v| v.to_str().ok()) {
+                if !id.is_empty() {
+                    return Some(id.to_string());
+                }
+            }
+        }
+        let json = serde_json::from_str::<serde_json::Value>(body).ok()?;
+        json.get("prompt_cache_key")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+    }
+
     fn get_rate_limit(&self) -> (u32, u32) {
         (self.settings.rate_limit_requests, self.settings.rate_limit_minutes.max(1))
     }
@@ -4,6 +4,7 @@ use axum::http::HeaderMap;
 use serde::{Deserialize, Serialize};
 
 use crate::backends::Backend;
+use crate::dlp::{DlpPatternAction, DlpPatternScope, DlpScanScope};
 use crate::requestresponsemetadata::{RequestMetadata, ResponseMetadata};
 
 /// Settings for a custom backend
@@ -24,6 +25,104 @@ pub struct CustomBackendSettings {
     /// Action to take when max tokens is exceeded: "block" or "notify" (default: "block")
     #[serde(default = "default_block")]
     pub action_for_max_tokens_in_a_request: String,
+    /// DLP pattern names applied to this backend (empty = all enabled patterns)
+    #[serde(default)]
+    pub dlp_include_patterns: Vec<String>,
+    /// DLP pattern names never applied to this backend
+    #[serde(default)]
+    pub dlp_exclude_patterns: Vec<String>,
+    /// Action used for every detection on this backend instead of the pattern's own:
+    /// "log", "notify", "redact" or "block" (default: none)
+    #[serde(default)]
+    pub dlp_action_override: Option<String>,
+    /// Scan user message text (default: true)
+    #[serde(default = "default_true")]
+    pub dlp_scan_user_messages: bool,
+    /// Scan tool and function call results (default: true)
+    #[serde(default = "default_true")]
+    pub dlp_scan_tool_results: bool,
+    /// Scan system prompts and instructions (default: false)
+    #[serde(default)]
+    pub dlp_scan_system_prompt: bool,
+    /// Scan tool definitions (default: false)
+    #[serde(default)]
+    pub dlp_scan_tool_definitions: bool,
+    /// Header carrying the agent session id, so a value keeps its DLP placeholder
+    /// across turns (default: none, each request gets fresh placeholders)
+    #[serde(default)]
+    pub dlp_session_header: Option<String>,
+    /// Request body field carrying the session id, as a dot-separated path
+    /// (e.g. "metadata.session_id"); used when the header is not set or missing
+    #[serde(default)]
+    pub dlp_session_field: Option<String>,
+}
+
+impl CustomBackendSettings {
+    /// DLP pattern scope for this backend
+    pub fn dlp_scope(&self) -> DlpPatternScope {
+        DlpPatternScope {
+            include: self.dlp_include_patterns.clone(),
+            exclude: self.dlp_exclude_patterns.clone(),
+            action_override: self
+                .dlp_action_override
+                .as_deref()
+                .and_then(DlpPatternAction::parse),
+        }
+    }
+
+    /// Parts of a request scanned on this backend
+    pub fn dlp_scan_scope(&self) -> DlpScanScope {
+        DlpScanScope {
+            user_messages: self.dlp_scan_user_messages,
+            tool_results: self.dlp_scan_tool_results,
+            system_prompt: self.dlp_scan_system_prompt,
+            tool_definitions: self.dlp_scan_tool_definitions,
+        }
+    }
+
+    /// Session id of a request, from the configured header or body field
+    pub fn session_key(&self, body: &str, headers: &HeaderMap) -> Option<String> {
+        let header = self.dlp_session_header.as_deref().map(str::trim).filter(|h| !h.is_empty());
+        if let Some(id) = header.and_then(|h| headers.get(h)).and_then(|v| v.to_str().ok()) {
+            if !id.is_empty() {
+                return Some(id.to_string());
+            }
+        }
+
+        let field = self.dlp_session_field.as_deref().map(str::trim).filter(|f| !f.is_empty())?;
+        let json = serde_json::from_str::<serde_json::Value>(body).ok()?;
+        let value = field.split('.').try_fold(&json, |value, key| value.get(key))?;
+        match value {
+            serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Check the DLP scope against the known pattern names
+    pub fn validate_dlp_scope(&self, pattern_names: &[String]) -> Result<(), String> {
+        for name in self.dlp_include_patterns.iter().chain(&self.dlp_exclude_patterns) {
+            if !pattern_names.contains(name) {
+                return Err(format!("Unknown detection pattern '{}'", name));
+            }
+        }
+        if let Some(name) = self
+            .dlp_include_patterns
+            .iter()
+            .find(|n| self.dlp_exclude_patterns.contains(n))
+        {
+            return Err(format!("Pattern '{}' cannot be both included and excluded", name));
+        }
+        if let Some(action) = &self.dlp_action_override {
+            if DlpPatternAction::parse(action).is_none() {
+                return Err(format!(
+                    "Invalid action override '{}'. Must be 'log', 'notify', 'redact' or 'block'",
+                    action
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 fn default_true() -> bool {
@@ -213,6 +312,18 @@ impl Backend for CustomBackend {
         self.settings.dlp_enabled
     }
 
+    fn dlp_scope(&self) -> DlpPatternScope {
+        self.settings.dlp_scope()
+    }
+
+    fn dlp_scan_scope(&self) -> DlpScanScope {
+        self.settings.dlp_scan_scope()
+    }
+
+    fn session_key(&self, body: &str, headers: &HeaderMap) -> Option<String> {
+        self.settings.session_key(body, headers)
+    }
+
     fn get_rate_limit(&self) -> (u32, u32) {
         (self.settings.rate_limit_requests, self.settings.rate_limit_minutes.max(1))
     }
@@ -221,3 +332,26 @@ impl Backend for CustomBackend {
         (self.settings.max_tokens_in_a_request, self.settings.action_for_max_tokens_in_a_request.clone())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_key_from_header_or_field() {
+        let settings: CustomBackendSettings =
+            serde_json::from_str(r#"{"dlp_session_header": "X-Session-Id", "dlp_session_field": "metadata.session_id"}"#)
+                .unwrap();
+        let body = r#"{"model": "m", "metadata": {"session_id": "from-body"}}"#;
+
+        let mut headers = HeaderMap::new();
+        assert_eq!(settings.session_key(body, &headers).as_deref(), Some("from-body"));
+        headers.insert("x-session-id", "from-header".parse().unwrap());
+        assert_eq!(settings.session_key(body, &headers).as_deref(), Some("from-header"));
+
+        // Without a configured source every request gets fresh placeholders
+        let unset = CustomBackendSettings::default();
+        assert_eq!(unset.session_key(body, &headers), None);
+        assert_eq!(settings.session_key(r#"{"metadata": {}}"#, &HeaderMap::new()), None);
+    }
+}
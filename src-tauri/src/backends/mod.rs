@@ -5,6 +5,7 @@ pub mod codex;
 pub mod custom;
 
 use axum::http::HeaderMap;
+use crate::dlp::{DlpPatternScope, DlpScanScope};
 use crate::requestresponsemetadata::{RequestMetadata, ResponseMetadata};
 
 /// Trait for API backend implementations
@@ -44,6 +45,25 @@ pub trait Backend: Send + Sync {
         true
     }
 
+    /// Get the DLP patterns and action override applied to this backend
+    /// Default implementation applies every enabled pattern with its own action
+    fn dlp_scope(&self) -> DlpPatternScope {
+        DlpPatternScope::default()
+    }
+
+    /// Get the parts of a request scanned for sensitive data
+    /// Default implementation scans user messages and tool results
+    fn dlp_scan_scope(&self) -> DlpScanScope {
+        DlpScanScope::default()
+    }
+
+    /// Identify the agent session a request belongs to, so DLP placeholders
+    /// stay the same across its turns
+    /// Default implementation returns None (each request gets fresh placeholders)
+    fn session_key(&self, _body: &str, _headers: &HeaderMap) -> Option<String> {
+        None
+    }
+
     /// Get rate limit settings (requests per window, window in minutes)
     /// Returns (0, 1) by default which means no rate limit
     fn get_rate_limit(&self) -> (u32, u32) {
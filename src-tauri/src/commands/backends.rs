@@ -2,6 +2,7 @@
 
 use crate::backends::claude::ANTHROPIC_BASE_URL;
 use crate::backends::codex::CODEX_BASE_URL;
+use crate::backends::custom::CustomBackendSettings;
 use crate::database::{CustomBackendRecord, Database};
 use crate::dlp_pattern_config::get_db_path;
 use serde::{Deserialize, Serialize};
@@ -22,13 +23,59 @@ impl From<CustomBackendRecord> for CustomBackendResponse {
             id: record.id,
             name: record.name,
             base_url: record.base_url,
-            settings: record.settings,
+            settings: normalize_settings(&record.settings),
             enabled: record.enabled,
             created_at: record.created_at,
         }
     }
 }
 
+/// Validate a settings JSON string and return it normalized
+/// Unknown pattern names and invalid DLP actions are rejected so a typo never
+/// silently widens or narrows a backend's protection
+fn validate_settings(db: &Database, settings: &str) -> Result<String, String> {
+    let settings = settings.trim();
+    let settings = if settings.is_empty() { "{}" } else { settings };
+
+    serde_json::from_str::<serde_json::Value>(settings)
+        .map_err(|_| "Settings must be valid JSON".to_string())?;
+    let mut parsed: CustomBackendSettings = serde_json::from_str(settings)
+        .map_err(|e| format!("Invalid settings: {}", e))?;
+
+    for names in [&mut parsed.dlp_include_patterns, &mut parsed.dlp_exclude_patterns] {
+        let mut cleaned: Vec<String> = Vec::new();
+        for name in names.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+            if !cleaned.iter().any(|c| c == name) {
+                cleaned.push(name.to_string());
+            }
+        }
+        *names = cleaned;
+    }
+    if parsed.dlp_action_override.as_deref().map(str::trim) == Some("") {
+        parsed.dlp_action_override = None;
+    }
+    for source in [&mut parsed.dlp_session_header, &mut parsed.dlp_session_field] {
+        *source = source.as_deref().map(str::trim).filter(|s| !s.is_empty()).map(String::from);
+    }
+    if let Some(header) = &parsed.dlp_session_header {
+        axum::http::HeaderName::from_bytes(header.as_bytes())
+            .map_err(|_| format!("Invalid session header name '{}'", header))?;
+    }
+
+    let pattern_names = db.get_dlp_pattern_names().map_err(|e| e.to_string())?;
+    parsed.validate_dlp_scope(&pattern_names)?;
+
+    serde_json::to_string(&parsed).map_err(|e| e.to_string())
+}
+
+/// Fill in defaults so the UI sees every setting, including DLP scope fields
+fn normalize_settings(settings: &str) -> String {
+    serde_json::from_str::<CustomBackendSettings>(settings)
+        .ok()
+        .and_then(|parsed| serde_json::to_string(&parsed).ok())
+        .unwrap_or_else(|| settings.to_string())
+}
+
 /// Get all custom backends
 #[tauri::command]
 pub fn get_custom_backends() -> Result<Vec<CustomBackendResponse>, String> {
@@ -64,22 +111,15 @@ pub fn add_custom_backend(
         return Err("Base URL must start with http:// or https://".to_string());
     }
 
-    // Validate settings is valid JSON
-    let settings = settings.trim();
-    if !settings.is_empty() && settings != "{}" {
-        serde_json::from_str::<serde_json::Value>(settings)
-            .map_err(|_| "Settings must be valid JSON".to_string())?;
-    }
-    let settings = if settings.is_empty() { "{}" } else { settings };
-
     let db = Database::new(get_db_path()).map_err(|e| e.to_string())?;
+    let settings = validate_settings(&db, &settings)?;
 
     // Check if name already exists
     if db.backend_name_exists(name).map_err(|e| e.to_string())? {
         return Err(format!("Backend name '{}' already exists or is reserved", name));
     }
 
-    db.add_custom_backend(name, base_url, settings)
+    db.add_custom_backend(name, base_url, &settings)
         .map_err(|e| e.to_string())
 }
 
@@ -109,22 +149,15 @@ pub fn update_custom_backend(
         return Err("Base URL must start with http:// or https://".to_string());
     }
 
-    // Validate settings is valid JSON
-    let settings = settings.trim();
-    if !settings.is_empty() && settings != "{}" {
-        serde_json::from_str::<serde_json::Value>(settings)
-            .map_err(|_| "Settings must be valid JSON".to_string())?;
-    }
-    let settings = if settings.is_empty() { "{}" } else { settings };
-
     let db = Database::new(get_db_path()).map_err(|e| e.to_string())?;
+    let settings = validate_settings(&db, &settings)?;
 
     // Check if name already exists (excluding this backend)
     if db.backend_name_exists_excluding(name, id).map_err(|e| e.to_string())? {
         return Err(format!("Backend name '{}' already exists or is reserved", name));
     }
 
-    db.update_custom_backend(id, name, base_url, settings)
+    db.update_custom_backend(id, name, base_url, &settings)
         .map_err(|e| e.to_string())
 }
 
@@ -179,7 +212,7 @@ pub fn get_predefined_backends() -> Result<Vec<PredefinedBackendResponse>, Strin
         backends.push(PredefinedBackendResponse {
             name: name.to_string(),
             base_url: base_url.to_string(),
-            settings,
+            settings: normalize_settings(&settings),
         });
     }
 
@@ -195,17 +228,10 @@ pub fn update_predefined_backend(name: String, settings: String) -> Result<(), S
         return Err(format!("Unknown predefined backend: {}", name));
     }
 
-    // Validate settings is valid JSON
-    let settings = settings.trim();
-    if !settings.is_empty() && settings != "{}" {
-        serde_json::from_str::<serde_json::Value>(settings)
-            .map_err(|_| "Settings must be valid JSON".to_string())?;
-    }
-    let settings = if settings.is_empty() { "{}" } else { settings };
-
     let db = Database::new(get_db_path()).map_err(|e| e.to_string())?;
+    let settings = validate_settings(&db, &settings)?;
 
-    db.update_predefined_backend_settings(&name, settings)
+    db.update_predefined_backend_settings(&name, &settings)
         .map_err(|e| e.to_string())
 }
 
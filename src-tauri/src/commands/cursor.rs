@@ -1,5 +1,7 @@
 // Cursor Hooks Installation Commands
 
+use crate::database::get_or_create_access_token;
+use crate::proxy_auth::ACCESS_TOKEN_HEADER;
 use crate::PROXY_PORT;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -24,7 +26,7 @@ fn get_hooks_json_path() -> Result<PathBuf, String> {
 }
 
 /// Generate the shell script content
-fn generate_shell_script(port: u16) -> String {
+fn generate_shell_script(port: u16, access_token: &str) -> String {
     format!(
         r#"#!/bin/bash
 # Quilr DLP Hook Script for Cursor
@@ -72,6 +74,7 @@ esac
 # Call the Quilr API
 RESPONSE=$(echo "$INPUT" | curl -s -X POST \
     -H "Content-Type: application/json" \
+    -H "{token_header}: {access_token}" \
     -d @- \
     "http://localhost:{port}/cursor_hook/$ENDPOINT" 2>/dev/null)
 
@@ -94,7 +97,9 @@ fi
 # Return the API response
 echo "$RESPONSE"
 "#,
-        port = port
+        port = port,
+        token_header = ACCESS_TOKEN_HEADER,
+        access_token = access_token
     )
 }
 
@@ -135,7 +140,8 @@ pub fn install_cursor_hooks() -> Result<String, String> {
 
     // Write the shell script
     let script_path = get_script_path()?;
-    let script_content = generate_shell_script(port);
+    let access_token = get_or_create_access_token()?;
+    let script_content = generate_shell_script(port, &access_token);
     fs::write(&script_path, &script_content)
         .map_err(|e| format!("Failed to write hook script: {}", e))?;
 
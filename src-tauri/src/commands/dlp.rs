@@ -1,9 +1,20 @@
 // DLP Settings Tauri Commands
 
-use crate::database::{get_dlp_action_from_db, open_connection, save_dlp_action_to_db};
+use crate::database::{get_dlp_action_from_db, open_connection, save_dlp_action_to_db, Database};
+use crate::detection_storage::{get_storage_mode, save_storage_mode, DetectionStorageMode, DetectionValueEncoder};
+use crate::dictionary::{self, DictionaryDiff, DictionaryOptions};
+use crate::dlp::{DlpPatternAction, PlaceholderStrategy};
+use crate::dlp_exceptions::{parse_expiry, DlpExceptionRule};
+use crate::dlp_pattern_config::get_db_path;
+use crate::known_secrets::{self, KnownSecretSyncResult, KNOWN_SECRET_TYPE};
+use crate::pattern_corpus::{self, CorpusReport};
 use crate::pattern_utils::{
-    collect_matches_with_negative_context, compile_pattern_set, filter_by_min_occurrences,
+    collect_matches_with_context, compile_entropy_patterns, compile_pattern_set, compile_patterns,
+    filter_by_min_occurrences, resolve_context_window, resolve_redact_group, resolve_validator, CandidateOutcome,
+    EntropyOptions, MatchHighlight, NegativeScope,
 };
+use crate::retro_scan::{self, RetroScanOptions};
+use crate::rule_import::{self, RuleFormat, RuleImportResult, SkippedRule};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -14,10 +25,47 @@ pub struct DlpPattern {
     pub patterns: Vec<String>,
     pub negative_pattern_type: Option<String>,
     pub negative_patterns: Option<Vec<String>>,
+    /// What negative patterns are checked against: "context" (None) or "value"
+    pub negative_scope: Option<String>,
     pub enabled: bool,
+    /// Shadow mode: matches are recorded but the request is left untouched
+    pub shadow: bool,
     pub min_occurrences: i32,
     pub min_unique_chars: i32,
     pub is_builtin: bool,
+    /// None = use the global detection action
+    pub action: Option<String>,
+    /// How redacted values are replaced (None = format-preserving)
+    pub placeholder_strategy: Option<String>,
+    /// Entropy patterns only: minimum bits per character (None = alphabet default)
+    pub entropy_threshold: Option<f64>,
+    /// Entropy patterns only: minimum token length (None = default)
+    pub entropy_min_length: Option<i32>,
+    /// Validator patterns only: checksum validator name (e.g. "luhn")
+    pub validator: Option<String>,
+    /// Regex/validator patterns only: capture group to redact (None = whole match)
+    pub redact_group: Option<String>,
+    /// Characters around a match checked for negative/required context (None = 30)
+    pub context_window: Option<i32>,
+    /// A match only counts if one of these appears in its context window
+    pub required_context: Option<Vec<String>>,
+    pub required_context_type: Option<String>,
+    /// Dictionary patterns only: match case exactly (default off)
+    pub dictionary_case_sensitive: bool,
+    /// Dictionary patterns only: match whole words only (default on)
+    pub dictionary_whole_words: bool,
+    /// Dictionary patterns only: number of entries (they are not listed in `patterns`)
+    pub dictionary_size: i64,
+    /// Builtin library section (builtins only)
+    pub category: Option<String>,
+    /// Builtin edited by the user; library upgrades leave it alone
+    pub user_modified: bool,
+    /// Rule import that manages this pattern (None = added by hand)
+    pub import_id: Option<i64>,
+    /// Regression corpus: samples the pattern must match and must not match
+    /// (builtins also run their stock samples)
+    pub must_match: Vec<String>,
+    pub must_not_match: Vec<String>,
 }
 
 #[derive(Serialize)]
@@ -33,7 +81,13 @@ pub fn get_dlp_settings() -> Result<DlpSettings, String> {
     let mut stmt = conn
         .prepare(
             "SELECT id, name, pattern_type, patterns, negative_pattern_type, negative_patterns,
-                    enabled, min_occurrences, min_unique_chars, is_builtin
+                    enabled, min_occurrences, min_unique_chars, is_builtin, action,
+                    entropy_threshold, entropy_min_length, validator, category,
+                    COALESCE(user_modified, 0), import_id, placeholder_strategy, redact_group,
+                    context_window, required_context, required_context_type,
+                    COALESCE(dictionary_case_sensitive, 0), COALESCE(dictionary_whole_words, 1),
+                    (SELECT COUNT(*) FROM dlp_dictionary_entries e WHERE e.pattern_id = dlp_patterns.id),
+                    COALESCE(shadow, 0), must_match, must_not_match, negative_scope
              FROM dlp_patterns ORDER BY is_builtin DESC, id",
         )
         .map_err(|e| e.to_string())?;
@@ -47,6 +101,15 @@ pub fn get_dlp_settings() -> Result<DlpSettings, String> {
             let negative_patterns: Option<Vec<String>> = negative_patterns_json
                 .and_then(|json| serde_json::from_str(&json).ok());
 
+            let required_context_json: Option<String> = row.get(20)?;
+            let required_context: Option<Vec<String>> = required_context_json
+                .and_then(|json| serde_json::from_str(&json).ok());
+
+            let samples = |idx: usize| -> rusqlite::Result<Vec<String>> {
+                let json: Option<String> = row.get(idx)?;
+                Ok(json.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default())
+            };
+
             Ok(DlpPattern {
                 id: row.get(0)?,
                 name: row.get(1)?,
@@ -54,10 +117,29 @@ pub fn get_dlp_settings() -> Result<DlpSettings, String> {
                 patterns,
                 negative_pattern_type: row.get(4)?,
                 negative_patterns,
+                negative_scope: row.get(28)?,
                 enabled: row.get::<_, i32>(6)? == 1,
+                shadow: row.get::<_, i32>(25)? == 1,
                 min_occurrences: row.get(7)?,
                 min_unique_chars: row.get(8)?,
                 is_builtin: row.get::<_, i32>(9)? == 1,
+                action: row.get(10)?,
+                entropy_threshold: row.get(11)?,
+                entropy_min_length: row.get(12)?,
+                validator: row.get(13)?,
+                category: row.get(14)?,
+                user_modified: row.get::<_, i32>(15)? == 1,
+                import_id: row.get(16)?,
+                placeholder_strategy: row.get(17)?,
+                redact_group: row.get(18)?,
+                context_window: row.get(19)?,
+                required_context,
+                required_context_type: row.get(21)?,
+                dictionary_case_sensitive: row.get::<_, i32>(22)? == 1,
+                dictionary_whole_words: row.get::<_, i32>(23)? == 1,
+                dictionary_size: row.get(24)?,
+                must_match: samples(26)?,
+                must_not_match: samples(27)?,
             })
         })
         .map_err(|e| e.to_string())?
@@ -67,22 +149,65 @@ pub fn get_dlp_settings() -> Result<DlpSettings, String> {
     Ok(DlpSettings { patterns })
 }
 
+/// A saved pattern
+#[derive(Serialize)]
+pub struct DlpPatternSaved {
+    pub id: i64,
+    /// Regression corpus failures the save was allowed despite
+    pub corpus_warning: Option<String>,
+}
+
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn add_dlp_pattern(
     name: String,
     pattern_type: String,
     patterns: Vec<String>,
     negative_pattern_type: Option<String>,
     negative_patterns: Option<Vec<String>>,
+    negative_scope: Option<String>,
     min_occurrences: Option<i32>,
     min_unique_chars: Option<i32>,
-) -> Result<i64, String> {
+    action: Option<String>,
+    entropy_threshold: Option<f64>,
+    entropy_min_length: Option<i32>,
+    validator: Option<String>,
+    placeholder_strategy: Option<String>,
+    redact_group: Option<String>,
+    context_window: Option<i32>,
+    required_context: Option<Vec<String>>,
+    required_context_type: Option<String>,
+    dictionary_case_sensitive: Option<bool>,
+    dictionary_whole_words: Option<bool>,
+    shadow: Option<bool>,
+    must_match: Option<Vec<String>>,
+    must_not_match: Option<Vec<String>>,
+    allow_corpus_failures: Option<bool>,
+) -> Result<DlpPatternSaved, String> {
     if name.trim().is_empty() {
         return Err("Name is required".to_string());
     }
-    if patterns.is_empty() {
+    // Dictionaries can start empty and be filled from a file
+    let is_dictionary = pattern_type == "dictionary";
+    if patterns.is_empty() && !is_dictionary {
         return Err("At least one pattern is required".to_string());
     }
+    let action = validate_pattern_action(action)?;
+    let placeholder_strategy = validate_placeholder_strategy(placeholder_strategy)?;
+    let negative_scope = validate_negative_scope(negative_scope)?;
+    let entropy = entropy_options(entropy_threshold, entropy_min_length);
+    if pattern_type == "entropy" {
+        compile_entropy_patterns(&patterns, &entropy)?;
+    }
+    let validator = resolve_validator(&pattern_type, validator.as_deref())?;
+    let redact_group = validate_redact_group(&patterns, &pattern_type, redact_group)?;
+    let context_window = validate_context_window(context_window)?;
+    let required_context_json = validate_required_context(required_context.as_ref(), required_context_type.as_deref())?;
+    let required_context_type = required_context_json.as_ref().and(required_context_type);
+
+    // Dictionary entries are stored in their own table, not in `patterns`
+    let dictionary_entries = is_dictionary.then(|| dictionary::parse_entries(&patterns.join("\n")));
+    let patterns = if is_dictionary { Vec::new() } else { patterns };
 
     let conn = open_connection().map_err(|e| e.to_string())?;
     let patterns_json = serde_json::to_string(&patterns).map_err(|e| e.to_string())?;
@@ -91,9 +216,11 @@ pub fn add_dlp_pattern(
         .map(|np| serde_json::to_string(np).unwrap_or_else(|_| "[]".to_string()));
     let created_at = chrono::Utc::now().to_rfc3339();
 
-    conn.execute(
-        "INSERT INTO dlp_patterns (name, pattern_type, patterns, negative_pattern_type, negative_patterns, enabled, min_occurrences, min_unique_chars, is_builtin, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6, ?7, 0, ?8)",
+    // Saved only if the pattern passes its regression corpus
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT INTO dlp_patterns (name, pattern_type, patterns, negative_pattern_type, negative_patterns, enabled, min_occurrences, min_unique_chars, is_builtin, created_at, action, entropy_threshold, entropy_min_length, validator, placeholder_strategy, redact_group, context_window, required_context, required_context_type, dictionary_case_sensitive, dictionary_whole_words, shadow, must_match, must_not_match, negative_scope)
+         VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6, ?7, 0, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23)",
         rusqlite::params![
             name.trim(),
             pattern_type,
@@ -102,15 +229,39 @@ pub fn add_dlp_pattern(
             negative_patterns_json,
             min_occurrences.unwrap_or(1),
             min_unique_chars.unwrap_or(0),
-            created_at
+            created_at,
+            action,
+            entropy.threshold,
+            entropy.min_length,
+            validator.map(|v| v.as_str()),
+            placeholder_strategy,
+            redact_group,
+            context_window,
+            required_context_json,
+            required_context_type,
+            dictionary_case_sensitive.filter(|_| is_dictionary),
+            dictionary_whole_words.filter(|_| is_dictionary),
+            shadow.unwrap_or(false),
+            corpus_json(must_match.as_ref())?,
+            corpus_json(must_not_match.as_ref())?,
+            negative_scope
         ],
     )
     .map_err(|e| e.to_string())?;
 
-    Ok(conn.last_insert_rowid())
+    let id = tx.last_insert_rowid();
+    if let Some(entries) = dictionary_entries {
+        dictionary::replace_entries(&tx, id, &entries).map_err(|e| e.to_string())?;
+    }
+    let corpus_warning = check_corpus(&tx, id, allow_corpus_failures)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    crate::DLP_PATTERNS.reload();
+
+    Ok(DlpPatternSaved { id, corpus_warning })
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn update_dlp_pattern(
     id: i64,
     name: Option<String>,
@@ -118,12 +269,76 @@ pub fn update_dlp_pattern(
     patterns: Option<Vec<String>>,
     negative_pattern_type: Option<String>,
     negative_patterns: Option<Vec<String>>,
+    negative_scope: Option<String>,
     enabled: Option<bool>,
     min_occurrences: Option<i32>,
     min_unique_chars: Option<i32>,
-) -> Result<(), String> {
+    action: Option<String>,
+    entropy_threshold: Option<f64>,
+    entropy_min_length: Option<i32>,
+    validator: Option<String>,
+    placeholder_strategy: Option<String>,
+    redact_group: Option<String>,
+    context_window: Option<i32>,
+    required_context: Option<Vec<String>>,
+    required_context_type: Option<String>,
+    dictionary_case_sensitive: Option<bool>,
+    dictionary_whole_words: Option<bool>,
+    shadow: Option<bool>,
+    must_match: Option<Vec<String>>,
+    must_not_match: Option<Vec<String>>,
+    allow_corpus_failures: Option<bool>,
+) -> Result<DlpPatternSaved, String> {
+    // Validator - required when switching to the "validator" type, cleared
+    // when switching away from it, empty string resets it otherwise
+    let validator_update: Option<Option<&'static str>> = match pattern_type.as_deref() {
+        Some(pt) if pt != "validator" => Some(None),
+        Some(pt) => Some(resolve_validator(pt, validator.as_deref())?.map(|v| v.as_str())),
+        None => match validator.as_deref().map(str::trim) {
+            None => None,
+            Some("") => Some(None),
+            Some(v) => Some(resolve_validator("validator", Some(v))?.map(|v| v.as_str())),
+        },
+    };
+
     let conn = open_connection().map_err(|e| e.to_string())?;
 
+    let (saved_type, saved_patterns, saved_threshold, saved_min_length): (String, String, Option<f64>, Option<i32>) = conn
+        .query_row(
+            "SELECT pattern_type, patterns, entropy_threshold, entropy_min_length FROM dlp_patterns WHERE id = ?1",
+            rusqlite::params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .map_err(|_| "Pattern not found".to_string())?;
+    let saved_patterns: Vec<String> = serde_json::from_str(&saved_patterns).unwrap_or_default();
+    let effective_type = pattern_type.as_deref().unwrap_or(&saved_type);
+    let was_dictionary = saved_type == "dictionary";
+    let is_dictionary = effective_type == "dictionary";
+
+    // Entropy settings - checked whenever the pattern is (or becomes) an entropy
+    // pattern, against the saved alphabets and settings where none are given
+    let entropy_changed =
+        pattern_type.is_some() || patterns.is_some() || entropy_threshold.is_some() || entropy_min_length.is_some();
+    if effective_type == "entropy" && entropy_changed {
+        let entropy = entropy_options(
+            entropy_threshold.or(saved_threshold),
+            entropy_min_length.or(saved_min_length),
+        );
+        compile_entropy_patterns(patterns.as_ref().unwrap_or(&saved_patterns), &entropy)?;
+    }
+
+    // Redact group - checked against the saved patterns unless new ones are given,
+    // cleared when switching to a type that cannot have one, empty string resets it
+    let redact_group_update: Option<Option<String>> = match (redact_group, pattern_type.as_deref()) {
+        (None, Some(pt)) if pt != "regex" && pt != "validator" => Some(None),
+        (None, _) => None,
+        (Some(group), _) => Some(validate_redact_group(
+            patterns.as_ref().unwrap_or(&saved_patterns),
+            effective_type,
+            Some(group),
+        )?),
+    };
+
     // Build dynamic update query based on provided fields
     let mut updates: Vec<String> = Vec::new();
     let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
@@ -141,18 +356,41 @@ pub fn update_dlp_pattern(
         params.push(Box::new(pt.clone()));
     }
 
+    // Dictionary entries are stored in their own table; `patterns` stays empty
+    let mut dictionary_entries: Option<Vec<String>> = None;
     if let Some(ref p) = patterns {
-        if p.is_empty() {
-            return Err("At least one pattern is required".to_string());
+        if is_dictionary {
+            dictionary_entries = Some(dictionary::parse_entries(&p.join("\n")));
+            updates.push("patterns = '[]'".to_string());
+        } else {
+            if p.is_empty() {
+                return Err("At least one pattern is required".to_string());
+            }
+            let patterns_json = serde_json::to_string(p).map_err(|e| e.to_string())?;
+            updates.push("patterns = ?".to_string());
+            params.push(Box::new(patterns_json));
         }
-        let patterns_json = serde_json::to_string(p).map_err(|e| e.to_string())?;
-        updates.push("patterns = ?".to_string());
-        params.push(Box::new(patterns_json));
+    } else if is_dictionary && !was_dictionary {
+        updates.push("patterns = '[]'".to_string());
+    }
+
+    // Dictionary options - cleared when switching away from the "dictionary" type
+    if is_dictionary {
+        if let Some(case_sensitive) = dictionary_case_sensitive {
+            updates.push("dictionary_case_sensitive = ?".to_string());
+            params.push(Box::new(case_sensitive));
+        }
+        if let Some(whole_words) = dictionary_whole_words {
+            updates.push("dictionary_whole_words = ?".to_string());
+            params.push(Box::new(whole_words));
+        }
+    } else if was_dictionary {
+        updates.push("dictionary_case_sensitive = NULL".to_string());
+        updates.push("dictionary_whole_words = NULL".to_string());
     }
 
     // Handle negative_pattern_type - allow setting to null by passing empty string
-    if negative_pattern_type.is_some() {
-        let npt = negative_pattern_type.as_ref().unwrap();
+    if let Some(npt) = &negative_pattern_type {
         if npt.is_empty() {
             updates.push("negative_pattern_type = NULL".to_string());
         } else {
@@ -162,8 +400,7 @@ pub fn update_dlp_pattern(
     }
 
     // Handle negative_patterns - allow setting to null by passing empty array
-    if negative_patterns.is_some() {
-        let np = negative_patterns.as_ref().unwrap();
+    if let Some(np) = &negative_patterns {
         if np.is_empty() {
             updates.push("negative_patterns = NULL".to_string());
         } else {
@@ -173,11 +410,27 @@ pub fn update_dlp_pattern(
         }
     }
 
+    // Handle negative scope - allow resetting to "context" by passing empty string
+    if negative_scope.is_some() {
+        match validate_negative_scope(negative_scope)? {
+            Some(s) => {
+                updates.push("negative_scope = ?".to_string());
+                params.push(Box::new(s));
+            }
+            None => updates.push("negative_scope = NULL".to_string()),
+        }
+    }
+
     if let Some(e) = enabled {
         updates.push("enabled = ?".to_string());
         params.push(Box::new(e as i32));
     }
 
+    if let Some(s) = shadow {
+        updates.push("shadow = ?".to_string());
+        params.push(Box::new(s as i32));
+    }
+
     if let Some(mo) = min_occurrences {
         updates.push("min_occurrences = ?".to_string());
         params.push(Box::new(mo));
@@ -188,8 +441,105 @@ pub fn update_dlp_pattern(
         params.push(Box::new(muc));
     }
 
+    // Handle action - allow resetting to the global action by passing empty string
+    if action.is_some() {
+        match validate_pattern_action(action)? {
+            Some(a) => {
+                updates.push("action = ?".to_string());
+                params.push(Box::new(a));
+            }
+            None => updates.push("action = NULL".to_string()),
+        }
+    }
+
+    // Handle placeholder strategy - allow resetting to the default by passing empty string
+    if placeholder_strategy.is_some() {
+        match validate_placeholder_strategy(placeholder_strategy)? {
+            Some(s) => {
+                updates.push("placeholder_strategy = ?".to_string());
+                params.push(Box::new(s));
+            }
+            None => updates.push("placeholder_strategy = NULL".to_string()),
+        }
+    }
+
+    // Entropy settings - allow resetting to the defaults by passing 0
+    if let Some(threshold) = entropy_threshold {
+        if threshold > 0.0 {
+            updates.push("entropy_threshold = ?".to_string());
+            params.push(Box::new(threshold));
+        } else {
+            updates.push("entropy_threshold = NULL".to_string());
+        }
+    }
+
+    if let Some(min_length) = entropy_min_length {
+        if min_length > 0 {
+            updates.push("entropy_min_length = ?".to_string());
+            params.push(Box::new(min_length));
+        } else {
+            updates.push("entropy_min_length = NULL".to_string());
+        }
+    }
+
+    match validator_update {
+        Some(Some(v)) => {
+            updates.push("validator = ?".to_string());
+            params.push(Box::new(v));
+        }
+        Some(None) => updates.push("validator = NULL".to_string()),
+        None => {}
+    }
+
+    match redact_group_update {
+        Some(Some(g)) => {
+            updates.push("redact_group = ?".to_string());
+            params.push(Box::new(g));
+        }
+        Some(None) => updates.push("redact_group = NULL".to_string()),
+        None => {}
+    }
+
+    // Context window - allow resetting to the default by passing 0
+    if context_window.is_some() {
+        match validate_context_window(context_window)? {
+            Some(w) => {
+                updates.push("context_window = ?".to_string());
+                params.push(Box::new(w));
+            }
+            None => updates.push("context_window = NULL".to_string()),
+        }
+    }
+
+    // Regression corpus - allow clearing a side by passing an empty array
+    if let Some(ref samples) = must_match {
+        updates.push("must_match = ?".to_string());
+        params.push(Box::new(corpus_json(Some(samples))?));
+    }
+
+    if let Some(ref samples) = must_not_match {
+        updates.push("must_not_match = ?".to_string());
+        params.push(Box::new(corpus_json(Some(samples))?));
+    }
+
+    // Required context - allow clearing it by passing an empty array
+    if required_context.is_some() {
+        match validate_required_context(required_context.as_ref(), required_context_type.as_deref())? {
+            Some(json) => {
+                updates.push("required_context = ?".to_string());
+                params.push(Box::new(json));
+                updates.push("required_context_type = ?".to_string());
+                params.push(Box::new(required_context_type.unwrap_or_else(|| "keyword".to_string())));
+            }
+            None => {
+                updates.push("required_context = NULL".to_string());
+                updates.push("required_context_type = NULL".to_string());
+            }
+        }
+    }
+
     if updates.is_empty() {
-        return Ok(()); // Nothing to update
+        return Ok(DlpPatternSaved { id, corpus_warning: None }); // Nothing to update
     }
 
     params.push(Box::new(id));
@@ -201,12 +551,141 @@ pub fn update_dlp_pattern(
 
     let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
 
-    conn.execute(&sql, params_refs.as_slice())
+    // Saved only if the pattern still passes its regression corpus
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(&sql, params_refs.as_slice())
         .map_err(|e| e.to_string())?;
+    Database::refresh_builtin_modified(&tx, id).map_err(|e| e.to_string())?;
+
+    if let Some(entries) = dictionary_entries {
+        dictionary::replace_entries(&tx, id, &entries).map_err(|e| e.to_string())?;
+    } else if was_dictionary && !is_dictionary {
+        dictionary::delete_entries(&tx, id).map_err(|e| e.to_string())?;
+    }
+
+    let corpus_warning = check_corpus(&tx, id, allow_corpus_failures)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    crate::DLP_PATTERNS.reload();
+
+    Ok(DlpPatternSaved { id, corpus_warning })
+}
+
+/// Restore an edited builtin pattern to its stock definition
+#[tauri::command]
+pub fn reset_builtin_dlp_pattern(id: i64) -> Result<(), String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+
+    if !Database::reset_builtin_pattern(&conn, id).map_err(|e| e.to_string())? {
+        return Err("Only builtin patterns can be reset".to_string());
+    }
+
+    crate::DLP_PATTERNS.reload();
 
     Ok(())
 }
 
+/// Entropy settings from command arguments; 0 (or less) means "use the default"
+fn entropy_options(threshold: Option<f64>, min_length: Option<i32>) -> EntropyOptions {
+    EntropyOptions {
+        threshold: threshold.filter(|t| *t > 0.0),
+        min_length: min_length.filter(|l| *l > 0),
+    }
+}
+
+/// Dictionary options from command arguments; None means "use the default"
+fn dictionary_options(case_sensitive: Option<bool>, whole_words: Option<bool>) -> DictionaryOptions {
+    let defaults = DictionaryOptions::default();
+    DictionaryOptions {
+        case_sensitive: case_sensitive.unwrap_or(defaults.case_sensitive),
+        whole_words: whole_words.unwrap_or(defaults.whole_words),
+    }
+}
+
+/// Validate a pattern action; empty means "use the global action"
+fn validate_pattern_action(action: Option<String>) -> Result<Option<String>, String> {
+    match action.as_deref().map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(a) => DlpPatternAction::parse(a)
+            .map(|a| Some(a.as_str().to_string()))
+            .ok_or_else(|| format!("Invalid action '{}'. Must be 'block', 'redact', 'notify' or 'log'", a)),
+    }
+}
+
+/// Validate a redact group against the patterns; empty means "the whole match"
+fn validate_redact_group(patterns: &[String], pattern_type: &str, group: Option<String>) -> Result<Option<String>, String> {
+    let Some(group) = group.map(|g| g.trim().to_string()).filter(|g| !g.is_empty()) else {
+        return Ok(None);
+    };
+    let regexes = compile_patterns(patterns, pattern_type)?;
+    resolve_redact_group(&regexes, pattern_type, Some(&group))?;
+    Ok(Some(group))
+}
+
+/// Validate a negative scope; empty or "context" means "the context window"
+fn validate_negative_scope(scope: Option<String>) -> Result<Option<&'static str>, String> {
+    match scope.as_deref().map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(s) => match NegativeScope::parse(s) {
+            Some(NegativeScope::Context) => Ok(None),
+            Some(scope) => Ok(Some(scope.as_str())),
+            None => Err(format!("Unknown negative scope '{}'. Use 'context' or 'value'", s)),
+        },
+    }
+}
+
+/// Validate a context window; 0 (or less) means "use the default"
+fn validate_context_window(window: Option<i32>) -> Result<Option<i32>, String> {
+    let window = window.filter(|w| *w > 0);
+    resolve_context_window(window)?;
+    Ok(window)
+}
+
+/// Validate required context patterns and return them as JSON (None = no requirement)
+fn validate_required_context(required: Option<&Vec<String>>, required_type: Option<&str>) -> Result<Option<String>, String> {
+    let Some(required) = required.filter(|r| r.iter().any(|p| !p.trim().is_empty())) else {
+        return Ok(None);
+    };
+    compile_patterns(required, required_type.unwrap_or("keyword"))?;
+    serde_json::to_string(required).map(Some).map_err(|e| e.to_string())
+}
+
+/// Validate a placeholder strategy; empty means "format-preserving"
+fn validate_placeholder_strategy(strategy: Option<String>) -> Result<Option<String>, String> {
+    match strategy.as_deref().map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(s) => PlaceholderStrategy::parse(s)
+            .map(|s| Some(s.as_str().to_string()))
+            .ok_or_else(|| format!("Invalid placeholder strategy '{}'. Must be 'format', 'token' or 'hash'", s)),
+    }
+}
+
+/// Regression corpus samples as stored (None = no samples)
+fn corpus_json(samples: Option<&Vec<String>>) -> Result<Option<String>, String> {
+    let samples: Vec<&String> = samples.into_iter().flatten().filter(|s| !s.trim().is_empty()).collect();
+    if samples.is_empty() {
+        return Ok(None);
+    }
+    serde_json::to_string(&samples).map(Some).map_err(|e| e.to_string())
+}
+
+/// Run a saved pattern's regression corpus; failures reject the save unless
+/// allowed, in which case they are returned as a warning. A definition that does
+/// not compile is always rejected
+fn check_corpus(conn: &rusqlite::Connection, id: i64, allow_failures: Option<bool>) -> Result<Option<String>, String> {
+    let result = pattern_corpus::check_pattern(conn, id)?;
+    if let Some(error) = &result.error {
+        return Err(format!("Pattern '{}' does not compile: {}", result.pattern_name, error));
+    }
+    if result.samples == 0 || result.passed {
+        return Ok(None);
+    }
+    if allow_failures.unwrap_or(false) {
+        Ok(Some(result.summary()))
+    } else {
+        Err(result.summary())
+    }
+}
+
 #[tauri::command]
 pub fn toggle_dlp_pattern(id: i64, enabled: bool) -> Result<(), String> {
     let conn = open_connection().map_err(|e| e.to_string())?;
@@ -217,6 +696,8 @@ pub fn toggle_dlp_pattern(id: i64, enabled: bool) -> Result<(), String> {
     )
     .map_err(|e| e.to_string())?;
 
+    crate::DLP_PATTERNS.reload();
+
     Ok(())
 }
 
@@ -224,24 +705,238 @@ pub fn toggle_dlp_pattern(id: i64, enabled: bool) -> Result<(), String> {
 pub fn delete_dlp_pattern(id: i64) -> Result<(), String> {
     let conn = open_connection().map_err(|e| e.to_string())?;
 
-    // Prevent deleting builtin patterns
-    let is_builtin: bool = conn
+    // Prevent deleting builtin and imported patterns
+    let (is_builtin, is_imported): (bool, bool) = conn
         .query_row(
-            "SELECT is_builtin FROM dlp_patterns WHERE id = ?1",
+            "SELECT is_builtin, import_id IS NOT NULL FROM dlp_patterns WHERE id = ?1",
             rusqlite::params![id],
-            |row| row.get::<_, i32>(0).map(|v| v == 1),
+            |row| Ok((row.get::<_, i32>(0)? == 1, row.get(1)?)),
         )
-        .unwrap_or(false);
+        .unwrap_or((false, false));
 
     if is_builtin {
         return Err("Cannot delete builtin patterns. You can disable them instead.".to_string());
     }
+    if is_imported {
+        return Err("Imported patterns are removed with their rule import. You can disable them instead.".to_string());
+    }
 
     conn.execute(
         "DELETE FROM dlp_patterns WHERE id = ?1",
         rusqlite::params![id],
     )
     .map_err(|e| e.to_string())?;
+    dictionary::delete_entries(&conn, id).map_err(|e| e.to_string())?;
+
+    crate::DLP_PATTERNS.reload();
+
+    Ok(())
+}
+
+// ============================================================================
+// Dictionaries
+// ============================================================================
+
+/// Add the entries of a list file (one per line) to a dictionary pattern,
+/// or replace its entries with them
+#[tauri::command]
+pub fn import_dlp_dictionary(id: i64, path: String, replace: bool) -> Result<DictionaryDiff, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    ensure_dictionary_pattern(&conn, id)?;
+
+    let diff = dictionary::import_file(&conn, id, path.trim(), replace)?;
+    crate::DLP_PATTERNS.reload();
+
+    Ok(diff)
+}
+
+/// Preview what replacing a dictionary with a list file would change
+#[tauri::command]
+pub fn diff_dlp_dictionary(id: i64, path: String) -> Result<DictionaryDiff, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    ensure_dictionary_pattern(&conn, id)?;
+
+    let current = dictionary::load_entries(&conn, id).map_err(|e| e.to_string())?;
+    let new_entries = dictionary::read_entries_file(path.trim())?;
+    Ok(dictionary::diff_entries(&current, &new_entries))
+}
+
+fn ensure_dictionary_pattern(conn: &rusqlite::Connection, id: i64) -> Result<(), String> {
+    let pattern_type: String = conn
+        .query_row(
+            "SELECT pattern_type FROM dlp_patterns WHERE id = ?1",
+            rusqlite::params![id],
+            |row| row.get(0),
+        )
+        .map_err(|_| "Pattern not found".to_string())?;
+    if pattern_type != "dictionary" {
+        return Err("Only dictionary patterns have a word list".to_string());
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Rule Imports (gitleaks / trufflehog)
+// ============================================================================
+
+#[derive(Serialize)]
+pub struct DlpRuleImport {
+    pub id: i64,
+    pub path: String,
+    pub format: String,
+    pub rule_count: i64,
+    pub skipped: Vec<SkippedRule>,
+    pub synced_at: Option<String>,
+}
+
+/// Import a gitleaks (.toml) or trufflehog (.yaml) rule file.
+/// Importing the same path again re-syncs it
+#[tauri::command]
+pub fn import_dlp_rules(path: String, format: Option<String>) -> Result<RuleImportResult, String> {
+    let format = match format.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(f) => Some(
+            RuleFormat::parse(f)
+                .ok_or_else(|| format!("Invalid format '{}'. Must be 'gitleaks' or 'trufflehog'", f))?,
+        ),
+    };
+
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    let result = rule_import::import_rule_file(&conn, path.trim(), format)?;
+
+    crate::DLP_PATTERNS.reload();
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_dlp_rule_imports() -> Result<Vec<DlpRuleImport>, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare("SELECT id, path, format, rule_count, skipped, synced_at FROM dlp_rule_imports ORDER BY id")
+        .map_err(|e| e.to_string())?;
+
+    let imports = stmt
+        .query_map([], |row| {
+            let skipped_json: Option<String> = row.get(4)?;
+            Ok(DlpRuleImport {
+                id: row.get(0)?,
+                path: row.get(1)?,
+                format: row.get(2)?,
+                rule_count: row.get(3)?,
+                skipped: skipped_json
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+                synced_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(imports)
+}
+
+/// Re-read an imported rule file now
+#[tauri::command]
+pub fn resync_dlp_rule_import(id: i64) -> Result<RuleImportResult, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    let result = rule_import::sync_rule_import(&conn, id, true)?
+        .ok_or_else(|| "Import not found".to_string())?;
+
+    crate::DLP_PATTERNS.reload();
+
+    Ok(result)
+}
+
+/// Remove a rule import together with its patterns
+#[tauri::command]
+pub fn delete_dlp_rule_import(id: i64) -> Result<(), String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    rule_import::delete_rule_import(&conn, id).map_err(|e| e.to_string())?;
+
+    crate::DLP_PATTERNS.reload();
+
+    Ok(())
+}
+
+// ============================================================================
+// Known Secrets (.env / credential files)
+// ============================================================================
+
+#[derive(Serialize)]
+pub struct DlpKnownSecretSource {
+    pub id: i64,
+    pub path: String,
+    pub file_count: i64,
+    pub value_count: i64,
+    pub error: Option<String>,
+    pub synced_at: Option<String>,
+}
+
+/// Add a file or glob of known secrets. Adding the same path again re-reads it
+#[tauri::command]
+pub fn add_dlp_known_secret_source(path: String) -> Result<KnownSecretSyncResult, String> {
+    if path.trim().is_empty() {
+        return Err("Path is required".to_string());
+    }
+
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    let result = known_secrets::add_source(&conn, &path)?;
+
+    crate::DLP_PATTERNS.reload();
+
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_dlp_known_secret_sources() -> Result<Vec<DlpKnownSecretSource>, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, path, file_count, value_count, error, synced_at
+             FROM dlp_known_secret_sources ORDER BY id",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let sources = stmt
+        .query_map([], |row| {
+            Ok(DlpKnownSecretSource {
+                id: row.get(0)?,
+                path: row.get(1)?,
+                file_count: row.get(2)?,
+                value_count: row.get(3)?,
+                error: row.get(4)?,
+                synced_at: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(sources)
+}
+
+/// Re-read a known secret source now
+#[tauri::command]
+pub fn resync_dlp_known_secret_source(id: i64) -> Result<KnownSecretSyncResult, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    let result = known_secrets::sync_source(&conn, id)?;
+
+    crate::DLP_PATTERNS.reload();
+
+    Ok(result)
+}
+
+/// Remove a known secret source and its hashes
+#[tauri::command]
+pub fn delete_dlp_known_secret_source(id: i64) -> Result<(), String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    known_secrets::delete_source(&conn, id).map_err(|e| e.to_string())?;
+
+    crate::DLP_PATTERNS.reload();
 
     Ok(())
 }
@@ -256,12 +951,49 @@ pub struct DlpDetectionRecord {
     original_value: String,
     placeholder: String,
     message_index: Option<i32>,
+    value_fingerprint: Option<String>,
+    value_storage: String,
+    action: Option<String>,
+    placeholder_strategy: Option<String>,
+    /// Decoded or normalized view the value was found in (None = the literal text)
+    found_in: Option<String>,
+    /// Found by a shadow pattern (`action` is what it would have done)
+    shadow: bool,
+    /// Exception that let the value through (`action` was not applied)
+    exception_id: Option<i64>,
+}
+
+/// Columns read by `detection_record` (detections table aliased `d`)
+const DETECTION_RECORD_COLUMNS: &str = "d.id, d.request_id, d.timestamp, d.pattern_name, d.pattern_type, d.original_value,
+    d.placeholder, d.message_index, d.value_fingerprint, COALESCE(d.value_storage, 'plaintext'), d.action,
+    d.placeholder_strategy, d.found_in, COALESCE(d.shadow, 0), d.exception_id";
+
+fn detection_record(row: &rusqlite::Row) -> rusqlite::Result<DlpDetectionRecord> {
+    Ok(DlpDetectionRecord {
+        id: row.get(0)?,
+        request_id: row.get(1)?,
+        timestamp: row.get(2)?,
+        pattern_name: row.get(3)?,
+        pattern_type: row.get(4)?,
+        original_value: row.get(5)?,
+        placeholder: row.get(6)?,
+        message_index: row.get(7)?,
+        value_fingerprint: row.get(8)?,
+        value_storage: row.get(9)?,
+        action: row.get(10)?,
+        placeholder_strategy: row.get(11)?,
+        found_in: row.get(12)?,
+        shadow: row.get::<_, i32>(13)? == 1,
+        exception_id: row.get(14)?,
+    })
 }
 
 #[derive(Serialize)]
 pub struct DlpStats {
     total_detections: i64,
+    unique_values: i64,
     detections_by_pattern: Vec<PatternCount>,
+    top_values: Vec<ValueCount>,
     recent_detections: Vec<DlpDetectionRecord>,
 }
 
@@ -269,6 +1001,39 @@ pub struct DlpStats {
 pub struct PatternCount {
     pattern_name: String,
     count: i64,
+    unique_values: i64,
+}
+
+/// Detections of the same value, grouped by fingerprint ("same key leaked 14 times")
+#[derive(Serialize)]
+pub struct ValueCount {
+    value_fingerprint: String,
+    pattern_name: String,
+    display_value: String,
+    count: i64,
+    request_count: i64,
+    first_seen: String,
+    last_seen: String,
+}
+
+/// Start of a stats time range ("1h", "6h", "1d" or "7d"; default one day)
+fn stats_cutoff(time_range: &str) -> String {
+    let hours = match time_range {
+        "1h" => 1,
+        "6h" => 6,
+        "1d" => 24,
+        "7d" => 24 * 7,
+        _ => 24,
+    };
+    (chrono::Utc::now() - chrono::Duration::hours(hours)).to_rfc3339()
+}
+
+/// Filter on the joined `requests r`; ?2 is the backend from `stats_backend`
+const STATS_BACKEND_FILTER: &str = " AND (?2 IS NULL OR r.backend = ?2)";
+
+/// Backend bound to `STATS_BACKEND_FILTER` ("all" = no filter)
+fn stats_backend(backend: &str) -> Option<&str> {
+    (backend != "all").then_some(backend)
 }
 
 #[tauri::command]
@@ -286,27 +1051,26 @@ pub fn get_dlp_detection_stats(time_range: String, backend: String) -> Result<Dl
             original_value TEXT NOT NULL,
             placeholder TEXT NOT NULL,
             message_index INTEGER,
+            value_fingerprint TEXT,
+            value_storage TEXT DEFAULT 'plaintext',
+            action TEXT,
+            placeholder_strategy TEXT,
+            found_in TEXT,
+            shadow INTEGER DEFAULT 0,
+            exception_id INTEGER,
             FOREIGN KEY (request_id) REFERENCES requests(id)
         )",
         [],
     );
 
-    let hours = match time_range.as_str() {
-        "1h" => 1,
-        "6h" => 6,
-        "1d" => 24,
-        "7d" => 24 * 7,
-        _ => 24,
-    };
-    let cutoff = chrono::Utc::now() - chrono::Duration::hours(hours);
-    let cutoff_ts = cutoff.to_rfc3339();
+    let cutoff_ts = stats_cutoff(&time_range);
+    let params = rusqlite::params![cutoff_ts, stats_backend(&backend)];
 
-    // Build backend filter
-    let backend_filter = if backend == "all" {
-        String::new()
-    } else {
-        format!(" AND r.backend = '{}'", backend)
-    };
+    // Build backend filter; shadow detections are reported separately, excepted ones not at all
+    let backend_filter = format!(
+        " AND COALESCE(d.shadow, 0) = 0 AND d.exception_id IS NULL{}",
+        STATS_BACKEND_FILTER
+    );
 
     // Get total detections count (with backend filter)
     let total_detections: i64 = conn
@@ -317,7 +1081,21 @@ pub fn get_dlp_detection_stats(time_range: String, backend: String) -> Result<Dl
                  WHERE d.timestamp >= ?1{}",
                 backend_filter
             ),
-            [&cutoff_ts],
+            params,
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    // Get number of distinct detected values (with backend filter)
+    let unique_values: i64 = conn
+        .query_row(
+            &format!(
+                "SELECT COUNT(DISTINCT d.value_fingerprint) FROM dlp_detections d
+                 JOIN requests r ON d.request_id = r.id
+                 WHERE d.timestamp >= ?1{}",
+                backend_filter
+            ),
+            params,
             |row| row.get(0),
         )
         .unwrap_or(0);
@@ -325,7 +1103,7 @@ pub fn get_dlp_detection_stats(time_range: String, backend: String) -> Result<Dl
     // Get detections by pattern (with backend filter)
     let mut stmt = conn
         .prepare(&format!(
-            "SELECT d.pattern_name, COUNT(*) as count FROM dlp_detections d
+            "SELECT d.pattern_name, COUNT(*) as count, COUNT(DISTINCT d.value_fingerprint) FROM dlp_detections d
              JOIN requests r ON d.request_id = r.id
              WHERE d.timestamp >= ?1{} GROUP BY d.pattern_name ORDER BY count DESC",
             backend_filter
@@ -333,79 +1111,209 @@ pub fn get_dlp_detection_stats(time_range: String, backend: String) -> Result<Dl
         .map_err(|e| e.to_string())?;
 
     let detections_by_pattern: Vec<PatternCount> = stmt
-        .query_map([&cutoff_ts], |row| {
+        .query_map(params, |row| {
             Ok(PatternCount {
                 pattern_name: row.get(0)?,
                 count: row.get(1)?,
+                unique_values: row.get(2)?,
             })
         })
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
-    // Get recent detections (with backend filter)
+    // Get most frequently detected values by fingerprint (with backend filter)
     let mut stmt = conn
         .prepare(&format!(
-            "SELECT d.id, d.request_id, d.timestamp, d.pattern_name, d.pattern_type, d.original_value, d.placeholder, d.message_index
+            "SELECT d.value_fingerprint, MAX(d.pattern_name), MAX(d.original_value), COUNT(*) as count,
+                    COUNT(DISTINCT d.request_id), MIN(d.timestamp), MAX(d.timestamp)
              FROM dlp_detections d
              JOIN requests r ON d.request_id = r.id
-             WHERE d.timestamp >= ?1{} ORDER BY d.id DESC LIMIT 50",
+             WHERE d.timestamp >= ?1 AND d.value_fingerprint IS NOT NULL{}
+             GROUP BY d.value_fingerprint ORDER BY count DESC LIMIT 20",
             backend_filter
         ))
         .map_err(|e| e.to_string())?;
 
-    let recent_detections: Vec<DlpDetectionRecord> = stmt
-        .query_map([&cutoff_ts], |row| {
-            Ok(DlpDetectionRecord {
-                id: row.get(0)?,
-                request_id: row.get(1)?,
-                timestamp: row.get(2)?,
-                pattern_name: row.get(3)?,
-                pattern_type: row.get(4)?,
-                original_value: row.get(5)?,
-                placeholder: row.get(6)?,
-                message_index: row.get(7)?,
+    let top_values: Vec<ValueCount> = stmt
+        .query_map(params, |row| {
+            Ok(ValueCount {
+                value_fingerprint: row.get(0)?,
+                pattern_name: row.get(1)?,
+                display_value: row.get(2)?,
+                count: row.get(3)?,
+                request_count: row.get(4)?,
+                first_seen: row.get(5)?,
+                last_seen: row.get(6)?,
             })
         })
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
+    // Get recent detections (with backend filter)
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {}
+             FROM dlp_detections d
+             JOIN requests r ON d.request_id = r.id
+             WHERE d.timestamp >= ?1{} ORDER BY d.id DESC LIMIT 50",
+            DETECTION_RECORD_COLUMNS, backend_filter
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let recent_detections: Vec<DlpDetectionRecord> = stmt
+        .query_map(params, detection_record)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
     Ok(DlpStats {
         total_detections,
+        unique_values,
         detections_by_pattern,
+        top_values,
         recent_detections,
     })
 }
 
+// ============================================================================
+// Shadow Mode
+// ============================================================================
+
+/// Distinct values sampled per shadow pattern
+const SHADOW_SAMPLE_SIZE: usize = 5;
+
+/// How often shadow patterns would have fired
+#[derive(Serialize)]
+pub struct DlpShadowStats {
+    /// Requests logged in the time range (for the selected backend)
+    requests_total: i64,
+    patterns: Vec<ShadowPatternStats>,
+}
+
+#[derive(Serialize)]
+pub struct ShadowPatternStats {
+    pattern_name: String,
+    /// Still in shadow mode (false once the pattern is enforced or removed)
+    in_shadow: bool,
+    /// Most severe action the pattern would have taken
+    action: Option<String>,
+    count: i64,
+    unique_values: i64,
+    request_count: i64,
+    first_seen: String,
+    last_seen: String,
+    /// Most recent detections of distinct values
+    samples: Vec<DlpDetectionRecord>,
+}
+
+/// Shadow pattern detections per pattern, with sample matches
 #[tauri::command]
-pub fn get_dlp_detections_for_request(request_id: i64) -> Result<Vec<DlpDetectionRecord>, String> {
+pub fn get_dlp_shadow_stats(time_range: String, backend: String) -> Result<DlpShadowStats, String> {
     let conn = open_connection().map_err(|e| e.to_string())?;
+    let cutoff_ts = stats_cutoff(&time_range);
+    let backend = stats_backend(&backend);
 
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, request_id, timestamp, pattern_name, pattern_type, original_value, placeholder, message_index
-             FROM dlp_detections WHERE request_id = ?1 ORDER BY id ASC",
+    let requests_total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM requests r WHERE r.timestamp >= ?1{}", STATS_BACKEND_FILTER),
+            rusqlite::params![cutoff_ts, backend],
+            |row| row.get(0),
         )
+        .unwrap_or(0);
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT d.pattern_name,
+                    EXISTS (SELECT 1 FROM dlp_patterns p WHERE p.name = d.pattern_name AND p.shadow = 1),
+                    GROUP_CONCAT(DISTINCT d.action), COUNT(*) as count, COUNT(DISTINCT d.value_fingerprint), COUNT(DISTINCT d.request_id),
+                    MIN(d.timestamp), MAX(d.timestamp)
+             FROM dlp_detections d
+             JOIN requests r ON d.request_id = r.id
+             WHERE d.timestamp >= ?1 AND d.shadow = 1 AND d.exception_id IS NULL{}
+             GROUP BY d.pattern_name ORDER BY count DESC",
+            STATS_BACKEND_FILTER
+        ))
         .map_err(|e| e.to_string())?;
 
-    let detections: Vec<DlpDetectionRecord> = stmt
-        .query_map([request_id], |row| {
-            Ok(DlpDetectionRecord {
-                id: row.get(0)?,
-                request_id: row.get(1)?,
-                timestamp: row.get(2)?,
-                pattern_name: row.get(3)?,
-                pattern_type: row.get(4)?,
-                original_value: row.get(5)?,
-                placeholder: row.get(6)?,
-                message_index: row.get(7)?,
+    let mut patterns: Vec<ShadowPatternStats> = stmt
+        .query_map(rusqlite::params![cutoff_ts, backend], |row| {
+            let actions: Option<String> = row.get(2)?;
+            Ok(ShadowPatternStats {
+                pattern_name: row.get(0)?,
+                in_shadow: row.get::<_, i32>(1)? == 1,
+                action: actions
+                    .as_deref()
+                    .and_then(|a| a.split(',').filter_map(DlpPatternAction::parse).max())
+                    .map(|a| a.as_str().to_string()),
+                count: row.get(3)?,
+                unique_values: row.get(4)?,
+                request_count: row.get(5)?,
+                first_seen: row.get(6)?,
+                last_seen: row.get(7)?,
+                samples: Vec::new(),
             })
         })
         .map_err(|e| e.to_string())?
         .filter_map(|r| r.ok())
         .collect();
 
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {}
+             FROM dlp_detections d
+             JOIN requests r ON d.request_id = r.id
+             WHERE d.timestamp >= ?1 AND d.shadow = 1 AND d.exception_id IS NULL AND d.pattern_name = ?3{}
+             ORDER BY d.id DESC LIMIT 100",
+            DETECTION_RECORD_COLUMNS, STATS_BACKEND_FILTER
+        ))
+        .map_err(|e| e.to_string())?;
+
+    for pattern in &mut patterns {
+        let recent: Vec<DlpDetectionRecord> = stmt
+            .query_map(rusqlite::params![cutoff_ts, backend, pattern.pattern_name], detection_record)
+            .map_err(|e| e.to_string())?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut seen: Vec<String> = Vec::new();
+        for record in recent {
+            let key = record.value_fingerprint.clone().unwrap_or_else(|| record.original_value.clone());
+            if seen.contains(&key) {
+                continue;
+            }
+            seen.push(key);
+            pattern.samples.push(record);
+            if pattern.samples.len() == SHADOW_SAMPLE_SIZE {
+                break;
+            }
+        }
+    }
+
+    Ok(DlpShadowStats {
+        requests_total,
+        patterns,
+    })
+}
+
+#[tauri::command]
+pub fn get_dlp_detections_for_request(request_id: i64) -> Result<Vec<DlpDetectionRecord>, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM dlp_detections d WHERE d.request_id = ?1 ORDER BY d.id ASC",
+            DETECTION_RECORD_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let detections: Vec<DlpDetectionRecord> = stmt
+        .query_map([request_id], detection_record)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
     Ok(detections)
 }
 
@@ -416,51 +1324,585 @@ pub fn get_dlp_action_setting() -> String {
 
 #[tauri::command]
 pub fn save_dlp_action_setting(action: String) -> Result<(), String> {
-    save_dlp_action_to_db(&action)
+    save_dlp_action_to_db(&action)?;
+    // Patterns without their own action pick up the new default
+    crate::DLP_PATTERNS.reload();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_detection_storage_mode() -> String {
+    get_storage_mode().as_str().to_string()
+}
+
+/// Save how detected values are stored and apply it to existing detections
+/// Returns the number of stored detections that were updated
+#[tauri::command]
+pub fn save_detection_storage_mode(mode: String) -> Result<usize, String> {
+    let mode = DetectionStorageMode::parse(&mode)
+        .ok_or_else(|| format!("Invalid storage mode '{}'", mode))?;
+    save_storage_mode(mode)?;
+
+    let db = Database::new(get_db_path()).map_err(|e| e.to_string())?;
+    db.reencode_stored_detections().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_dlp_vault_ttl_hours() -> i64 {
+    crate::REDACTION_VAULT.ttl_hours()
+}
+
+/// Save how long a session keeps its placeholders after its last request
+#[tauri::command]
+pub fn save_dlp_vault_ttl_hours(hours: i64) -> Result<(), String> {
+    crate::REDACTION_VAULT.set_ttl_hours(hours)?;
+    crate::REDACTION_VAULT.purge_expired();
+    Ok(())
+}
+
+/// Forget all stored placeholders; sessions get new ones from their next request
+#[tauri::command]
+pub fn clear_dlp_vault() -> Result<(), String> {
+    crate::REDACTION_VAULT.clear()
 }
 
 #[derive(Serialize)]
 pub struct TestPatternResult {
     pub matches: Vec<String>,
+    /// Each match with the span that would be replaced marked out
+    pub highlights: Vec<MatchHighlight>,
     pub excluded: bool,
+    /// Every candidate with the reason it was kept or dropped
+    pub candidates: Vec<CandidateOutcome>,
 }
 
 /// Test a pattern configuration against sample text without saving
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub fn test_dlp_pattern(
     pattern_type: String,
     patterns: Vec<String>,
     negative_pattern_type: Option<String>,
     negative_patterns: Option<Vec<String>>,
+    negative_scope: Option<String>,
     min_occurrences: i32,
     min_unique_chars: i32,
     test_text: String,
+    entropy_threshold: Option<f64>,
+    entropy_min_length: Option<i32>,
+    validator: Option<String>,
+    redact_group: Option<String>,
+    context_window: Option<i32>,
+    required_context: Option<Vec<String>>,
+    required_context_type: Option<String>,
+    dictionary_case_sensitive: Option<bool>,
+    dictionary_whole_words: Option<bool>,
+    pattern_id: Option<i64>,
 ) -> Result<TestPatternResult, String> {
+    // A saved dictionary is tested with its stored entries unless new ones are given
+    let dictionary_entries = if pattern_type != "dictionary" {
+        Vec::new()
+    } else if let (Some(id), true) = (pattern_id, patterns.is_empty()) {
+        let conn = open_connection().map_err(|e| e.to_string())?;
+        dictionary::load_entries(&conn, id).map_err(|e| e.to_string())?
+    } else {
+        dictionary::parse_entries(&patterns.join("\n"))
+    };
+
     // Compile patterns using shared utility
     let compiled = compile_pattern_set(
         &patterns,
         &pattern_type,
         negative_patterns.as_ref(),
         negative_pattern_type.as_deref(),
+        &entropy_options(entropy_threshold, entropy_min_length),
+        validator.as_deref(),
+    )?
+    .with_negative_scope(negative_scope.as_deref())?
+    .with_redact_group(&pattern_type, redact_group.as_deref())?
+    .with_context(
+        context_window.filter(|w| *w > 0),
+        required_context.as_ref().filter(|r| !r.is_empty()),
+        required_context_type.as_deref(),
+    )?
+    .with_dictionary(
+        &pattern_type,
+        &dictionary_entries,
+        dictionary_options(dictionary_case_sensitive, dictionary_whole_words),
     )?;
 
-    // Collect matches with context-aware negative pattern filtering
-    // Each match is checked against negative patterns within its 30-char context window
-    let match_result = collect_matches_with_negative_context(
-        &test_text,
-        &compiled.regexes,
-        &compiled.negative_regexes,
-        min_unique_chars,
-    );
+    // Collect matches with context-aware filtering
+    // Each match is checked against negative and required context within its context window
+    let match_result = collect_matches_with_context(&test_text, &compiled, min_unique_chars);
 
     // Filter by min_occurrences threshold
+    let highlights = match_result.highlights.clone();
+    let mut candidates = match_result.candidates.clone();
     let matches = filter_by_min_occurrences(match_result, min_occurrences);
+    let highlights = if matches.is_empty() { Vec::new() } else { highlights };
+    if matches.is_empty() {
+        for candidate in candidates.iter_mut().filter(|c| c.kept) {
+            candidate.kept = false;
+            candidate.reason = format!("fewer than {} distinct matches", min_occurrences);
+        }
+    }
 
-    // If all matches were excluded by negative patterns, indicate exclusion
-    let excluded = matches.is_empty() && !compiled.negative_regexes.is_empty();
+    // If all matches were excluded by context rules, indicate exclusion
+    let excluded = matches.is_empty()
+        && (!compiled.negative_regexes.is_empty() || !compiled.required_regexes.is_empty());
 
     Ok(TestPatternResult {
         matches,
+        highlights,
         excluded,
+        candidates,
+    })
+}
+
+/// Run the regression corpus of every pattern, builtins and disabled ones included
+#[tauri::command]
+pub fn run_dlp_pattern_corpora() -> Result<CorpusReport, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    pattern_corpus::check_all(&conn)
+}
+
+// ============================================================================
+// Exceptions (false positives)
+// ============================================================================
+
+/// A stored exception with how often it has applied
+#[derive(Serialize)]
+pub struct DlpException {
+    pub id: i64,
+    /// Pattern it applies to (None = any pattern)
+    pub pattern_name: Option<String>,
+    /// Fingerprint of the excepted value (the value itself is not kept)
+    pub value_hash: Option<String>,
+    /// The excepted value as stored with its detections (per storage mode)
+    pub value_display: Option<String>,
+    pub value_regex: Option<String>,
+    pub file_path: Option<String>,
+    pub backend: Option<String>,
+    pub expires_at: Option<String>,
+    pub reason: Option<String>,
+    /// Detection the exception was created from
+    pub detection_id: Option<i64>,
+    pub created_at: String,
+    pub revoked_at: Option<String>,
+    /// "active", "expired" or "revoked"
+    pub status: String,
+    /// Detections let through by this exception
+    pub hit_count: i64,
+    pub last_hit: Option<String>,
+}
+
+fn exception_status(expires_at: Option<&str>, revoked_at: Option<&str>) -> String {
+    let expired = expires_at
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .is_some_and(|t| t <= chrono::Utc::now());
+    match (revoked_at, expired) {
+        (Some(_), _) => "revoked",
+        (None, true) => "expired",
+        (None, false) => "active",
+    }
+    .to_string()
+}
+
+/// Store an exception and publish it to the pattern registry; returns its id
+fn insert_exception(
+    conn: &rusqlite::Connection,
+    rule: &DlpExceptionRule,
+    reason: Option<String>,
+    detection_id: Option<i64>,
+) -> Result<i64, String> {
+    rule.validate()?;
+    conn.execute(
+        "INSERT INTO dlp_exceptions (pattern_name, value_hash, value_regex, file_path, backend, expires_at, reason, detection_id, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![
+            rule.pattern_name,
+            rule.value_hash,
+            rule.value_regex,
+            rule.file_path,
+            rule.backend,
+            rule.expires_at,
+            reason.filter(|r| !r.trim().is_empty()),
+            detection_id,
+            chrono::Utc::now().to_rfc3339(),
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    let id = conn.last_insert_rowid();
+
+    crate::DLP_PATTERNS.reload();
+
+    Ok(id)
+}
+
+/// Mark a detection (a row of `get_dlp_detections_for_request`) as a false positive
+/// The exception covers the detected value for the same pattern; `any_pattern`,
+/// `match_backend` and `match_file` widen or narrow it
+#[tauri::command]
+pub fn create_dlp_exception_from_detection(
+    detection_id: i64,
+    any_pattern: Option<bool>,
+    match_backend: Option<bool>,
+    match_file: Option<bool>,
+    expires_at: Option<String>,
+    reason: Option<String>,
+) -> Result<i64, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+
+    let (pattern_name, pattern_type, fingerprint, backend, file_path): (
+        String,
+        String,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    ) = conn
+        .query_row(
+            "SELECT d.pattern_name, d.pattern_type, d.value_fingerprint, r.backend, json_extract(r.extra_metadata, '$.file_path')
+             FROM dlp_detections d LEFT JOIN requests r ON r.id = d.request_id
+             WHERE d.id = ?1",
+            [detection_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+        )
+        .map_err(|_| "Detection not found".to_string())?;
+
+    if pattern_type == KNOWN_SECRET_TYPE {
+        return Err("Known secrets cannot be excepted; remove the value from its source file instead".to_string());
+    }
+    let fingerprint = fingerprint.ok_or_else(|| "Detection has no value fingerprint".to_string())?;
+
+    let rule = DlpExceptionRule {
+        pattern_name: (!any_pattern.unwrap_or(false)).then_some(pattern_name),
+        value_hash: Some(fingerprint),
+        value_regex: None,
+        file_path: match match_file.unwrap_or(false) {
+            true => Some(file_path.ok_or_else(|| "The request was not a file read".to_string())?),
+            false => None,
+        },
+        backend: match match_backend.unwrap_or(false) {
+            true => Some(backend.ok_or_else(|| "The request of this detection no longer exists".to_string())?),
+            false => None,
+        },
+        expires_at: expires_at.filter(|e| !e.trim().is_empty()).map(|e| parse_expiry(&e)).transpose()?,
+    };
+    insert_exception(&conn, &rule, reason, Some(detection_id))
+}
+
+/// Add an exception by hand; at least one of `value`, `value_regex` and `file_path` is required
+/// `value` is stored only as its fingerprint
+#[tauri::command]
+pub fn add_dlp_exception(
+    pattern_name: Option<String>,
+    value: Option<String>,
+    value_regex: Option<String>,
+    file_path: Option<String>,
+    backend: Option<String>,
+    expires_at: Option<String>,
+    reason: Option<String>,
+) -> Result<i64, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    let non_empty = |s: Option<String>| s.filter(|s| !s.trim().is_empty());
+
+    let value_hash = match non_empty(value) {
+        Some(value) => Some(
+            DetectionValueEncoder::load_with(&conn)
+                .map_err(|e| e.to_string())?
+                .fingerprint(&value),
+        ),
+        None => None,
+    };
+    let rule = DlpExceptionRule {
+        pattern_name: non_empty(pattern_name),
+        value_hash,
+        value_regex: non_empty(value_regex),
+        file_path: non_empty(file_path),
+        backend: non_empty(backend),
+        expires_at: non_empty(expires_at).map(|e| parse_expiry(&e)).transpose()?,
+    };
+    insert_exception(&conn, &rule, reason, None)
+}
+
+/// List exceptions, newest first; revoked ones only if asked for
+#[tauri::command]
+pub fn get_dlp_exceptions(include_revoked: Option<bool>) -> Result<Vec<DlpException>, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT e.id, e.pattern_name, e.value_hash,
+                    CASE WHEN e.value_hash IS NOT NULL THEN
+                        (SELECT d.original_value FROM dlp_detections d
+                         WHERE d.value_fingerprint = e.value_hash AND d.original_value != ''
+                         ORDER BY d.id DESC LIMIT 1)
+                    END,
+                    e.value_regex, e.file_path, e.backend, e.expires_at, e.reason, e.detection_id, e.created_at, e.revoked_at,
+                    (SELECT COUNT(*) FROM dlp_detections d WHERE d.exception_id = e.id),
+                    (SELECT MAX(d.timestamp) FROM dlp_detections d WHERE d.exception_id = e.id)
+             FROM dlp_exceptions e{}
+             ORDER BY e.id DESC",
+            if include_revoked.unwrap_or(false) { "" } else { " WHERE e.revoked_at IS NULL" }
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let exceptions = stmt
+        .query_map([], |row| {
+            let expires_at: Option<String> = row.get(7)?;
+            let revoked_at: Option<String> = row.get(11)?;
+            Ok(DlpException {
+                id: row.get(0)?,
+                pattern_name: row.get(1)?,
+                value_hash: row.get(2)?,
+                value_display: row.get(3)?,
+                value_regex: row.get(4)?,
+                file_path: row.get(5)?,
+                backend: row.get(6)?,
+                status: exception_status(expires_at.as_deref(), revoked_at.as_deref()),
+                expires_at,
+                reason: row.get(8)?,
+                detection_id: row.get(9)?,
+                created_at: row.get(10)?,
+                revoked_at,
+                hit_count: row.get(12)?,
+                last_hit: row.get(13)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(exceptions)
+}
+
+/// Revoke an exception; the row is kept for the audit trail
+#[tauri::command]
+pub fn revoke_dlp_exception(id: i64) -> Result<(), String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    let updated = conn
+        .execute(
+            "UPDATE dlp_exceptions SET revoked_at = ?1 WHERE id = ?2 AND revoked_at IS NULL",
+            rusqlite::params![chrono::Utc::now().to_rfc3339(), id],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err("Exception not found or already revoked".to_string());
+    }
+
+    crate::DLP_PATTERNS.reload();
+
+    Ok(())
+}
+
+// ============================================================================
+// Retroactive Scan
+// ============================================================================
+
+/// Detections listed per scan result (the per-pattern summary covers all of them)
+const RETRO_DETECTIONS_LIMIT: i64 = 200;
+
+/// Columns read by `read_retro_scan`
+const RETRO_SCAN_COLUMNS: &str = "id, started_at, finished_at, status, since, backend, pattern_ids,
+    requests_total, requests_scanned, detections, error";
+
+/// A history scan and how far it got
+#[derive(Serialize)]
+pub struct DlpRetroScan {
+    pub id: i64,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// "running", "completed", "cancelled", "failed" or "interrupted"
+    pub status: String,
+    pub since: String,
+    pub backend: Option<String>,
+    /// Candidate pattern ids (None = the pattern set at the time)
+    pub pattern_ids: Option<Vec<i64>>,
+    pub requests_total: i64,
+    pub requests_scanned: i64,
+    pub detections: i64,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DlpRetroDetection {
+    pub id: i64,
+    pub request_id: i64,
+    pub request_timestamp: String,
+    pub backend: Option<String>,
+    pub pattern_name: String,
+    pub pattern_type: String,
+    pub original_value: String,
+    pub action: Option<String>,
+    pub found_in: Option<String>,
+    pub shadow: bool,
+    pub exception_id: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct RetroPatternSummary {
+    pattern_name: String,
+    count: i64,
+    unique_values: i64,
+    request_count: i64,
+    first_seen: String,
+    last_seen: String,
+}
+
+#[derive(Serialize)]
+pub struct DlpRetroScanResults {
+    scan: DlpRetroScan,
+    patterns: Vec<RetroPatternSummary>,
+    /// Most recent requests first, at most `RETRO_DETECTIONS_LIMIT`
+    detections: Vec<DlpRetroDetection>,
+}
+
+fn read_retro_scan(row: &rusqlite::Row) -> rusqlite::Result<DlpRetroScan> {
+    let id: i64 = row.get(0)?;
+    let mut status: String = row.get(3)?;
+    // A scan still marked running that this process is not running was cut short
+    if retro_scan::RetroScanStatus::parse(&status) == Some(retro_scan::RetroScanStatus::Running)
+        && retro_scan::running_scan_id() != Some(id)
+    {
+        status = retro_scan::RetroScanStatus::Interrupted.as_str().to_string();
+    }
+    let pattern_ids: Option<String> = row.get(6)?;
+    Ok(DlpRetroScan {
+        id,
+        started_at: row.get(1)?,
+        finished_at: row.get(2)?,
+        status,
+        since: row.get(4)?,
+        backend: row.get(5)?,
+        pattern_ids: pattern_ids.and_then(|ids| serde_json::from_str(&ids).ok()),
+        requests_total: row.get(7)?,
+        requests_scanned: row.get(8)?,
+        detections: row.get(9)?,
+        error: row.get(10)?,
     })
 }
+
+/// Scan the stored requests of a time range ("1h", "6h", "1d" or "7d") in the
+/// background; progress comes as `dlp-retro-scan-progress` events
+/// `pattern_ids` picks candidate patterns (None = the current pattern set)
+#[tauri::command]
+pub fn start_dlp_retro_scan(
+    app: tauri::AppHandle,
+    time_range: String,
+    backend: Option<String>,
+    pattern_ids: Option<Vec<i64>>,
+) -> Result<i64, String> {
+    retro_scan::start(
+        app,
+        RetroScanOptions {
+            since: stats_cutoff(&time_range),
+            backend: backend.filter(|b| b != "all"),
+            pattern_ids,
+            batch_size: retro_scan::BATCH_SIZE,
+        },
+    )
+}
+
+/// Stop a running scan after its current batch
+#[tauri::command]
+pub fn cancel_dlp_retro_scan(scan_id: i64) -> Result<(), String> {
+    retro_scan::cancel(scan_id)
+}
+
+/// Recent history scans, newest first
+#[tauri::command]
+pub fn get_dlp_retro_scans() -> Result<Vec<DlpRetroScan>, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM dlp_retro_scans ORDER BY id DESC LIMIT 20",
+            RETRO_SCAN_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let scans = stmt
+        .query_map([], read_retro_scan)
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(scans)
+}
+
+/// What a history scan found, per pattern and by request
+#[tauri::command]
+pub fn get_dlp_retro_scan_results(scan_id: i64) -> Result<DlpRetroScanResults, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    let scan = conn
+        .query_row(
+            &format!("SELECT {} FROM dlp_retro_scans WHERE id = ?1", RETRO_SCAN_COLUMNS),
+            [scan_id],
+            read_retro_scan,
+        )
+        .map_err(|_| "Scan not found".to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT pattern_name, COUNT(*), COUNT(DISTINCT COALESCE(value_fingerprint, original_value)),
+                    COUNT(DISTINCT request_id), MIN(request_timestamp), MAX(request_timestamp)
+             FROM dlp_retro_detections WHERE scan_id = ?1
+             GROUP BY pattern_name ORDER BY COUNT(*) DESC",
+        )
+        .map_err(|e| e.to_string())?;
+    let patterns = stmt
+        .query_map([scan_id], |row| {
+            Ok(RetroPatternSummary {
+                pattern_name: row.get(0)?,
+                count: row.get(1)?,
+                unique_values: row.get(2)?,
+                request_count: row.get(3)?,
+                first_seen: row.get(4)?,
+                last_seen: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, request_id, request_timestamp, backend, pattern_name, pattern_type, original_value,
+                    action, found_in, shadow, exception_id
+             FROM dlp_retro_detections WHERE scan_id = ?1
+             ORDER BY request_id DESC, id LIMIT ?2",
+        )
+        .map_err(|e| e.to_string())?;
+    let detections = stmt
+        .query_map(rusqlite::params![scan_id, RETRO_DETECTIONS_LIMIT], |row| {
+            Ok(DlpRetroDetection {
+                id: row.get(0)?,
+                request_id: row.get(1)?,
+                request_timestamp: row.get(2)?,
+                backend: row.get(3)?,
+                pattern_name: row.get(4)?,
+                pattern_type: row.get(5)?,
+                original_value: row.get(6)?,
+                action: row.get(7)?,
+                found_in: row.get(8)?,
+                shadow: row.get::<_, i32>(9)? == 1,
+                exception_id: row.get(10)?,
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(DlpRetroScanResults { scan, patterns, detections })
+}
+
+/// Delete a finished scan and its detections
+#[tauri::command]
+pub fn delete_dlp_retro_scan(scan_id: i64) -> Result<(), String> {
+    if retro_scan::running_scan_id() == Some(scan_id) {
+        return Err("Cancel the scan before deleting it".to_string());
+    }
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM dlp_retro_detections WHERE scan_id = ?1", [scan_id])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM dlp_retro_scans WHERE id = ?1", [scan_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
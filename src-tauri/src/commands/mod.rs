@@ -3,10 +3,12 @@
 pub mod backends;
 pub mod cursor;
 pub mod dlp;
+pub mod privacy;
 pub mod stats;
 
 // Re-export all commands for convenience
 pub use backends::*;
 pub use cursor::*;
 pub use dlp::*;
+pub use privacy::*;
 pub use stats::*;
@@ -0,0 +1,36 @@
+// Privacy Settings Tauri Commands
+
+use crate::database::Database;
+use crate::dlp_pattern_config::get_db_path;
+use crate::header_scrub::{
+    get_custom_header_names, save_custom_header_names, HeaderScrubPolicy,
+    BUILTIN_SENSITIVE_HEADERS, BUILTIN_SENSITIVE_SUFFIXES,
+};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct HeaderScrubSettings {
+    pub builtin_names: Vec<String>,
+    pub builtin_suffixes: Vec<String>,
+    pub custom_names: Vec<String>,
+}
+
+#[tauri::command]
+pub fn get_header_scrub_settings() -> HeaderScrubSettings {
+    HeaderScrubSettings {
+        builtin_names: BUILTIN_SENSITIVE_HEADERS.iter().map(|s| s.to_string()).collect(),
+        builtin_suffixes: BUILTIN_SENSITIVE_SUFFIXES.iter().map(|s| s.to_string()).collect(),
+        custom_names: get_custom_header_names(),
+    }
+}
+
+/// Save user-added sensitive header names and scrub them from stored requests
+/// New requests use the updated list after the proxy restarts
+#[tauri::command]
+pub fn save_header_scrub_names(names: Vec<String>) -> Result<usize, String> {
+    save_custom_header_names(&names)?;
+
+    let db = Database::new(get_db_path()).map_err(|e| e.to_string())?;
+    db.scrub_stored_headers(&HeaderScrubPolicy::load())
+        .map_err(|e| e.to_string())
+}
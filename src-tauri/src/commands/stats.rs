@@ -1,6 +1,7 @@
 // Stats and Monitoring Tauri Commands
 
-use crate::database::{get_port_from_db, open_connection, save_port_to_db, DLP_ACTION_BLOCKED, DLP_ACTION_PASSED, DLP_ACTION_REDACTED, DLP_ACTION_RATELIMITED, DLP_ACTION_NOTIFY_RATELIMIT};
+use crate::database::{get_bind_address_from_db, get_or_create_access_token, get_port_from_db, get_require_token_from_db, open_connection, regenerate_access_token, save_bind_address_to_db, save_port_to_db, save_require_token_to_db, DLP_ACTION_BLOCKED, DLP_ACTION_PASSED, DLP_ACTION_REDACTED, DLP_ACTION_RATELIMITED, DLP_ACTION_NOTIFY_RATELIMIT, DLP_ACTION_NOTIFIED, DLP_ACTION_LOGGED};
+use crate::proxy_auth::client_base_url;
 use crate::{PROXY_PORT, PROXY_STATUS, RESTART_SENDER, ProxyStatus};
 use serde::Serialize;
 
@@ -474,6 +475,8 @@ pub fn get_message_logs(
         "blocked" => format!(" AND dlp_action = {}", DLP_ACTION_BLOCKED),
         "ratelimited" => format!(" AND dlp_action = {}", DLP_ACTION_RATELIMITED),
         "notify-ratelimit" => format!(" AND dlp_action = {}", DLP_ACTION_NOTIFY_RATELIMIT),
+        "notified" => format!(" AND dlp_action = {}", DLP_ACTION_NOTIFIED),
+        "logged" => format!(" AND dlp_action = {}", DLP_ACTION_LOGGED),
         _ => String::new(),
     };
 
@@ -586,6 +589,8 @@ pub fn export_message_logs(
         "blocked" => format!(" AND dlp_action = {}", DLP_ACTION_BLOCKED),
         "ratelimited" => format!(" AND dlp_action = {}", DLP_ACTION_RATELIMITED),
         "notify-ratelimit" => format!(" AND dlp_action = {}", DLP_ACTION_NOTIFY_RATELIMIT),
+        "notified" => format!(" AND dlp_action = {}", DLP_ACTION_NOTIFIED),
+        "logged" => format!(" AND dlp_action = {}", DLP_ACTION_LOGGED),
         _ => String::new(),
     };
 
@@ -691,6 +696,45 @@ pub fn save_port_setting(port: u16) -> Result<(), String> {
     Ok(())
 }
 
+#[derive(Serialize)]
+pub struct ProxyAccessSettings {
+    pub bind_address: String,
+    pub require_token: bool,
+    pub access_token: String,
+}
+
+#[tauri::command]
+pub fn get_proxy_access_settings() -> Result<ProxyAccessSettings, String> {
+    Ok(ProxyAccessSettings {
+        bind_address: get_bind_address_from_db(),
+        require_token: get_require_token_from_db(),
+        access_token: get_or_create_access_token()?,
+    })
+}
+
+/// Save bind address and token requirement (applied on proxy restart)
+/// The token cannot be made optional on an address other machines can reach
+#[tauri::command]
+pub fn save_proxy_access_settings(bind_address: String, require_token: bool) -> Result<(), String> {
+    let bind_address = bind_address.trim();
+    let is_loopback = bind_address.parse::<std::net::IpAddr>().map(|ip| ip.is_loopback()).unwrap_or(true);
+    if !is_loopback && !require_token {
+        return Err(format!(
+            "An access token is required when listening on {} (only loopback addresses may leave it optional)",
+            bind_address
+        ));
+    }
+    save_bind_address_to_db(bind_address)?;
+    save_require_token_to_db(require_token)?;
+    Ok(())
+}
+
+/// Generate a new access token (installed clients must be re-installed, applied on proxy restart)
+#[tauri::command]
+pub fn regenerate_proxy_access_token() -> Result<String, String> {
+    regenerate_access_token()
+}
+
 #[tauri::command]
 pub fn restart_proxy() -> Result<String, String> {
     let port = *PROXY_PORT.lock().unwrap();
@@ -724,19 +768,14 @@ fn find_fish_binary() -> Option<&'static str> {
         "/bin/fish",               // Unlikely but check
     ];
 
-    for path in FISH_PATHS {
-        if std::path::Path::new(path).exists() {
-            return Some(path);
-        }
-    }
-    None
+    FISH_PATHS.iter().copied().find(|path| std::path::Path::new(path).exists())
 }
 
 #[tauri::command]
 pub fn set_shell_env(shell: String, tool: String) -> Result<String, String> {
     let port = *PROXY_PORT.lock().unwrap();
     let (env_var, route) = get_tool_env_config(&tool)?;
-    let base_url = format!("http://localhost:{}{}", port, route);
+    let base_url = client_base_url(port, route)?;
 
     // Codex uses function wrapper instead of global env var
     if tool == "codex" {
@@ -1343,18 +1382,16 @@ fn extract_target(tool_name: &str, tool_input: &str) -> Option<String> {
             // For Glob/Grep, use path if available, otherwise pattern
             if let Some(path) = json.get("path").and_then(|v| v.as_str()) {
                 Some(path.rsplit('/').next()?.to_string())
-            } else if let Some(pattern) = json.get("pattern").and_then(|v| v.as_str()) {
-                Some(pattern.chars().take(20).collect())
             } else {
-                None
+                json.get("pattern").and_then(|v| v.as_str()).map(|pattern| pattern.chars().take(20).collect())
             }
         }
         // Bash: extract first word of command
         "Bash" => {
             let cmd = json.get("command")?.as_str()?;
-            let first_word = cmd.trim().split_whitespace().next()?;
+            let first_word = cmd.split_whitespace().next()?;
             let clean = first_word.trim_start_matches("sudo ");
-            Some(clean.split('/').last()?.to_string())
+            Some(clean.split('/').next_back()?.to_string())
         }
         _ => None
     }
@@ -1448,14 +1485,14 @@ pub fn get_tool_call_insights(time_range: String, backend: String) -> Result<Too
                 .map(|(target, count)| ToolTargetStats { target, count })
                 .collect();
 
-            targets.sort_by(|a, b| b.count.cmp(&a.count));
+            targets.sort_by_key(|t| std::cmp::Reverse(t.count));
             targets.truncate(5); // Top 5 targets per tool
 
             ToolWithTargets { tool_name, count, targets }
         })
         .collect();
 
-    tools.sort_by(|a, b| b.count.cmp(&a.count));
+    tools.sort_by_key(|t| std::cmp::Reverse(t.count));
     tools.truncate(8); // Top 8 tools
 
     Ok(ToolInsights { tools })
@@ -1524,7 +1561,7 @@ pub fn check_claude_code_settings() -> Result<bool, String> {
 #[tauri::command]
 pub fn set_claude_code_settings() -> Result<String, String> {
     let port = *PROXY_PORT.lock().unwrap();
-    let base_url = format!("http://localhost:{}/claude", port);
+    let base_url = client_base_url(port, "/claude")?;
 
     let mut settings = read_claude_settings()?;
 
@@ -1546,7 +1583,7 @@ pub fn set_claude_code_settings() -> Result<String, String> {
 
     write_claude_settings(&settings)?;
 
-    Ok(format!("ANTHROPIC_BASE_URL set in ~/.claude/settings.json"))
+    Ok("ANTHROPIC_BASE_URL set in ~/.claude/settings.json".to_string())
 }
 
 #[tauri::command]
@@ -1567,5 +1604,5 @@ pub fn remove_claude_code_settings() -> Result<String, String> {
 
     write_claude_settings(&settings)?;
 
-    Ok(format!("ANTHROPIC_BASE_URL removed from ~/.claude/settings.json"))
+    Ok("ANTHROPIC_BASE_URL removed from ~/.claude/settings.json".to_string())
 }
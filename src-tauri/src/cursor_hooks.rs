@@ -7,8 +7,10 @@
 
 use crate::backends::custom::CustomBackendSettings;
 use crate::database::{Database, DLP_ACTION_BLOCKED, DLP_ACTION_PASSED, DLP_ACTION_RATELIMITED};
-use crate::dlp::{check_dlp_patterns, DlpDetection};
-use crate::proxy::RateLimiter;
+use crate::dlp::{check_dlp_patterns, most_severe_action, DlpDetection, DlpPatternAction, DlpPatternRegistry};
+use crate::dlp_exceptions::ExceptionContext;
+use crate::proxy::{notify_dlp_detections, RateLimiter};
+use crate::secret_masking::mask_keep_ends;
 use axum::{
     extract::State,
     http::StatusCode,
@@ -19,6 +21,7 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
+use tauri::AppHandle;
 
 // ============================================================================
 // Common Input Fields (present in all hooks)
@@ -275,7 +278,9 @@ struct CursorHookMetadata {
 pub struct CursorHooksState {
     pub db: Database,
     pub rate_limiter: RateLimiter,
+    pub dlp_patterns: DlpPatternRegistry,
     pub settings: Arc<CustomBackendSettings>,
+    pub app_handle: AppHandle,
 }
 
 // ============================================================================
@@ -292,13 +297,43 @@ fn estimate_tokens(text: &str) -> i32 {
     (count_words(text) as f32 * 1.5) as i32
 }
 
-/// Format detected entities for user message
+/// Backend name hook requests are logged under (exceptions can be limited to it)
+pub(crate) const HOOKS_BACKEND: &str = "cursor-hooks";
+
+/// Exception context for text scanned by a hook, read from `file_path` if known
+fn hook_context(file_path: Option<&str>) -> ExceptionContext<'_> {
+    ExceptionContext {
+        backend: HOOKS_BACKEND,
+        file_path,
+    }
+}
+
+/// Cursor hooks can only allow or deny, so redact-action detections block too
+fn blocks_hook(detections: &[DlpDetection]) -> bool {
+    most_severe_action(detections).is_some_and(|a| a >= DlpPatternAction::Redact)
+}
+
+/// Status code for a hook request: blocked, or the most severe non-blocking action
+fn hook_dlp_status(detections: &[DlpDetection], is_blocked: bool) -> i32 {
+    if is_blocked {
+        DLP_ACTION_BLOCKED
+    } else {
+        most_severe_action(detections)
+            .map(|a| a.status_code())
+            .unwrap_or(DLP_ACTION_PASSED)
+    }
+}
+
+/// Format detected entities for user message (blocking detections only)
+/// Values are masked since the message is also returned to the agent
 fn format_detection_message(detections: &[DlpDetection]) -> String {
     let mut message = String::from("Blocked: Sensitive data detected:\n");
-    for detection in detections {
+    for detection in detections.iter().filter(|d| d.is_enforced() && d.action >= DlpPatternAction::Redact) {
         message.push_str(&format!(
             "- {} ({}): \"{}\"\n",
-            detection.pattern_name, detection.pattern_type, detection.original_value
+            detection.pattern_name,
+            detection.pattern_type,
+            mask_keep_ends(&detection.original_value)
         ));
     }
     message
@@ -478,7 +513,8 @@ async fn before_submit_prompt_handler(
     // Check DLP patterns (only if DLP is enabled)
     let mut all_detections: Vec<DlpDetection> = Vec::new();
     if state.settings.dlp_enabled {
-        all_detections = check_dlp_patterns(&input.prompt);
+        let dlp_snapshot = state.dlp_patterns.snapshot();
+        all_detections = check_dlp_patterns(&input.prompt, &dlp_snapshot, &hook_context(None));
 
         // Also check attached files
         for attachment in &input.attachments {
@@ -486,7 +522,7 @@ async fn before_submit_prompt_handler(
                 if att_type == "file" {
                     match std::fs::read_to_string(file_path) {
                         Ok(content) => {
-                            let file_detections = check_dlp_patterns(&content);
+                            let file_detections = check_dlp_patterns(&content, &dlp_snapshot, &hook_context(Some(file_path)));
                             if !file_detections.is_empty() {
                                 println!(
                                     "[CURSOR_HOOK] DLP detected in attached file: {}",
@@ -507,7 +543,8 @@ async fn before_submit_prompt_handler(
         }
     }
 
-    let is_blocked = !all_detections.is_empty();
+    notify_dlp_detections(&state.app_handle, "Cursor", &all_detections);
+    let is_blocked = blocks_hook(&all_detections);
 
     // Create or update request entry
     let response_status = if is_blocked { 403 } else { 200 };
@@ -525,7 +562,7 @@ async fn before_submit_prompt_handler(
     let response_body_json = serde_json::to_string(&response).unwrap_or_default();
 
     // Log to database
-    let dlp_action = if is_blocked { DLP_ACTION_BLOCKED } else { DLP_ACTION_PASSED };
+    let dlp_action = hook_dlp_status(&all_detections, is_blocked);
     match state.db.log_cursor_hook_request(
         &input.generation_id,
         "CursorChat",
@@ -679,7 +716,8 @@ async fn before_read_file_handler(
     // Check DLP patterns (only if DLP is enabled)
     let mut all_detections: Vec<DlpDetection> = Vec::new();
     if state.settings.dlp_enabled {
-        all_detections = check_dlp_patterns(&content);
+        let dlp_snapshot = state.dlp_patterns.snapshot();
+        all_detections = check_dlp_patterns(&content, &dlp_snapshot, &hook_context(Some(&input.file_path)));
 
         // Also check attached files if present
         if let Some(attachments) = &input.attachments {
@@ -688,7 +726,7 @@ async fn before_read_file_handler(
                     if att_type == "file" {
                         match std::fs::read_to_string(file_path) {
                             Ok(att_content) => {
-                                let file_detections = check_dlp_patterns(&att_content);
+                                let file_detections = check_dlp_patterns(&att_content, &dlp_snapshot, &hook_context(Some(file_path)));
                                 if !file_detections.is_empty() {
                                     println!(
                                         "[CURSOR_HOOK] DLP detected in attached file: {}",
@@ -710,7 +748,8 @@ async fn before_read_file_handler(
         }
     }
 
-    let is_blocked = !all_detections.is_empty();
+    notify_dlp_detections(&state.app_handle, "Cursor", &all_detections);
+    let is_blocked = blocks_hook(&all_detections);
 
     let (permission, user_message, agent_message) = if is_blocked {
         let msg = format_detection_message(&all_detections);
@@ -736,7 +775,7 @@ async fn before_read_file_handler(
 
     // Log to database
     let response_status = if is_blocked { 403 } else { 200 };
-    let dlp_action = if is_blocked { DLP_ACTION_BLOCKED } else { DLP_ACTION_PASSED };
+    let dlp_action = hook_dlp_status(&all_detections, is_blocked);
 
     if let Ok(request_id) = state.db.log_cursor_hook_request(
         &input.generation_id,
@@ -801,11 +840,12 @@ async fn before_tab_file_read_handler(
     // Check DLP patterns (only if DLP is enabled)
     // NOTE: before_tab_file_read is NOT rate limited
     let detections = if state.settings.dlp_enabled {
-        check_dlp_patterns(&content)
+        check_dlp_patterns(&content, &state.dlp_patterns.snapshot(), &hook_context(Some(&input.file_path)))
     } else {
         Vec::new()
     };
-    let is_blocked = !detections.is_empty();
+    notify_dlp_detections(&state.app_handle, "Cursor", &detections);
+    let is_blocked = blocks_hook(&detections);
 
     // Build extra metadata
     let metadata = CursorHookMetadata {
@@ -829,7 +869,7 @@ async fn before_tab_file_read_handler(
     // Log to database
     let token_count = estimate_tokens(&content);
     let response_status = if is_blocked { 403 } else { 200 };
-    let dlp_action = if is_blocked { DLP_ACTION_BLOCKED } else { DLP_ACTION_PASSED };
+    let dlp_action = hook_dlp_status(&detections, is_blocked);
 
     if let Ok(request_id) = state.db.log_cursor_hook_request(
         &input.generation_id,
@@ -1007,20 +1047,19 @@ async fn before_shell_execution_handler(
 
     // Check DLP patterns on command (only if DLP is enabled)
     let detections = if state.settings.dlp_enabled {
-        check_dlp_patterns(&input.command)
+        check_dlp_patterns(&input.command, &state.dlp_patterns.snapshot(), &hook_context(None))
     } else {
         Vec::new()
     };
-    let is_blocked = !detections.is_empty();
+    notify_dlp_detections(&state.app_handle, "Cursor", &detections);
+    let is_blocked = blocks_hook(&detections);
 
     let (permission, user_message, agent_message) = if is_blocked {
         let msg = format_detection_message(&detections);
         (
             "deny".to_string(),
             Some(msg.clone()),
-            Some(format!(
-                "Shell command was blocked due to sensitive data detection."
-            )),
+            Some("Shell command was blocked due to sensitive data detection.".to_string()),
         )
     } else {
         ("allow".to_string(), None, None)
@@ -1036,7 +1075,7 @@ async fn before_shell_execution_handler(
 
     // Log to database
     let response_status = if is_blocked { 403 } else { 200 };
-    let dlp_action = if is_blocked { DLP_ACTION_BLOCKED } else { DLP_ACTION_PASSED };
+    let dlp_action = hook_dlp_status(&detections, is_blocked);
 
     if let Ok(request_id) = state.db.log_cursor_hook_request(
         &input.generation_id,
@@ -1121,11 +1160,12 @@ async fn before_mcp_execution_handler(
 
     // Check DLP patterns on arguments (only if DLP is enabled)
     let detections = if state.settings.dlp_enabled {
-        check_dlp_patterns(&args_str)
+        check_dlp_patterns(&args_str, &state.dlp_patterns.snapshot(), &hook_context(None))
     } else {
         Vec::new()
     };
-    let is_blocked = !detections.is_empty();
+    notify_dlp_detections(&state.app_handle, "Cursor", &detections);
+    let is_blocked = blocks_hook(&detections);
 
     let (permission, user_message, agent_message) = if is_blocked {
         let msg = format_detection_message(&detections);
@@ -1151,7 +1191,7 @@ async fn before_mcp_execution_handler(
 
     // Log to database
     let response_status = if is_blocked { 403 } else { 200 };
-    let dlp_action = if is_blocked { DLP_ACTION_BLOCKED } else { DLP_ACTION_PASSED };
+    let dlp_action = hook_dlp_status(&detections, is_blocked);
 
     if let Ok(request_id) = state.db.log_cursor_hook_request(
         &input.generation_id,
@@ -1198,12 +1238,16 @@ async fn before_mcp_execution_handler(
 pub fn create_cursor_hooks_router(
     db: Database,
     rate_limiter: RateLimiter,
+    dlp_patterns: DlpPatternRegistry,
     settings: CustomBackendSettings,
+    app_handle: AppHandle,
 ) -> Router {
     let state = CursorHooksState {
         db,
         rate_limiter,
+        dlp_patterns,
         settings: Arc::new(settings),
+        app_handle,
     };
 
     Router::new()
@@ -1,8 +1,10 @@
 // Database operations and schema management
 
-use crate::builtin_patterns::get_builtin_patterns;
+use crate::builtin_patterns::{get_builtin_patterns, get_legacy_builtin_patterns, BuiltinPattern};
+use crate::detection_storage::{DetectionStorageMode, DetectionValueEncoder};
 use crate::dlp::DlpDetection;
-use crate::dlp_pattern_config::{get_db_path, DEFAULT_PORT};
+use crate::dlp_pattern_config::{get_db_path, DEFAULT_BIND_ADDRESS, DEFAULT_PORT};
+use crate::header_scrub::HeaderScrubPolicy;
 use crate::requestresponsemetadata::{RequestMetadata, ResponseMetadata};
 use rusqlite::Connection;
 use std::sync::{Arc, Mutex};
@@ -26,6 +28,30 @@ pub const DLP_ACTION_RATELIMITED: i32 = 3;
 /// DLP action: Token limit exceeded but request was allowed (notify mode)
 pub const DLP_ACTION_NOTIFY_RATELIMIT: i32 = 4;
 
+/// DLP action: Sensitive data was detected by notify-only patterns (request allowed)
+pub const DLP_ACTION_NOTIFIED: i32 = 5;
+
+/// DLP action: Sensitive data was detected by log-only patterns (request allowed)
+pub const DLP_ACTION_LOGGED: i32 = 6;
+
+/// Definition columns of a stored builtin, compared with its stock version
+struct StoredBuiltin {
+    name: String,
+    pattern_type: String,
+    patterns: String,
+    negative_pattern_type: Option<String>,
+    negative_patterns: Option<String>,
+    min_occurrences: i32,
+    min_unique_chars: i32,
+    validator: Option<String>,
+    entropy_threshold: Option<f64>,
+    entropy_min_length: Option<i32>,
+    redact_group: Option<String>,
+    context_window: Option<i32>,
+    required_context: Option<String>,
+    negative_scope: Option<String>,
+}
+
 /// Thread-safe database wrapper
 #[derive(Clone)]
 pub struct Database {
@@ -68,6 +94,28 @@ impl Database {
             println!("[DB] auto_vacuum migration complete");
         }
 
+        Self::create_schema(&conn)?;
+
+        // Enable transparent zstd compression on large columns if not already enabled
+        Self::enable_compression_if_needed(&conn)?;
+
+        // Backfill tool_calls for existing requests
+        Self::backfill_tool_calls(&conn);
+
+        // Mask credentials in headers stored before scrubbing existed
+        Self::scrub_stored_headers_once(&conn);
+
+        // Fingerprint and mask detected values stored before storage modes existed
+        Self::protect_stored_detections_once(&conn);
+
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Create the tables and run the schema migrations (no compression; tests
+    /// run this on an in-memory connection)
+    pub(crate) fn create_schema(conn: &Connection) -> Result<(), rusqlite::Error> {
         // Create requests table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS requests (
@@ -158,8 +206,170 @@ impl Database {
             [],
         )?;
 
+        // Migration: per-pattern action (NULL = use the global dlp_action setting)
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN action TEXT",
+            [],
+        );
+
+        // Migration: settings for the "entropy" pattern type (NULL = defaults)
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN entropy_threshold REAL",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN entropy_min_length INTEGER",
+            [],
+        );
+
+        // Migration: checksum validator for the "validator" pattern type
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN validator TEXT",
+            [],
+        );
+
+        // Migration: builtin library versioning. Builtin rows record the library
+        // version they were seeded from; user_modified rows are kept on upgrade
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN category TEXT",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN builtin_version INTEGER",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN user_modified INTEGER DEFAULT 0",
+            [],
+        );
+
+        // Migration: placeholder strategy for redacted values (NULL = format-preserving)
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN placeholder_strategy TEXT",
+            [],
+        );
+
+        // Migration: named capture group to redact instead of the whole match
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN redact_group TEXT",
+            [],
+        );
+
+        // Migration: per-pattern context window (NULL = 30 chars) and required context
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN context_window INTEGER",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN required_context TEXT",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN required_context_type TEXT",
+            [],
+        );
+
+        // Migration: negative patterns checked against the matched value ('value')
+        // instead of the context window (NULL = context)
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN negative_scope TEXT",
+            [],
+        );
+
+        // Create DLP rule imports table (gitleaks / trufflehog rule files)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dlp_rule_imports (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path TEXT NOT NULL UNIQUE,
+                format TEXT NOT NULL,
+                content_hash TEXT,
+                rule_count INTEGER DEFAULT 0,
+                skipped TEXT,
+                created_at TEXT NOT NULL,
+                synced_at TEXT
+            )",
+            [],
+        )?;
+
+        // Migration: pattern groups created by a rule import, keyed by rule id
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN import_id INTEGER",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN import_rule_id TEXT",
+            [],
+        );
+
+        // Create known secret tables (values read from .env / credential files,
+        // stored only as salted hashes of each value and its encoded forms)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dlp_known_secret_sources (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                path TEXT NOT NULL UNIQUE,
+                file_count INTEGER DEFAULT 0,
+                value_count INTEGER DEFAULT 0,
+                error TEXT,
+                created_at TEXT NOT NULL,
+                synced_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dlp_known_secrets (
+                source_id INTEGER NOT NULL,
+                hash TEXT NOT NULL,
+                length INTEGER NOT NULL,
+                form TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_dlp_known_secrets_source ON dlp_known_secrets(source_id)",
+            [],
+        )?;
+
+        // Migration: dictionary pattern options (NULL = case-insensitive, whole words)
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN dictionary_case_sensitive INTEGER",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN dictionary_whole_words INTEGER",
+            [],
+        );
+
+        // Migration: shadow mode (pattern is evaluated and recorded, never enforced)
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN shadow INTEGER DEFAULT 0",
+            [],
+        );
+
+        // Migration: regression corpus (JSON arrays of sample texts, see pattern_corpus.rs)
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN must_match TEXT",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE dlp_patterns ADD COLUMN must_not_match TEXT",
+            [],
+        );
+
+        // Create dictionary entries table (word lists of dictionary patterns)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dlp_dictionary_entries (
+                pattern_id INTEGER NOT NULL,
+                entry TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_dlp_dictionary_entries_pattern ON dlp_dictionary_entries(pattern_id)",
+            [],
+        )?;
+
         // Seed builtin patterns if not exists
-        Self::seed_builtin_patterns(&conn)?;
+        Self::seed_builtin_patterns(conn)?;
 
         // Create DLP detections table
         conn.execute(
@@ -172,17 +382,173 @@ impl Database {
                 original_value TEXT NOT NULL,
                 placeholder TEXT NOT NULL,
                 message_index INTEGER,
+                value_fingerprint TEXT,
+                value_storage TEXT DEFAULT 'plaintext',
+                action TEXT,
+                placeholder_strategy TEXT,
+                found_in TEXT,
+                shadow INTEGER DEFAULT 0,
+                exception_id INTEGER,
                 FOREIGN KEY (request_id) REFERENCES requests(id)
             )",
             [],
         )?;
 
+        // Migration: salted fingerprint of the detected value and how the value was stored
+        let _ = conn.execute(
+            "ALTER TABLE dlp_detections ADD COLUMN value_fingerprint TEXT",
+            [],
+        );
+        let _ = conn.execute(
+            "ALTER TABLE dlp_detections ADD COLUMN value_storage TEXT DEFAULT 'plaintext'",
+            [],
+        );
+
+        // Migration: action of the pattern that fired
+        let _ = conn.execute(
+            "ALTER TABLE dlp_detections ADD COLUMN action TEXT",
+            [],
+        );
+
+        // Migration: placeholder strategy of redacted values
+        let _ = conn.execute(
+            "ALTER TABLE dlp_detections ADD COLUMN placeholder_strategy TEXT",
+            [],
+        );
+
+        // Migration: decoded or normalized view a value was found in (NULL = literal text)
+        let _ = conn.execute(
+            "ALTER TABLE dlp_detections ADD COLUMN found_in TEXT",
+            [],
+        );
+
+        // Migration: detections of shadow patterns (the request was left untouched)
+        let _ = conn.execute(
+            "ALTER TABLE dlp_detections ADD COLUMN shadow INTEGER DEFAULT 0",
+            [],
+        );
+
+        // Migration: exception that covered the value (recorded, not acted on)
+        let _ = conn.execute(
+            "ALTER TABLE dlp_detections ADD COLUMN exception_id INTEGER",
+            [],
+        );
+
         // Index for faster cleanup of dlp_detections by request_id
         let _ = conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_dlp_detections_request_id ON dlp_detections(request_id)",
             [],
         );
 
+        // Index for grouping detections of the same value
+        let _ = conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_dlp_detections_fingerprint ON dlp_detections(value_fingerprint)",
+            [],
+        );
+
+        // Index for counting what each exception let through
+        let _ = conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_dlp_detections_exception ON dlp_detections(exception_id)",
+            [],
+        );
+
+        // Create DLP exceptions table (false positives, see dlp_exceptions.rs)
+        // Rows are revoked, never deleted, so the table doubles as an audit trail
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dlp_exceptions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                pattern_name TEXT,
+                value_hash TEXT,
+                value_regex TEXT,
+                file_path TEXT,
+                backend TEXT,
+                expires_at TEXT,
+                reason TEXT,
+                detection_id INTEGER,
+                created_at TEXT NOT NULL,
+                revoked_at TEXT
+            )",
+            [],
+        )?;
+
+        // Create retroactive scan tables (see retro_scan.rs); kept apart from
+        // dlp_detections so replayed results never count as live detections
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dlp_retro_scans (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                started_at TEXT NOT NULL,
+                finished_at TEXT,
+                status TEXT NOT NULL,
+                since TEXT NOT NULL,
+                backend TEXT,
+                pattern_ids TEXT,
+                requests_total INTEGER DEFAULT 0,
+                requests_scanned INTEGER DEFAULT 0,
+                detections INTEGER DEFAULT 0,
+                last_request_id INTEGER DEFAULT 0,
+                error TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dlp_retro_detections (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                scan_id INTEGER NOT NULL,
+                request_id INTEGER NOT NULL,
+                request_timestamp TEXT NOT NULL,
+                backend TEXT,
+                pattern_name TEXT NOT NULL,
+                pattern_type TEXT NOT NULL,
+                original_value TEXT NOT NULL,
+                message_index INTEGER,
+                value_fingerprint TEXT,
+                value_storage TEXT,
+                action TEXT,
+                found_in TEXT,
+                shadow INTEGER DEFAULT 0,
+                exception_id INTEGER
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_dlp_retro_detections_scan ON dlp_retro_detections(scan_id)",
+            [],
+        )?;
+
+        // Migration: vault entries used to store the redacted value itself; they
+        // are dropped, as only a fingerprint of the value is stored now
+        let vault_has_originals: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('dlp_vault') WHERE name = 'original'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap_or(false);
+        if vault_has_originals {
+            conn.execute("DROP TABLE dlp_vault", [])?;
+        }
+
+        // Create DLP redaction vault (placeholders per agent session, see redaction_vault.rs)
+        // strategy: NULL = format-preserving
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS dlp_vault (
+                session_key TEXT NOT NULL,
+                placeholder TEXT NOT NULL,
+                value_fingerprint TEXT NOT NULL,
+                strategy TEXT,
+                created_at TEXT NOT NULL,
+                last_used_at INTEGER NOT NULL,
+                PRIMARY KEY (session_key, placeholder)
+            )",
+            [],
+        )?;
+
+        // Index for purging expired vault sessions
+        let _ = conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_dlp_vault_last_used ON dlp_vault(last_used_at)",
+            [],
+        );
+
         // Create tool_calls table (no FK constraint - requests is a view due to zstd compression)
         conn.execute(
             "CREATE TABLE IF NOT EXISTS tool_calls (
@@ -230,15 +596,7 @@ impl Database {
             [],
         )?;
 
-        // Enable transparent zstd compression on large columns if not already enabled
-        Self::enable_compression_if_needed(&conn)?;
-
-        // Backfill tool_calls for existing requests
-        Self::backfill_tool_calls(&conn);
-
-        Ok(Self {
-            conn: Arc::new(Mutex::new(conn)),
-        })
+        Ok(())
     }
 
     /// One-time backfill of tool_calls from existing response bodies
@@ -312,6 +670,179 @@ impl Database {
         println!("[DB] Backfill complete. Extracted {} tool calls.", total_tool_calls);
     }
 
+    /// One-time scrub of credentials from stored request/response headers
+    fn scrub_stored_headers_once(conn: &Connection) {
+        let already_done: bool = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'header_scrub_migration_done'",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .map(|v| v == "true")
+            .unwrap_or(false);
+
+        if already_done {
+            return;
+        }
+
+        println!("[DB] Scrubbing credentials from stored headers...");
+        let policy = HeaderScrubPolicy::load_with(conn);
+        match Self::scrub_headers_with_policy(conn, &policy) {
+            Ok(scrubbed) => {
+                let _ = conn.execute(
+                    "INSERT OR REPLACE INTO settings (key, value) VALUES ('header_scrub_migration_done', 'true')",
+                    [],
+                );
+                println!("[DB] Header scrub complete. Updated {} requests.", scrubbed);
+            }
+            Err(e) => println!("[DB] Failed to scrub stored headers: {}", e),
+        }
+    }
+
+    /// Re-apply a header scrubbing policy to all stored requests
+    /// Used when the user adds sensitive header names
+    pub fn scrub_stored_headers(&self, policy: &HeaderScrubPolicy) -> Result<usize, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        Self::scrub_headers_with_policy(&conn, policy)
+    }
+
+    fn scrub_headers_with_policy(conn: &Connection, policy: &HeaderScrubPolicy) -> Result<usize, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT id, request_headers, response_headers FROM requests
+             WHERE request_headers IS NOT NULL OR response_headers IS NOT NULL",
+        )?;
+
+        let updates: Vec<(i64, Option<String>, Option<String>)> = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            })?
+            .filter_map(|r| r.ok())
+            .filter_map(|(id, req_headers, resp_headers)| {
+                let req_scrubbed = req_headers.as_deref().and_then(|h| policy.scrub_json(h));
+                let resp_scrubbed = resp_headers.as_deref().and_then(|h| policy.scrub_json(h));
+                if req_scrubbed.is_none() && resp_scrubbed.is_none() {
+                    return None;
+                }
+                Some((
+                    id,
+                    req_scrubbed.or(req_headers),
+                    resp_scrubbed.or(resp_headers),
+                ))
+            })
+            .collect();
+
+        let tx = conn.unchecked_transaction()?;
+        for (id, req_headers, resp_headers) in &updates {
+            tx.execute(
+                "UPDATE requests SET request_headers = ?1, response_headers = ?2 WHERE id = ?3",
+                rusqlite::params![req_headers, resp_headers, id],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(updates.len())
+    }
+
+    /// One-time fingerprinting of detections stored in plaintext
+    fn protect_stored_detections_once(conn: &Connection) {
+        let already_done: bool = conn
+            .query_row(
+                "SELECT value FROM settings WHERE key = 'detection_storage_migration_done'",
+                [],
+                |row| row.get::<_, String>(0),
+            )
+            .map(|v| v == "true")
+            .unwrap_or(false);
+
+        if already_done {
+            return;
+        }
+
+        println!("[DB] Fingerprinting stored detection values...");
+        let result = DetectionValueEncoder::load_with(conn)
+            .and_then(|encoder| Self::reencode_detections(conn, &encoder, "dlp_detections"));
+        match result {
+            Ok(updated) => {
+                let _ = conn.execute(
+                    "INSERT OR REPLACE INTO settings (key, value) VALUES ('detection_storage_migration_done', 'true')",
+                    [],
+                );
+                println!("[DB] Detection migration complete. Updated {} detections.", updated);
+            }
+            Err(e) => println!("[DB] Failed to migrate stored detections: {}", e),
+        }
+    }
+
+    /// Re-apply the configured storage mode to stored detections (live and retroactive)
+    /// Only rows stored under a less strict mode are changed (masking cannot be undone)
+    pub fn reencode_stored_detections(&self) -> Result<usize, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        let encoder = DetectionValueEncoder::load_with(&conn)?;
+        Ok(Self::reencode_detections(&conn, &encoder, "dlp_detections")?
+            + Self::reencode_detections(&conn, &encoder, "dlp_retro_detections")?)
+    }
+
+    fn reencode_detections(
+        conn: &Connection,
+        encoder: &DetectionValueEncoder,
+        table: &str,
+    ) -> Result<usize, rusqlite::Error> {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id, original_value, value_fingerprint, COALESCE(value_storage, 'plaintext') FROM {}",
+            table
+        ))?;
+
+        let updates: Vec<(i64, String, String)> = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .filter_map(|r| r.ok())
+            .filter_map(|(id, value, fingerprint, storage)| {
+                let storage = DetectionStorageMode::parse(&storage).unwrap_or(DetectionStorageMode::Plaintext);
+                match fingerprint {
+                    // Rows without a fingerprint predate storage modes and hold the raw value
+                    None => {
+                        let (stored, fingerprint) = encoder.encode(&value);
+                        Some((id, stored, fingerprint))
+                    }
+                    Some(fingerprint) if encoder.mode.is_stricter_than(storage) => {
+                        // Masked values are re-masked only when going to fingerprint, which stores nothing
+                        let stored = if storage == DetectionStorageMode::Plaintext {
+                            encoder.mode.store(&value)
+                        } else {
+                            String::new()
+                        };
+                        Some((id, stored, fingerprint))
+                    }
+                    Some(_) => None,
+                }
+            })
+            .collect();
+
+        let tx = conn.unchecked_transaction()?;
+        for (id, stored, fingerprint) in &updates {
+            tx.execute(
+                &format!(
+                    "UPDATE {} SET original_value = ?1, value_fingerprint = ?2, value_storage = ?3 WHERE id = ?4",
+                    table
+                ),
+                rusqlite::params![stored, fingerprint, encoder.mode.as_str(), id],
+            )?;
+        }
+        tx.commit()?;
+
+        Ok(updates.len())
+    }
+
     /// Extract tool calls from Claude response body (used for backfill)
     fn extract_tool_calls_claude(body: &str, is_streaming: bool) -> Vec<crate::requestresponsemetadata::ToolCall> {
         use std::collections::HashMap;
@@ -437,9 +968,7 @@ impl Database {
                                             let call_id = item.get("call_id").and_then(|v| v.as_str()).unwrap_or("").to_string();
                                             let name = item.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string();
                                             let arguments = item.get("arguments").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                                            if !function_calls_map.contains_key(&item_id) {
-                                                function_calls_map.insert(item_id, (call_id, name, arguments));
-                                            }
+                                            function_calls_map.entry(item_id).or_insert((call_id, name, arguments));
                                         }
                                     }
                                 }
@@ -476,66 +1005,200 @@ impl Database {
         tool_calls
     }
 
-    /// Seed builtin DLP patterns, overwriting if they already exist
+    /// Seed builtin DLP patterns
+    /// - new builtins are inserted (enabled unless marked noisy)
+    /// - builtins seeded from an older library version are updated in place,
+    ///   preserving the enabled flag and action
+    /// - builtins edited by the user are left as they are
     fn seed_builtin_patterns(conn: &Connection) -> Result<(), rusqlite::Error> {
         let builtin_patterns = get_builtin_patterns();
         let created_at = chrono::Utc::now().to_rfc3339();
 
         for pattern in builtin_patterns {
-            // Convert static slices to JSON strings for storage
-            let patterns_vec: Vec<&str> = pattern.patterns.to_vec();
-            let patterns_json =
-                serde_json::to_string(&patterns_vec).unwrap_or_else(|_| "[]".to_string());
-            let negative_patterns_json = pattern.negative_patterns.map(|np| {
-                let np_vec: Vec<&str> = np.to_vec();
-                serde_json::to_string(&np_vec).unwrap_or_else(|_| "[]".to_string())
-            });
-
             // Check if this builtin pattern already exists
-            let existing_id: Option<i64> = conn
+            let existing: Option<(i64, Option<i32>, bool)> = conn
                 .query_row(
-                    "SELECT id FROM dlp_patterns WHERE is_builtin = 1 AND name = ?1",
+                    "SELECT id, builtin_version, COALESCE(user_modified, 0) FROM dlp_patterns WHERE is_builtin = 1 AND name = ?1",
                     rusqlite::params![pattern.name],
-                    |row| row.get(0),
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get::<_, i32>(2)? == 1)),
                 )
                 .ok();
 
-            if let Some(id) = existing_id {
-                // Update existing pattern (preserve enabled state)
-                conn.execute(
-                    "UPDATE dlp_patterns SET pattern_type = ?1, patterns = ?2, negative_pattern_type = ?3, negative_patterns = ?4, min_occurrences = ?5, min_unique_chars = ?6 WHERE id = ?7",
-                    rusqlite::params![
-                        pattern.pattern_type,
-                        patterns_json,
-                        pattern.negative_pattern_type,
-                        negative_patterns_json,
-                        pattern.min_occurrences,
-                        pattern.min_unique_chars,
-                        id
-                    ],
-                )?;
-            } else {
-                // Insert new pattern
-                conn.execute(
-                    "INSERT INTO dlp_patterns (name, pattern_type, patterns, negative_pattern_type, negative_patterns, enabled, min_occurrences, min_unique_chars, is_builtin, created_at)
-                     VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6, ?7, 1, ?8)",
-                    rusqlite::params![
-                        pattern.name,
-                        pattern.pattern_type,
-                        patterns_json,
-                        pattern.negative_pattern_type,
-                        negative_patterns_json,
-                        pattern.min_occurrences,
-                        pattern.min_unique_chars,
-                        created_at
-                    ],
-                )?;
+            match existing {
+                Some((id, version, mut user_modified)) => {
+                    conn.execute(
+                        "UPDATE dlp_patterns SET category = ?1 WHERE id = ?2",
+                        rusqlite::params![pattern.category, id],
+                    )?;
+                    // Rows seeded before versioning were never flagged when edited
+                    if version.is_none() && !user_modified && Self::legacy_builtin_modified(conn, id) {
+                        conn.execute(
+                            "UPDATE dlp_patterns SET user_modified = 1 WHERE id = ?1",
+                            rusqlite::params![id],
+                        )?;
+                        user_modified = true;
+                    }
+                    if !user_modified && version.unwrap_or(0) < pattern.version {
+                        Self::write_builtin_definition(conn, id, pattern)?;
+                    }
+                }
+                None => {
+                    let (patterns_json, negative_patterns_json) = Self::builtin_pattern_json(pattern);
+                    conn.execute(
+                        "INSERT INTO dlp_patterns (name, pattern_type, patterns, negative_pattern_type, negative_patterns, enabled, min_occurrences, min_unique_chars, is_builtin, created_at, validator, category, builtin_version, user_modified)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 1, ?9, ?10, ?11, ?12, 0)",
+                        rusqlite::params![
+                            pattern.name,
+                            pattern.pattern_type,
+                            patterns_json,
+                            pattern.negative_pattern_type,
+                            negative_patterns_json,
+                            pattern.enabled_by_default as i32,
+                            pattern.min_occurrences,
+                            pattern.min_unique_chars,
+                            created_at,
+                            pattern.validator,
+                            pattern.category,
+                            pattern.version
+                        ],
+                    )?;
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Patterns and negative patterns of a builtin as stored (JSON arrays)
+    fn builtin_pattern_json(pattern: &BuiltinPattern) -> (String, Option<String>) {
+        let patterns_json =
+            serde_json::to_string(pattern.patterns).unwrap_or_else(|_| "[]".to_string());
+        let negative_patterns_json = pattern
+            .negative_patterns
+            .map(|np| serde_json::to_string(np).unwrap_or_else(|_| "[]".to_string()));
+        (patterns_json, negative_patterns_json)
+    }
+
+    /// Overwrite a builtin row with the stock definition (enabled and action are kept)
+    fn write_builtin_definition(conn: &Connection, id: i64, pattern: &BuiltinPattern) -> Result<(), rusqlite::Error> {
+        let (patterns_json, negative_patterns_json) = Self::builtin_pattern_json(pattern);
+        conn.execute(
+            "UPDATE dlp_patterns SET pattern_type = ?1, patterns = ?2, negative_pattern_type = ?3, negative_patterns = ?4,
+                    min_occurrences = ?5, min_unique_chars = ?6, validator = ?7, entropy_threshold = NULL,
+                    entropy_min_length = NULL, redact_group = NULL, context_window = NULL, required_context = NULL,
+                    required_context_type = NULL, negative_scope = NULL, category = ?8, builtin_version = ?9, user_modified = 0
+             WHERE id = ?10",
+            rusqlite::params![
+                pattern.pattern_type,
+                patterns_json,
+                pattern.negative_pattern_type,
+                negative_patterns_json,
+                pattern.min_occurrences,
+                pattern.min_unique_chars,
+                pattern.validator,
+                pattern.category,
+                pattern.version,
+                id
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Whether a row seeded before versioning differs from the legacy stock definition
+    fn legacy_builtin_modified(conn: &Connection, id: i64) -> bool {
+        let Some(stored) = Self::load_stored_builtin(conn, id) else {
+            return false;
+        };
+        match get_legacy_builtin_patterns().iter().find(|p| p.name == stored.name) {
+            Some(legacy) => !Self::matches_builtin(&stored, legacy),
+            // Nothing to compare with: keep the row as it is
+            None => true,
+        }
+    }
+
+    /// After a builtin row is edited, record whether it still matches the stock
+    /// definition (so upgrades know whether they may overwrite it)
+    pub fn refresh_builtin_modified(conn: &Connection, id: i64) -> Result<(), rusqlite::Error> {
+        let Some(stored) = Self::load_stored_builtin(conn, id) else {
+            return Ok(());
+        };
+        let Some(stock) = get_builtin_patterns().iter().find(|p| p.name == stored.name) else {
+            return Ok(());
+        };
+
+        conn.execute(
+            "UPDATE dlp_patterns SET user_modified = ?1 WHERE id = ?2",
+            rusqlite::params![!Self::matches_builtin(&stored, stock) as i32, id],
+        )?;
+        Ok(())
+    }
+
+    fn load_stored_builtin(conn: &Connection, id: i64) -> Option<StoredBuiltin> {
+        conn.query_row(
+            "SELECT name, pattern_type, patterns, negative_pattern_type, negative_patterns, min_occurrences,
+                    min_unique_chars, validator, entropy_threshold, entropy_min_length, redact_group,
+                    context_window, required_context, negative_scope
+             FROM dlp_patterns WHERE id = ?1 AND is_builtin = 1",
+            rusqlite::params![id],
+            |row| {
+                Ok(StoredBuiltin {
+                    name: row.get(0)?,
+                    pattern_type: row.get(1)?,
+                    patterns: row.get(2)?,
+                    negative_pattern_type: row.get(3)?,
+                    negative_patterns: row.get(4)?,
+                    min_occurrences: row.get(5)?,
+                    min_unique_chars: row.get(6)?,
+                    validator: row.get(7)?,
+                    entropy_threshold: row.get(8)?,
+                    entropy_min_length: row.get(9)?,
+                    redact_group: row.get(10)?,
+                    context_window: row.get(11)?,
+                    required_context: row.get(12)?,
+                    negative_scope: row.get(13)?,
+                })
+            },
+        )
+        .ok()
+    }
+
+    /// Whether a stored builtin holds exactly the given stock definition
+    fn matches_builtin(stored: &StoredBuiltin, stock: &BuiltinPattern) -> bool {
+        let (stock_patterns, stock_negative) = Self::builtin_pattern_json(stock);
+        stored.pattern_type == stock.pattern_type
+            && stored.patterns == stock_patterns
+            && stored.negative_pattern_type.as_deref() == stock.negative_pattern_type
+            && stored.negative_patterns == stock_negative
+            && stored.min_occurrences == stock.min_occurrences
+            && stored.min_unique_chars == stock.min_unique_chars
+            && stored.validator.as_deref() == stock.validator
+            && stored.entropy_threshold.is_none()
+            && stored.entropy_min_length.is_none()
+            && stored.redact_group.is_none()
+            && stored.context_window.is_none()
+            && stored.required_context.is_none()
+            && stored.negative_scope.is_none()
+    }
+
+    /// Restore a builtin row to the current stock definition.
+    /// Returns false if the row is not a known builtin
+    pub fn reset_builtin_pattern(conn: &Connection, id: i64) -> Result<bool, rusqlite::Error> {
+        let name: Option<String> = conn
+            .query_row(
+                "SELECT name FROM dlp_patterns WHERE id = ?1 AND is_builtin = 1",
+                rusqlite::params![id],
+                |row| row.get(0),
+            )
+            .ok();
+        match name.and_then(|n| get_builtin_patterns().iter().find(|p| p.name == n)) {
+            Some(stock) => {
+                Self::write_builtin_definition(conn, id, stock)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     /// Enable transparent zstd compression on large text columns
     /// This is a one-time migration that compresses existing data
     fn enable_compression_if_needed(conn: &Connection) -> Result<(), rusqlite::Error> {
@@ -631,6 +1294,12 @@ impl Database {
             rusqlite::params![cutoff_ts],
         )?;
 
+        // Retroactive scan results go with the requests they were found in
+        conn.execute(
+            "DELETE FROM dlp_retro_detections WHERE request_id IN (SELECT id FROM requests WHERE timestamp < ?1)",
+            rusqlite::params![cutoff_ts],
+        )?;
+
         // Delete tool calls for requests that will be deleted
         conn.execute(
             "DELETE FROM tool_calls WHERE request_id IN (SELECT id FROM requests WHERE timestamp < ?1)",
@@ -765,21 +1434,34 @@ impl Database {
         request_id: i64,
         detections: &[DlpDetection],
     ) -> Result<(), rusqlite::Error> {
+        if detections.is_empty() {
+            return Ok(());
+        }
+
         let conn = self.conn.lock().unwrap();
         let timestamp = chrono::Utc::now().to_rfc3339();
+        let encoder = DetectionValueEncoder::load_with(&conn)?;
 
         for detection in detections {
+            let (stored_value, fingerprint) = encoder.encode(&detection.original_value);
             conn.execute(
-                "INSERT INTO dlp_detections (request_id, timestamp, pattern_name, pattern_type, original_value, placeholder, message_index)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                "INSERT INTO dlp_detections (request_id, timestamp, pattern_name, pattern_type, original_value, placeholder, message_index, value_fingerprint, value_storage, action, placeholder_strategy, found_in, shadow, exception_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
                 rusqlite::params![
                     request_id,
                     timestamp,
                     detection.pattern_name,
                     detection.pattern_type,
-                    detection.original_value,
+                    stored_value,
                     detection.placeholder,
                     detection.message_index,
+                    fingerprint,
+                    encoder.mode.as_str(),
+                    detection.action.as_str(),
+                    detection.placeholder_strategy.map(|s| s.as_str()),
+                    detection.found_in.map(|v| v.as_str()),
+                    detection.shadow as i32,
+                    detection.exception_id,
                 ],
             )?;
         }
@@ -945,7 +1627,7 @@ impl Database {
             let latency_ms = chrono::DateTime::parse_from_rfc3339(&timestamp_str)
                 .map(|start_time| {
                     let now = chrono::Utc::now();
-                    (now.signed_duration_since(start_time)).num_milliseconds().max(0) as i64
+                    (now.signed_duration_since(start_time)).num_milliseconds().max(0)
                 })
                 .unwrap_or(0);
 
@@ -1160,6 +1842,16 @@ impl Database {
         Ok(count > 0)
     }
 
+    /// Get the names of all DLP patterns (referenced by backend pattern scopes)
+    pub fn get_dlp_pattern_names(&self) -> Result<Vec<String>, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT DISTINCT name FROM dlp_patterns")?;
+        let names = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(names)
+    }
+
     // ========================================================================
     // Predefined Backend Settings Methods
     // ========================================================================
@@ -1306,6 +1998,103 @@ pub fn save_dlp_action_to_db(action: &str) -> Result<(), String> {
     Ok(())
 }
 
+// Proxy access helpers
+
+pub fn get_bind_address_from_db() -> String {
+    let conn = match open_connection() {
+        Ok(c) => c,
+        Err(_) => return DEFAULT_BIND_ADDRESS.to_string(),
+    };
+
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = 'proxy_bind_address'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .filter(|v| v.parse::<std::net::IpAddr>().is_ok())
+    .unwrap_or_else(|| DEFAULT_BIND_ADDRESS.to_string())
+}
+
+pub fn save_bind_address_to_db(address: &str) -> Result<(), String> {
+    // Validate address value
+    if address.parse::<std::net::IpAddr>().is_err() {
+        return Err(format!("Invalid bind address '{}'. Must be an IP address", address));
+    }
+
+    let conn = open_connection().map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('proxy_bind_address', ?1)",
+        rusqlite::params![address],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+pub fn get_require_token_from_db() -> bool {
+    let conn = match open_connection() {
+        Ok(c) => c,
+        Err(_) => return false,
+    };
+
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = 'proxy_require_token'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .map(|v| v == "true")
+    .unwrap_or(false)
+}
+
+pub fn save_require_token_to_db(required: bool) -> Result<(), String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('proxy_require_token', ?1)",
+        rusqlite::params![if required { "true" } else { "false" }],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Get the proxy access token, generating one on first use
+pub fn get_or_create_access_token() -> Result<String, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+
+    // Only fills a missing or empty token, so concurrent first callers all end
+    // up with the one that was stored first
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES ('proxy_access_token', ?1)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value WHERE settings.value = ''",
+        rusqlite::params![crate::proxy_auth::generate_access_token()],
+    )
+    .map_err(|e| e.to_string())?;
+
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = 'proxy_access_token'",
+        [],
+        |row| row.get::<_, String>(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Replace the proxy access token with a freshly generated one
+pub fn regenerate_access_token() -> Result<String, String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    let token = crate::proxy_auth::generate_access_token();
+
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('proxy_access_token', ?1)",
+        rusqlite::params![token],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(token)
+}
+
 // Notification rate limiting helpers
 
 pub fn get_last_notification_time() -> Option<u64> {
@@ -1334,3 +2123,65 @@ pub fn set_last_notification_time(timestamp: u64) -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Database with the dlp_patterns table and "API Keys" row of the first release
+    fn baseline_db(patterns: &[&str]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE dlp_patterns (
+                id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT NOT NULL, pattern_type TEXT NOT NULL,
+                patterns TEXT NOT NULL, negative_pattern_type TEXT, negative_patterns TEXT,
+                enabled INTEGER DEFAULT 1, min_occurrences INTEGER DEFAULT 1,
+                min_unique_chars INTEGER DEFAULT 0, is_builtin INTEGER DEFAULT 0, created_at TEXT NOT NULL
+            )",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO dlp_patterns (name, pattern_type, patterns, min_occurrences, min_unique_chars, is_builtin, created_at)
+             VALUES ('API Keys', 'regex', ?1, 1, 10, 1, '2025-01-01T00:00:00+00:00')",
+            rusqlite::params![serde_json::to_string(patterns).unwrap()],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn api_keys_row(conn: &Connection) -> (String, Option<i32>, i32) {
+        conn.query_row(
+            "SELECT patterns, builtin_version, user_modified FROM dlp_patterns WHERE name = 'API Keys'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_upgrade_keeps_edited_legacy_builtin() {
+        let conn = baseline_db(&[r"sk-[a-zA-Z0-9]{20,}", r"internal-key-[0-9]{8}"]);
+        Database::create_schema(&conn).unwrap();
+
+        let (patterns, version, user_modified) = api_keys_row(&conn);
+        assert!(patterns.contains("internal-key-"));
+        assert_eq!(version, None);
+        assert_eq!(user_modified, 1);
+
+        // Later starts leave it alone as well
+        Database::create_schema(&conn).unwrap();
+        assert!(api_keys_row(&conn).0.contains("internal-key-"));
+    }
+
+    #[test]
+    fn test_upgrade_replaces_stock_legacy_builtin() {
+        let conn = baseline_db(get_legacy_builtin_patterns()[0].patterns);
+        Database::create_schema(&conn).unwrap();
+
+        let stock = get_builtin_patterns().iter().find(|p| p.name == "API Keys").unwrap();
+        let (patterns, version, user_modified) = api_keys_row(&conn);
+        assert_eq!(patterns, serde_json::to_string(stock.patterns).unwrap());
+        assert_eq!(version, Some(stock.version));
+        assert_eq!(user_modified, 0);
+    }
+}
@@ -0,0 +1,162 @@
+// Detection Value Storage
+//
+// Controls how matched values are written to the dlp_detections table
+// (settings key `dlp_detection_storage_mode`):
+// - masked: only the first/last few characters are kept (default)
+// - fingerprint: the value itself is not stored at all
+// - plaintext: the raw value, for debugging patterns
+// Every row also carries a salted HMAC-SHA256 fingerprint so repeated leaks of
+// the same secret can be counted without keeping the secret.
+
+use crate::database::open_connection;
+use crate::secret_masking::{hmac_sha256_hex, mask_keep_ends};
+use rusqlite::Connection;
+
+const STORAGE_MODE_KEY: &str = "dlp_detection_storage_mode";
+pub const FINGERPRINT_SALT_KEY: &str = "dlp_fingerprint_salt";
+
+/// Number of hex characters kept from the HMAC (64 bits)
+const FINGERPRINT_LEN: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DetectionStorageMode {
+    Masked,
+    Fingerprint,
+    Plaintext,
+}
+
+impl DetectionStorageMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "masked" => Some(Self::Masked),
+            "fingerprint" => Some(Self::Fingerprint),
+            "plaintext" => Some(Self::Plaintext),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Masked => "masked",
+            Self::Fingerprint => "fingerprint",
+            Self::Plaintext => "plaintext",
+        }
+    }
+
+    /// Convert a value for storage under this mode
+    pub fn store(&self, value: &str) -> String {
+        match self {
+            Self::Masked => mask_keep_ends(value),
+            Self::Fingerprint => String::new(),
+            Self::Plaintext => value.to_string(),
+        }
+    }
+
+    /// Check whether values stored under `other` reveal more than this mode
+    pub fn is_stricter_than(&self, other: Self) -> bool {
+        self.rank() < other.rank()
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Fingerprint => 0,
+            Self::Masked => 1,
+            Self::Plaintext => 2,
+        }
+    }
+}
+
+/// Converts detected values into their stored form and fingerprint
+pub struct DetectionValueEncoder {
+    pub mode: DetectionStorageMode,
+    salt: Vec<u8>,
+}
+
+impl DetectionValueEncoder {
+    /// Load the storage mode and salt, creating the salt on first use
+    pub fn load_with(conn: &Connection) -> Result<Self, rusqlite::Error> {
+        Ok(Self {
+            mode: read_storage_mode(conn),
+            salt: get_or_create_salt(conn, FINGERPRINT_SALT_KEY)?,
+        })
+    }
+
+    /// Salted fingerprint of a value
+    pub fn fingerprint(&self, value: &str) -> String {
+        hmac_sha256_hex(&self.salt, value)[..FINGERPRINT_LEN].to_string()
+    }
+
+    /// Returns (stored value, fingerprint)
+    pub fn encode(&self, value: &str) -> (String, String) {
+        (self.mode.store(value), self.fingerprint(value))
+    }
+}
+
+/// Get the configured storage mode for detected values
+pub fn get_storage_mode() -> DetectionStorageMode {
+    match open_connection() {
+        Ok(conn) => read_storage_mode(&conn),
+        Err(_) => DetectionStorageMode::Masked,
+    }
+}
+
+pub fn read_storage_mode(conn: &Connection) -> DetectionStorageMode {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        rusqlite::params![STORAGE_MODE_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|v| DetectionStorageMode::parse(&v))
+    .unwrap_or(DetectionStorageMode::Masked)
+}
+
+/// Save the storage mode for detected values
+pub fn save_storage_mode(mode: DetectionStorageMode) -> Result<(), String> {
+    let conn = open_connection().map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        rusqlite::params![STORAGE_MODE_KEY, mode.as_str()],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Per-install salt stored under a settings key, created on first use
+/// (`FINGERPRINT_SALT_KEY` for detections, header masks and the vault)
+pub fn get_or_create_salt(conn: &Connection, key: &str) -> Result<Vec<u8>, rusqlite::Error> {
+    let read = || {
+        conn.query_row("SELECT value FROM settings WHERE key = ?1", rusqlite::params![key], |row| {
+            row.get::<_, String>(0)
+        })
+    };
+    let decode = |value: &str| hex::decode(value).ok().filter(|salt| !salt.is_empty());
+
+    if let Some(salt) = read().ok().as_deref().and_then(decode) {
+        return Ok(salt);
+    }
+
+    // Only stored if missing, then re-read: concurrent first callers all end up
+    // with the salt stored first, so nothing hashed with it stops matching
+    let fresh = hex::encode(rand::random::<[u8; 32]>());
+    conn.execute(
+        "INSERT OR IGNORE INTO settings (key, value) VALUES (?1, ?2)",
+        rusqlite::params![key, fresh],
+    )?;
+    let stored = read()?;
+    if let Some(salt) = decode(&stored) {
+        return Ok(salt);
+    }
+
+    // An unreadable salt is replaced, unless another caller already did
+    conn.execute(
+        "UPDATE settings SET value = ?3 WHERE key = ?1 AND value = ?2",
+        rusqlite::params![key, stored, fresh],
+    )?;
+    let stored = read()?;
+    decode(&stored).ok_or_else(|| {
+        rusqlite::Error::InvalidColumnType(0, key.to_string(), rusqlite::types::Type::Text)
+    })
+}
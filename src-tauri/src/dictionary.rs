@@ -0,0 +1,248 @@
+// DLP Dictionaries
+//
+// "dictionary" patterns match large word lists (customer names, project
+// codenames, ...) that would be unusable as one `(?i)` regex per keyword.
+// Entries live in `dlp_dictionary_entries` instead of the `patterns` column
+// and each dictionary is compiled into a single Aho-Corasick automaton, with
+// per-pattern case sensitivity and whole-word options. Lists are imported,
+// replaced and diffed against files one entry per line.
+
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Entries listed per side of a diff
+const DIFF_SAMPLE_SIZE: usize = 20;
+
+/// Matching options of a dictionary pattern
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DictionaryOptions {
+    /// Match case exactly (default: ASCII case-insensitive)
+    pub case_sensitive: bool,
+    /// Only match entries that are not part of a longer word (default: on)
+    pub whole_words: bool,
+}
+
+impl Default for DictionaryOptions {
+    fn default() -> Self {
+        Self {
+            case_sensitive: false,
+            whole_words: true,
+        }
+    }
+}
+
+/// Compiled dictionary of one pattern
+pub struct Dictionary {
+    automaton: AhoCorasick,
+    options: DictionaryOptions,
+    size: usize,
+}
+
+impl Dictionary {
+    /// Compile entries into one automaton (None if there are no entries)
+    pub fn build(entries: &[String], options: DictionaryOptions) -> Result<Option<Self>, String> {
+        let entries: Vec<&str> = entries.iter().map(|e| e.trim()).filter(|e| !e.is_empty()).collect();
+        if entries.is_empty() {
+            return Ok(None);
+        }
+
+        let automaton = AhoCorasickBuilder::new()
+            .ascii_case_insensitive(!options.case_sensitive)
+            .match_kind(MatchKind::Standard)
+            .build(&entries)
+            .map_err(|e| format!("Failed to build dictionary: {}", e))?;
+
+        Ok(Some(Self {
+            automaton,
+            options,
+            size: entries.len(),
+        }))
+    }
+
+    /// Number of entries compiled
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Spans of every entry found in text (overlapping; the engine resolves overlaps)
+    pub fn find_spans(&self, text: &str) -> Vec<(usize, usize)> {
+        self.automaton
+            .find_overlapping_iter(text)
+            .map(|m| (m.start(), m.end()))
+            .filter(|&(start, end)| !self.options.whole_words || is_whole_word(text, start, end))
+            .collect()
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Check that a span is not part of a longer word (same boundaries as regex `\b`
+/// for entries that start and end with word characters)
+fn is_whole_word(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    let first = text[start..end].chars().next();
+    let last = text[start..end].chars().next_back();
+
+    let open = !(before.is_some_and(is_word_char) && first.is_some_and(is_word_char));
+    let close = !(after.is_some_and(is_word_char) && last.is_some_and(is_word_char));
+    open && close
+}
+
+/// Entries of a list file: one per line, blank lines and `#` comments skipped,
+/// duplicates dropped (first occurrence kept)
+pub fn parse_entries(content: &str) -> Vec<String> {
+    let mut seen: HashSet<&str> = HashSet::new();
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|line| seen.insert(line))
+        .map(str::to_string)
+        .collect()
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/// Difference between a dictionary and a list, shown before replacing it
+#[derive(Debug, Default, Serialize)]
+pub struct DictionaryDiff {
+    pub added: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+    /// First few entries on each side
+    pub added_sample: Vec<String>,
+    pub removed_sample: Vec<String>,
+}
+
+/// Compare the current entries with a new list
+pub fn diff_entries(current: &[String], new: &[String]) -> DictionaryDiff {
+    let current_set: HashSet<&str> = current.iter().map(String::as_str).collect();
+    let new_set: HashSet<&str> = new.iter().map(String::as_str).collect();
+
+    let added: Vec<&String> = new.iter().filter(|e| !current_set.contains(e.as_str())).collect();
+    let removed: Vec<&String> = current.iter().filter(|e| !new_set.contains(e.as_str())).collect();
+
+    DictionaryDiff {
+        added: added.len(),
+        removed: removed.len(),
+        unchanged: new.len() - added.len(),
+        added_sample: added.iter().take(DIFF_SAMPLE_SIZE).map(|e| e.to_string()).collect(),
+        removed_sample: removed.iter().take(DIFF_SAMPLE_SIZE).map(|e| e.to_string()).collect(),
+    }
+}
+
+/// Entries of a dictionary pattern, in insertion order
+pub fn load_entries(conn: &Connection, pattern_id: i64) -> Result<Vec<String>, rusqlite::Error> {
+    let mut stmt = conn.prepare("SELECT entry FROM dlp_dictionary_entries WHERE pattern_id = ?1 ORDER BY rowid")?;
+    let rows = stmt.query_map(rusqlite::params![pattern_id], |row| row.get(0))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Replace every entry of a dictionary pattern
+/// Runs in the caller's transaction if there is one
+pub fn replace_entries(conn: &Connection, pattern_id: i64, entries: &[String]) -> Result<(), rusqlite::Error> {
+    let tx = conn.is_autocommit().then(|| conn.unchecked_transaction()).transpose()?;
+    conn.execute(
+        "DELETE FROM dlp_dictionary_entries WHERE pattern_id = ?1",
+        rusqlite::params![pattern_id],
+    )?;
+    {
+        let mut stmt = conn.prepare("INSERT INTO dlp_dictionary_entries (pattern_id, entry) VALUES (?1, ?2)")?;
+        for entry in entries {
+            stmt.execute(rusqlite::params![pattern_id, entry])?;
+        }
+    }
+    match tx {
+        Some(tx) => tx.commit(),
+        None => Ok(()),
+    }
+}
+
+/// Remove the entries of a pattern (when it is deleted or stops being a dictionary)
+pub fn delete_entries(conn: &Connection, pattern_id: i64) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM dlp_dictionary_entries WHERE pattern_id = ?1",
+        rusqlite::params![pattern_id],
+    )?;
+    Ok(())
+}
+
+/// Read a list file
+pub fn read_entries_file(path: &str) -> Result<Vec<String>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    Ok(parse_entries(&content))
+}
+
+/// Import a list file into a dictionary pattern, replacing its entries or
+/// adding to them. Returns what changed
+pub fn import_file(conn: &Connection, pattern_id: i64, path: &str, replace: bool) -> Result<DictionaryDiff, String> {
+    let file_entries = read_entries_file(path)?;
+    let current = load_entries(conn, pattern_id).map_err(|e| e.to_string())?;
+
+    let new_entries: Vec<String> = if replace {
+        file_entries
+    } else {
+        let mut merged = current.clone();
+        let mut seen: HashSet<String> = current.iter().cloned().collect();
+        merged.extend(file_entries.into_iter().filter(|e| seen.insert(e.clone())));
+        merged
+    };
+
+    let diff = diff_entries(&current, &new_entries);
+    replace_entries(conn, pattern_id, &new_entries).map_err(|e| e.to_string())?;
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(list: &[&str]) -> Vec<String> {
+        list.iter().map(|e| e.to_string()).collect()
+    }
+
+    fn found<'a>(dictionary: &Dictionary, text: &'a str) -> Vec<&'a str> {
+        dictionary.find_spans(text).iter().map(|&(s, e)| &text[s..e]).collect()
+    }
+
+    #[test]
+    fn test_whole_words_and_case() {
+        let list = entries(&["Acme", "Project Falcon", "C++"]);
+        let dictionary = Dictionary::build(&list, DictionaryOptions::default()).unwrap().unwrap();
+        assert_eq!(dictionary.size(), 3);
+        assert_eq!(
+            found(&dictionary, "ACME ships project falcon in C++; acmebank and Acme_ are not matches"),
+            vec!["ACME", "project falcon", "C++"]
+        );
+
+        let options = DictionaryOptions {
+            case_sensitive: true,
+            whole_words: false,
+        };
+        let dictionary = Dictionary::build(&list, options).unwrap().unwrap();
+        assert_eq!(found(&dictionary, "ACME AcmeBank"), vec!["Acme"]);
+    }
+
+    #[test]
+    fn test_empty_dictionary() {
+        assert!(Dictionary::build(&entries(&["", "  "]), DictionaryOptions::default()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_and_diff() {
+        let list = parse_entries("# customers\nAcme\n\nGlobex\nAcme\n  Initech  \n");
+        assert_eq!(list, entries(&["Acme", "Globex", "Initech"]));
+
+        let diff = diff_entries(&list, &entries(&["Acme", "Initech", "Umbrella"]));
+        assert_eq!((diff.added, diff.removed, diff.unchanged), (1, 1, 2));
+        assert_eq!(diff.added_sample, vec!["Umbrella"]);
+        assert_eq!(diff.removed_sample, vec!["Globex"]);
+    }
+}
@@ -0,0 +1,599 @@
+// Single-pass DLP Matching Engine
+//
+// Scans a string once for every enabled pattern instead of looping over each
+// pattern and regex separately:
+// - Keyword patterns are compiled into one Aho-Corasick automaton
+// - Regex patterns are compiled into one RegexSet, used as a prefilter so only
+//   regexes that actually match are run again to find their positions
+// - Dictionary patterns bring their own automaton (see `dictionary.rs`)
+// Overlapping matches are resolved deterministically and the caller rewrites
+// the text in a single pass (see `rewrite_matches`). Overlaps are resolved per
+// pattern action, so e.g. a log-only match never hides a redact match.
+// `find_matches_with_views` also scans normalized and decoded views of the text
+// (see `dlp_views.rs`) and maps their matches back to the original characters.
+
+use crate::dlp::{CompiledDlpPattern, DlpPatternAction};
+use crate::dlp_views::{text_views, ViewKind};
+use crate::pattern_utils::{check_match_context, count_unique_chars, find_match_spans, passes_match_checks, ContextCheck};
+use aho_corasick::{AhoCorasick, AhoCorasickBuilder, MatchKind};
+use regex::RegexSet;
+use std::collections::{BTreeMap, HashSet};
+
+/// A match that survived all filters and overlap resolution
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EngineMatch {
+    pub start: usize,
+    pub end: usize,
+    /// Index into the pattern list the engine was built from
+    pub pattern_index: usize,
+    /// View of the text the match was found in (None = the text itself)
+    pub view: Option<ViewKind>,
+}
+
+/// Pre-built matcher for a fixed list of compiled DLP patterns
+pub struct DlpMatchEngine {
+    /// Automaton over all ASCII keywords (ASCII case-insensitive)
+    keywords: Option<AhoCorasick>,
+    /// Automaton pattern id -> (pattern index, regex index within the pattern)
+    keyword_owners: Vec<(usize, usize)>,
+    /// Prefilter over all regexes (and non-ASCII keywords)
+    regex_set: Option<RegexSet>,
+    /// RegexSet index -> (pattern index, regex index within the pattern)
+    regex_owners: Vec<(usize, usize)>,
+}
+
+impl DlpMatchEngine {
+    /// Build the engine for a list of compiled patterns
+    pub fn build(patterns: &[CompiledDlpPattern]) -> Self {
+        let mut keyword_strings: Vec<&str> = Vec::new();
+        let mut keyword_owners: Vec<(usize, usize)> = Vec::new();
+        let mut regex_sources: Vec<&str> = Vec::new();
+        let mut regex_owners: Vec<(usize, usize)> = Vec::new();
+
+        for (pattern_idx, pattern) in patterns.iter().enumerate() {
+            for (regex_idx, regex) in pattern.regexes.iter().enumerate() {
+                // Keywords are matched case-insensitively; Aho-Corasick only folds
+                // ASCII, so non-ASCII keywords keep using their (?i) regex
+                let keyword = pattern
+                    .keywords
+                    .get(regex_idx)
+                    .filter(|k| pattern.pattern_type == "keyword" && k.is_ascii());
+
+                match keyword {
+                    Some(k) => {
+                        keyword_strings.push(k.as_str());
+                        keyword_owners.push((pattern_idx, regex_idx));
+                    }
+                    None => {
+                        regex_sources.push(regex.as_str());
+                        regex_owners.push((pattern_idx, regex_idx));
+                    }
+                }
+            }
+        }
+
+        let keywords = if keyword_strings.is_empty() {
+            None
+        } else {
+            match AhoCorasickBuilder::new()
+                .ascii_case_insensitive(true)
+                .match_kind(MatchKind::Standard)
+                .build(&keyword_strings)
+            {
+                Ok(ac) => Some(ac),
+                Err(e) => {
+                    eprintln!("[DLP] Error building keyword automaton: {}", e);
+                    // Fall back to the per-keyword regexes
+                    regex_sources.extend(
+                        keyword_owners
+                            .iter()
+                            .map(|&(p, r)| patterns[p].regexes[r].as_str()),
+                    );
+                    regex_owners.append(&mut keyword_owners);
+                    None
+                }
+            }
+        };
+
+        // If the set cannot be built (e.g. size limits) every regex is run directly
+        let regex_set = if regex_sources.is_empty() {
+            None
+        } else {
+            match RegexSet::new(&regex_sources) {
+                Ok(set) => Some(set),
+                Err(e) => {
+                    eprintln!("[DLP] Error building regex set, falling back to per-regex scan: {}", e);
+                    None
+                }
+            }
+        };
+
+        Self {
+            keywords,
+            keyword_owners,
+            regex_set,
+            regex_owners,
+        }
+    }
+
+    /// Find all matches in `text` for the patterns the engine was built from
+    /// Applies negative context, min_unique_chars and min_occurrences per pattern,
+    /// then resolves overlaps among patterns with the same action. Returned matches
+    /// are sorted; matches of one action never overlap each other.
+    pub fn find_matches(&self, patterns: &[CompiledDlpPattern], text: &str) -> Vec<EngineMatch> {
+        resolve_per_action(patterns, self.find_candidates(patterns, text))
+    }
+
+    /// Matches of every pattern that pass the pattern's own checks, overlaps unresolved
+    fn find_candidates(&self, patterns: &[CompiledDlpPattern], text: &str) -> Vec<EngineMatch> {
+        if text.is_empty() || patterns.is_empty() {
+            return Vec::new();
+        }
+
+        // Raw candidates per pattern: (start, end, regex index)
+        let mut candidates: Vec<Vec<(usize, usize, usize)>> = vec![Vec::new(); patterns.len()];
+
+        if let Some(ac) = &self.keywords {
+            for m in ac.find_overlapping_iter(text) {
+                let (pattern_idx, regex_idx) = self.keyword_owners[m.pattern().as_usize()];
+                candidates[pattern_idx].push((m.start(), m.end(), regex_idx));
+            }
+        }
+
+        // Regexes with a redact group only report the group's span
+        let mut push_regex_spans = |pattern_idx: usize, regex_idx: usize| {
+            let pattern = &patterns[pattern_idx];
+            let redact_group = pattern.redact_groups.get(regex_idx).copied().flatten();
+            for span in find_match_spans(&pattern.regexes[regex_idx], redact_group, text) {
+                candidates[pattern_idx].push((span.start, span.end, regex_idx));
+            }
+        };
+
+        match &self.regex_set {
+            Some(set) => {
+                for set_idx in set.matches(text).iter() {
+                    let (pattern_idx, regex_idx) = self.regex_owners[set_idx];
+                    push_regex_spans(pattern_idx, regex_idx);
+                }
+            }
+            None => {
+                for &(pattern_idx, regex_idx) in &self.regex_owners {
+                    push_regex_spans(pattern_idx, regex_idx);
+                }
+            }
+        }
+
+        for (pattern_idx, pattern) in patterns.iter().enumerate() {
+            if let Some(dictionary) = &pattern.dictionary {
+                for (start, end) in dictionary.find_spans(text) {
+                    candidates[pattern_idx].push((start, end, 0));
+                }
+            }
+        }
+
+        let mut accepted: Vec<EngineMatch> = Vec::new();
+
+        for (pattern_idx, spans) in candidates.into_iter().enumerate() {
+            if spans.is_empty() {
+                continue;
+            }
+            let pattern = &patterns[pattern_idx];
+
+            let mut unique_values: HashSet<&str> = HashSet::new();
+            let mut valid: Vec<EngineMatch> = Vec::new();
+
+            for (start, end, regex_idx) in spans {
+                if start == end {
+                    continue;
+                }
+                let matched = &text[start..end];
+
+                // Entropy and validator patterns: the regex only finds candidates
+                if !passes_match_checks(matched, regex_idx, &pattern.entropy_thresholds, pattern.validator) {
+                    continue;
+                }
+
+                // Check the negative and required context around this match
+                // Context = context_window chars before + match + context_window chars after
+                if check_match_context(
+                    text,
+                    start,
+                    end,
+                    pattern.context_window,
+                    &pattern.negative_regexes,
+                    pattern.negative_scope,
+                    &pattern.required_regexes,
+                ) != ContextCheck::Passed
+                {
+                    continue;
+                }
+
+                // Validate min_unique_chars
+                if pattern.min_unique_chars > 0
+                    && (count_unique_chars(matched) as i32) < pattern.min_unique_chars
+                {
+                    continue;
+                }
+
+                unique_values.insert(matched);
+                valid.push(EngineMatch {
+                    start,
+                    end,
+                    pattern_index: pattern_idx,
+                    view: None,
+                });
+            }
+
+            // Check min_occurrences threshold (counted on distinct values)
+            if (unique_values.len() as i32) < pattern.min_occurrences {
+                continue;
+            }
+
+            accepted.extend(valid);
+        }
+
+        accepted
+    }
+
+    /// Find matches in `text` and in its normalized and decoded views
+    /// A match in a view covers the original characters it came from (for base64
+    /// and hex, the whole encoded run). On equal spans the literal match wins.
+    pub fn find_matches_with_views(&self, patterns: &[CompiledDlpPattern], text: &str) -> Vec<EngineMatch> {
+        self.find_matches_excepting(patterns, text, |_| false).0
+    }
+
+    /// Like `find_matches_with_views`, but matches `is_excepted` accepts are taken
+    /// out before overlaps are resolved, so a value excepted for one pattern never
+    /// hides another pattern's match on the same characters.
+    /// Returns (matches, excepted matches), each resolved on its own.
+    pub fn find_matches_excepting(
+        &self,
+        patterns: &[CompiledDlpPattern],
+        text: &str,
+        is_excepted: impl Fn(&EngineMatch) -> bool,
+    ) -> (Vec<EngineMatch>, Vec<EngineMatch>) {
+        let mut candidates = self.find_candidates(patterns, text);
+
+        for view in &text_views(text) {
+            for m in self.find_candidates(patterns, &view.text) {
+                let (start, end) = view.original_span(m.start, m.end);
+                candidates.push(EngineMatch {
+                    start,
+                    end,
+                    pattern_index: m.pattern_index,
+                    view: Some(view.kind),
+                });
+            }
+        }
+
+        let (excepted, matches): (Vec<EngineMatch>, Vec<EngineMatch>) =
+            candidates.into_iter().partition(|m| is_excepted(m));
+        (resolve_per_action(patterns, matches), resolve_per_action(patterns, excepted))
+    }
+}
+
+/// Resolve overlaps among the matches of each action; the result is sorted
+fn resolve_per_action(patterns: &[CompiledDlpPattern], matches: Vec<EngineMatch>) -> Vec<EngineMatch> {
+    let mut by_action: BTreeMap<DlpPatternAction, Vec<EngineMatch>> = BTreeMap::new();
+    for m in matches {
+        by_action.entry(patterns[m.pattern_index].action).or_default().push(m);
+    }
+
+    let mut resolved: Vec<EngineMatch> = by_action.into_values().flat_map(resolve_overlaps).collect();
+    resolved.sort_by_key(|m| (m.start, m.end, m.pattern_index));
+    resolved
+}
+
+/// Resolve overlapping matches deterministically
+/// Earliest start wins; on equal starts the longest match wins; on equal spans the
+/// pattern listed first wins, then the match in the text itself over one in a view.
+/// Any match overlapping an already chosen one is dropped.
+pub fn resolve_overlaps(mut matches: Vec<EngineMatch>) -> Vec<EngineMatch> {
+    matches.sort_by(|a, b| {
+        a.start
+            .cmp(&b.start)
+            .then((b.end - b.start).cmp(&(a.end - a.start)))
+            .then(a.pattern_index.cmp(&b.pattern_index))
+            .then(a.view.cmp(&b.view))
+    });
+
+    let mut resolved: Vec<EngineMatch> = Vec::with_capacity(matches.len());
+    let mut last_end = 0;
+
+    for m in matches {
+        if !resolved.is_empty() && m.start < last_end {
+            continue;
+        }
+        last_end = m.end;
+        resolved.push(m);
+    }
+
+    resolved
+}
+
+/// Rewrite `text` in one pass, replacing each (sorted, non-overlapping) match with
+/// the string returned by `replacement_for`
+pub fn rewrite_matches<F>(text: &str, matches: &[EngineMatch], mut replacement_for: F) -> String
+where
+    F: FnMut(&EngineMatch, &str) -> String,
+{
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for m in matches {
+        result.push_str(&text[last..m.start]);
+        result.push_str(&replacement_for(m, &text[m.start..m.end]));
+        last = m.end;
+    }
+    result.push_str(&text[last..]);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn pattern(name: &str, pattern_type: &str, list: &[&str], min_unique_chars: i32) -> CompiledDlpPattern {
+        CompiledDlpPattern {
+            min_unique_chars,
+            ..CompiledDlpPattern::for_test(name, pattern_type, list)
+        }
+    }
+
+    fn matched_values<'a>(text: &'a str, matches: &[EngineMatch]) -> Vec<&'a str> {
+        matches.iter().map(|m| &text[m.start..m.end]).collect()
+    }
+
+    #[test]
+    fn test_overlaps_are_resolved_per_action() {
+        let mut customer = pattern("Customers", "keyword", &["acme corp"], 0);
+        customer.action = DlpPatternAction::Log;
+        let patterns = vec![customer, pattern("Hosts", "regex", &[r"corp\.internal\.[a-z]+"], 0)];
+        let engine = DlpMatchEngine::build(&patterns);
+        let text = "deploy to acme corp.internal.net";
+        let matches = engine.find_matches(&patterns, text);
+        // The log-only keyword overlaps the hostname but does not hide it
+        assert_eq!(matched_values(text, &matches), vec!["acme corp", "corp.internal.net"]);
+    }
+
+    #[test]
+    fn test_entropy_candidates_share_a_pattern() {
+        // A hex token also matches the base64 alphabet; only the hex threshold accepts it
+        let patterns = vec![pattern("Random Tokens", "entropy", &["base64", "hex"], 0)];
+        let engine = DlpMatchEngine::build(&patterns);
+        let text = "sha 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b and id src/components/settings/page";
+        let matches = engine.find_matches(&patterns, text);
+        assert_eq!(matched_values(text, &matches), vec!["9f86d081884c7d659a2feaa0c55ad015a3bf4f1b"]);
+    }
+
+    #[test]
+    fn test_keywords_are_case_insensitive() {
+        let patterns = vec![pattern("Words", "keyword", &["project falcon", "Ünïcode"], 0)];
+        let engine = DlpMatchEngine::build(&patterns);
+        let text = "about PROJECT Falcon and üNÏCODE";
+        let matches = engine.find_matches(&patterns, text);
+        assert_eq!(matched_values(text, &matches), vec!["PROJECT Falcon", "üNÏCODE"]);
+    }
+
+    #[test]
+    fn test_dictionary_matches_whole_words() {
+        use crate::dictionary::{Dictionary, DictionaryOptions};
+
+        let entries: Vec<String> = ["Globex", "Initech"].iter().map(|e| e.to_string()).collect();
+        let mut customers = pattern("Customers", "dictionary", &[], 0);
+        customers.dictionary = Dictionary::build(&entries, DictionaryOptions::default())
+            .unwrap()
+            .map(std::sync::Arc::new);
+        let patterns = vec![customers];
+        let engine = DlpMatchEngine::build(&patterns);
+        let text = "invoice for GLOBEX, not globexcorp, cc initech";
+        let matches = engine.find_matches(&patterns, text);
+        assert_eq!(matched_values(text, &matches), vec!["GLOBEX", "initech"]);
+    }
+
+    #[test]
+    fn test_overlaps_prefer_earliest_then_longest() {
+        let patterns = vec![
+            pattern("Short", "regex", &[r"sk-[a-z]{4}"], 0),
+            pattern("Long", "regex", &[r"sk-[a-z]{4,}"], 0),
+            pattern("Inner", "keyword", &["abcd"], 0),
+        ];
+        let engine = DlpMatchEngine::build(&patterns);
+        let text = "key sk-abcdefgh end";
+        let matches = engine.find_matches(&patterns, text);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matched_values(text, &matches), vec!["sk-abcdefgh"]);
+        assert_eq!(matches[0].pattern_index, 1);
+    }
+
+    #[test]
+    fn test_equal_spans_prefer_first_pattern() {
+        let patterns = vec![
+            pattern("First", "keyword", &["secret"], 0),
+            pattern("Second", "regex", &[r"secret"], 0),
+        ];
+        let engine = DlpMatchEngine::build(&patterns);
+        let matches = engine.find_matches(&patterns, "a secret b");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].pattern_index, 0);
+    }
+
+    #[test]
+    fn test_filters_apply_per_match() {
+        let mut api = pattern("API", "regex", &[r"sk-[a-zA-Z0-9]{20,}"], 10);
+        api.negative_regexes = vec![regex::Regex::new("example").unwrap()];
+        let patterns = vec![api];
+        let engine = DlpMatchEngine::build(&patterns);
+        let text = "example sk-aaaaaaaaaaaaaaaaaaaaaaaa; real sk-Ab3dEf6hIj9kLm2nOp5qRs8t ok";
+        let matches = engine.find_matches(&patterns, text);
+        // first is excluded by negative context, and would also fail min_unique_chars
+        assert_eq!(matched_values(text, &matches), vec!["sk-Ab3dEf6hIj9kLm2nOp5qRs8t"]);
+    }
+
+    #[test]
+    fn test_required_context_uses_pattern_window() {
+        let mut hex = pattern("Hex", "regex", &[r"\b[0-9a-f]{16}\b"], 0);
+        hex.required_regexes = vec![regex::Regex::new("(?i)token").unwrap()];
+        hex.context_window = 8;
+        let patterns = vec![hex];
+        let engine = DlpMatchEngine::build(&patterns);
+        let text = "token: 9f86d081884c7d65, commit 0b822c1a3bf4f1b2";
+        let matches = engine.find_matches(&patterns, text);
+        assert_eq!(matched_values(text, &matches), vec!["9f86d081884c7d65"]);
+    }
+
+    #[test]
+    fn test_rewrite_matches_single_pass() {
+        let patterns = vec![pattern("Words", "keyword", &["alpha", "beta"], 0)];
+        let engine = DlpMatchEngine::build(&patterns);
+        let text = "alpha, beta and Alpha";
+        let matches = engine.find_matches(&patterns, text);
+        let rewritten = rewrite_matches(text, &matches, |_, v| "#".repeat(v.len()));
+        assert_eq!(rewritten, "#####, #### and #####");
+    }
+
+    /// Previous approach: every regex of every pattern over the whole text,
+    /// followed by a full-text replace per match
+    fn naive_redact(text: &str, patterns: &[CompiledDlpPattern]) -> String {
+        let mut result = text.to_string();
+        for pattern in patterns {
+            let mut found: Vec<String> = Vec::new();
+            for (regex_idx, regex) in pattern.regexes.iter().enumerate() {
+                for m in regex.find_iter(&result) {
+                    if check_match_context(
+                        &result,
+                        m.start(),
+                        m.end(),
+                        pattern.context_window,
+                        &pattern.negative_regexes,
+                        pattern.negative_scope,
+                        &pattern.required_regexes,
+                    ) != ContextCheck::Passed
+                    {
+                        continue;
+                    }
+                    if !passes_match_checks(m.as_str(), regex_idx, &pattern.entropy_thresholds, pattern.validator) {
+                        continue;
+                    }
+                    if (count_unique_chars(m.as_str()) as i32) < pattern.min_unique_chars {
+                        continue;
+                    }
+                    found.push(m.as_str().to_string());
+                }
+            }
+            for value in found {
+                result = result.replace(&value, &"x".repeat(value.len()));
+            }
+        }
+        result
+    }
+
+    /// Builds a request body shaped like a long Claude Code session: a large
+    /// system prompt, many tool_use/tool_result turns with file contents and
+    /// git logs, and a handful of secrets scattered through the history
+    fn realistic_claude_code_body() -> String {
+        let source_file = "use std::collections::HashMap;\n\
+            fn handle(req: Request) -> Response {\n    let cfg = load_config();\n    \
+            let client = Client::new(&cfg.endpoint);\n    client.send(req).unwrap()\n}\n"
+            .repeat(40);
+        let mut messages = Vec::new();
+        for turn in 0..60 {
+            let mut tool_output = source_file.clone();
+            if turn % 5 == 0 {
+                for commit in 0..10 {
+                    tool_output.push_str(&format!(
+                        "commit {:040x}\nAuthor: Dev {} <dev{}@example.com>\n\n    Fix Northwind sync for Blue Harbor\n\n",
+                        turn * 10 + commit,
+                        commit,
+                        commit
+                    ));
+                }
+            }
+            if turn % 15 == 0 {
+                tool_output.push_str(&format!(
+                    " export OPENAI_API_KEY=sk-{:0>24}Zq9 AKIA{:0>12}WXYZ",
+                    turn, turn
+                ));
+            }
+            messages.push(serde_json::json!({
+                "role": "user",
+                "content": [
+                    {"type": "tool_result", "tool_use_id": format!("toolu_{}", turn),
+                     "content": tool_output},
+                    {"type": "text", "text": format!("Turn {}: please refactor the handler", turn)}
+                ]
+            }));
+            messages.push(serde_json::json!({
+                "role": "assistant",
+                "content": [{"type": "tool_use", "id": format!("toolu_{}", turn + 1),
+                    "name": "Read", "input": {"file_path": "src/handler.rs"}}]
+            }));
+        }
+        serde_json::json!({
+            "model": "claude-sonnet-4-5",
+            "max_tokens": 32000,
+            "stream": true,
+            "system": "You are an interactive CLI tool that helps users with software engineering tasks. ".repeat(200),
+            "messages": messages
+        })
+        .to_string()
+    }
+
+    fn benchmark_patterns() -> Vec<CompiledDlpPattern> {
+        let mut patterns: Vec<CompiledDlpPattern> = crate::builtin_patterns::get_builtin_patterns()
+            .iter()
+            .map(CompiledDlpPattern::builtin_for_test)
+            .collect();
+        // A typical customer / codename list
+        let mut keywords: Vec<String> = (0..36).map(|i| format!("customer-{:03}", i)).collect();
+        keywords.extend(["project falcon", "project osprey", "blue harbor", "northwind"].map(String::from));
+        let keywords: Vec<&str> = keywords.iter().map(|k| k.as_str()).collect();
+        patterns.push(pattern("Internal Names", "keyword", &keywords, 0));
+        patterns.push(pattern(
+            "Emails",
+            "regex",
+            &[r"[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}"],
+            0,
+        ));
+        patterns
+    }
+
+    /// Compares the single-pass engine against the previous per-pattern loop
+    /// Run with: cargo test --release dlp_engine::tests::bench -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_realistic_claude_code_request() {
+        let body = realistic_claude_code_body();
+        let patterns = benchmark_patterns();
+        let engine = DlpMatchEngine::build(&patterns);
+        let iterations = 50;
+
+        let start = Instant::now();
+        let mut naive_len = 0;
+        for _ in 0..iterations {
+            naive_len = naive_redact(&body, &patterns).len();
+        }
+        let naive = start.elapsed();
+
+        let start = Instant::now();
+        let mut engine_matches = 0;
+        for _ in 0..iterations {
+            let matches = engine.find_matches(&patterns, &body);
+            engine_matches = matches.len();
+            let rewritten = rewrite_matches(&body, &matches, |_, v| "x".repeat(v.len()));
+            assert_eq!(rewritten.len(), naive_len);
+        }
+        let single_pass = start.elapsed();
+
+        println!(
+            "DLP benchmark: body {} KB, {} pattern groups, {} matches",
+            body.len() / 1024,
+            patterns.len(),
+            engine_matches
+        );
+        println!("  per-pattern loop: {:?}/request", naive / iterations);
+        println!("  single-pass:      {:?}/request", single_pass / iterations);
+    }
+}
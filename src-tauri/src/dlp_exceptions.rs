@@ -0,0 +1,309 @@
+// DLP Exceptions (false positives)
+//
+// An exception lets a detected value through without weakening the pattern.
+// Each row of `dlp_exceptions` describes what it covers, and every condition
+// that is set must hold:
+// - pattern_name: pattern group that fired (NULL = any pattern)
+// - value_hash: fingerprint of the exact value, the same salted HMAC as
+//   dlp_detections.value_fingerprint, so the value itself is never stored
+// - value_regex: regex the whole value must match
+// - file_path: path of the file the text came from, with `*` / `?` wildcards
+//   (only Cursor hooks know which file text was read from)
+// - backend: backend name as logged ("claude", "cursor-hooks", ...)
+// - expires_at: the exception no longer applies after this time
+// One of value_hash, value_regex and file_path is required, so an exception
+// never silences a whole pattern (that is what backend pattern scopes are for).
+//
+// Covered values are still recorded, with the exception's id, but not redacted,
+// blocked or notified. Known secrets are never covered. Exceptions are revoked,
+// not deleted, so who allowed what and when stays visible.
+
+use crate::database::open_connection;
+use crate::detection_storage::DetectionValueEncoder;
+use crate::known_secrets::wildcard_match;
+use chrono::{DateTime, NaiveDate, Utc};
+use regex::Regex;
+use rusqlite::Connection;
+
+/// Where a scanned text comes from
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExceptionContext<'a> {
+    /// Backend name as logged with the request
+    pub backend: &'a str,
+    /// File the text was read from, if known
+    pub file_path: Option<&'a str>,
+}
+
+/// Conditions of one exception, as stored
+#[derive(Clone, Debug, Default)]
+pub struct DlpExceptionRule {
+    pub pattern_name: Option<String>,
+    pub value_hash: Option<String>,
+    pub value_regex: Option<String>,
+    pub file_path: Option<String>,
+    pub backend: Option<String>,
+    /// RFC 3339
+    pub expires_at: Option<String>,
+}
+
+impl DlpExceptionRule {
+    /// Check the rule is narrow enough and its regex and expiry parse
+    pub fn validate(&self) -> Result<(), String> {
+        if self.value_hash.is_none() && self.value_regex.is_none() && self.file_path.is_none() {
+            return Err("An exception needs a value, a value regex or a file path".to_string());
+        }
+        self.compile(0).map(|_| ())
+    }
+
+    fn compile(&self, id: i64) -> Result<CompiledException, String> {
+        let value_regex = match &self.value_regex {
+            Some(regex) => Some(
+                Regex::new(&format!("^(?:{})$", regex)).map_err(|e| format!("Invalid value regex: {}", e))?,
+            ),
+            None => None,
+        };
+        let expires_at = match &self.expires_at {
+            Some(expiry) => Some(
+                DateTime::parse_from_rfc3339(expiry)
+                    .map_err(|e| format!("Invalid expiry date: {}", e))?
+                    .with_timezone(&Utc),
+            ),
+            None => None,
+        };
+        Ok(CompiledException {
+            id,
+            pattern_name: self.pattern_name.clone(),
+            value_hash: self.value_hash.clone(),
+            value_regex,
+            file_path: self.file_path.clone(),
+            backend: self.backend.clone(),
+            expires_at,
+        })
+    }
+}
+
+/// Normalize an expiry given as RFC 3339 or as a date ("2026-12-31" lasts
+/// through the end of that day, UTC)
+pub fn parse_expiry(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let end_of_day = date.and_hms_opt(23, 59, 59).unwrap().and_utc();
+        return Ok(end_of_day.to_rfc3339());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc).to_rfc3339())
+        .map_err(|_| format!("Invalid expiry date '{}' (expected YYYY-MM-DD)", value))
+}
+
+struct CompiledException {
+    id: i64,
+    pattern_name: Option<String>,
+    value_hash: Option<String>,
+    value_regex: Option<Regex>,
+    file_path: Option<String>,
+    backend: Option<String>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+/// Active exceptions, loaded with each pattern snapshot
+#[derive(Default)]
+pub struct DlpExceptionSet {
+    exceptions: Vec<CompiledException>,
+    /// Fingerprints values for `value_hash` (None if no exception uses one)
+    encoder: Option<DetectionValueEncoder>,
+}
+
+impl DlpExceptionSet {
+    pub fn load() -> Self {
+        let Ok(conn) = open_connection() else {
+            return Self::default();
+        };
+        Self::load_with(&conn).unwrap_or_else(|e| {
+            eprintln!("[DLP] Failed to load exceptions: {}", e);
+            Self::default()
+        })
+    }
+
+    /// Load every exception that is not revoked (expired ones are skipped at match time)
+    pub fn load_with(conn: &Connection) -> Result<Self, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT id, pattern_name, value_hash, value_regex, file_path, backend, expires_at
+             FROM dlp_exceptions WHERE revoked_at IS NULL ORDER BY id",
+        )?;
+        let rows: Vec<(i64, DlpExceptionRule)> = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get(0)?,
+                    DlpExceptionRule {
+                        pattern_name: row.get(1)?,
+                        value_hash: row.get(2)?,
+                        value_regex: row.get(3)?,
+                        file_path: row.get(4)?,
+                        backend: row.get(5)?,
+                        expires_at: row.get(6)?,
+                    },
+                ))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        let mut exceptions = Vec::new();
+        for (id, rule) in rows {
+            match rule.compile(id) {
+                Ok(exception) => exceptions.push(exception),
+                Err(e) => eprintln!("[DLP] Skipping exception {}: {}", id, e),
+            }
+        }
+
+        let encoder = if exceptions.iter().any(|e| e.value_hash.is_some()) {
+            Some(DetectionValueEncoder::load_with(conn)?)
+        } else {
+            None
+        };
+        Ok(Self { exceptions, encoder })
+    }
+
+    /// In-memory set of the given rules, numbered from 1 (tests only; no value hashes)
+    #[cfg(test)]
+    pub fn from_rules(rules: &[DlpExceptionRule]) -> Self {
+        Self {
+            exceptions: rules.iter().zip(1..).map(|(rule, id)| rule.compile(id).unwrap()).collect(),
+            encoder: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.exceptions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exceptions.is_empty()
+    }
+
+    /// Id of the first exception covering a value found by a pattern
+    pub fn find(&self, pattern_name: &str, value: &str, context: &ExceptionContext) -> Option<i64> {
+        if self.exceptions.is_empty() {
+            return None;
+        }
+
+        let now = Utc::now();
+        let mut fingerprint: Option<String> = None;
+        self.exceptions
+            .iter()
+            .find(|e| {
+                if e.expires_at.is_some_and(|t| t <= now)
+                    || e.pattern_name.as_ref().is_some_and(|p| p != pattern_name)
+                    || e.backend.as_ref().is_some_and(|b| b != context.backend)
+                    || e.value_regex.as_ref().is_some_and(|r| !r.is_match(value))
+                {
+                    return false;
+                }
+                if let Some(path) = &e.file_path {
+                    if !context.file_path.is_some_and(|f| wildcard_match(path, f)) {
+                        return false;
+                    }
+                }
+                match (&e.value_hash, &self.encoder) {
+                    (Some(hash), Some(encoder)) => {
+                        fingerprint.get_or_insert_with(|| encoder.fingerprint(value)) == hash
+                    }
+                    (Some(_), None) => false,
+                    (None, _) => true,
+                }
+            })
+            .map(|e| e.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    fn test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        Database::create_schema(&conn).unwrap();
+        conn
+    }
+
+    fn insert(conn: &Connection, rule: DlpExceptionRule, revoked: bool) {
+        conn.execute(
+            "INSERT INTO dlp_exceptions (pattern_name, value_hash, value_regex, file_path, backend, expires_at, created_at, revoked_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, '2026-01-01T00:00:00+00:00', ?7)",
+            rusqlite::params![
+                rule.pattern_name,
+                rule.value_hash,
+                rule.value_regex,
+                rule.file_path,
+                rule.backend,
+                rule.expires_at,
+                revoked.then_some("2026-01-02T00:00:00+00:00"),
+            ],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_exception_conditions_must_all_hold() {
+        let conn = test_db();
+        let fingerprint = DetectionValueEncoder::load_with(&conn).unwrap().fingerprint("sk-test0001");
+
+        // 1: this exact value, Emails pattern only
+        insert(&conn, DlpExceptionRule {
+            pattern_name: Some("Emails".to_string()),
+            value_hash: Some(fingerprint.clone()),
+            ..Default::default()
+        }, false);
+        // 2: this exact value for any pattern, Cursor only
+        insert(&conn, DlpExceptionRule {
+            value_hash: Some(fingerprint),
+            backend: Some("cursor-hooks".to_string()),
+            ..Default::default()
+        }, false);
+        // 3: fixtures, until it expired
+        insert(&conn, DlpExceptionRule {
+            file_path: Some("*/fixtures/*".to_string()),
+            expires_at: Some("2020-01-01T00:00:00+00:00".to_string()),
+            ..Default::default()
+        }, false);
+        // 4: example keys, revoked
+        insert(&conn, DlpExceptionRule {
+            value_regex: Some("sk-example.*".to_string()),
+            ..Default::default()
+        }, true);
+        // 5: test keys in docs
+        insert(&conn, DlpExceptionRule {
+            value_regex: Some("sk-test[0-9]+".to_string()),
+            file_path: Some("*/docs/*.md".to_string()),
+            ..Default::default()
+        }, false);
+
+        let set = DlpExceptionSet::load_with(&conn).unwrap();
+        assert_eq!(set.len(), 4);
+
+        let claude = ExceptionContext { backend: "claude", file_path: None };
+        let cursor = ExceptionContext { backend: "cursor-hooks", file_path: Some("/repo/docs/setup.md") };
+        let fixture = ExceptionContext { backend: "cursor-hooks", file_path: Some("/repo/fixtures/keys.txt") };
+
+        assert_eq!(set.find("API Keys", "sk-test0001", &claude), None);
+        assert_eq!(set.find("Emails", "sk-test0001", &claude), Some(1));
+        assert_eq!(set.find("API Keys", "sk-test0001", &cursor), Some(2));
+        assert_eq!(set.find("API Keys", "sk-test0002", &cursor), Some(5));
+        // The regex covers the whole value, not a part of it
+        assert_eq!(set.find("API Keys", "sk-test0002x", &cursor), None);
+        assert_eq!(set.find("API Keys", "sk-example1", &fixture), None);
+    }
+
+    #[test]
+    fn test_rule_validation_and_expiry() {
+        assert!(DlpExceptionRule { backend: Some("claude".to_string()), ..Default::default() }
+            .validate()
+            .is_err());
+        assert!(DlpExceptionRule { value_regex: Some("sk-(".to_string()), ..Default::default() }
+            .validate()
+            .is_err());
+        assert_eq!(parse_expiry("2026-12-31").unwrap(), "2026-12-31T23:59:59+00:00");
+        assert_eq!(parse_expiry("2026-12-31T10:00:00+02:00").unwrap(), "2026-12-31T08:00:00+00:00");
+        assert!(parse_expiry("next week").is_err());
+    }
+}
@@ -7,6 +7,9 @@ use std::sync::OnceLock;
 
 pub const DEFAULT_PORT: u16 = 8008;
 
+/// Default bind address (loopback only; ::1 is also bound when available)
+pub const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1";
+
 static DB_PATH: OnceLock<String> = OnceLock::new();
 
 /// Returns the path to the database file at ~/.quilrdlpapp/proxy_requests.db
@@ -0,0 +1,755 @@
+// Streaming DLP Unredaction
+//
+// Restores DLP placeholders in streamed (SSE) responses. Upstream chunks are
+// buffered until a full SSE event is available, and the text deltas inside
+// each event are tracked per content block so a placeholder split across
+// network chunks or across several deltas is still restored. Originals are
+// re-escaped for the JSON string they land in, including the doubly-encoded
+// tool argument fragments (Claude `partial_json`, Codex/OpenAI `arguments`).
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// JSON keys whose string values are themselves fragments of JSON text
+const JSON_FRAGMENT_KEYS: &[&str] = &["partial_json", "arguments"];
+
+/// How the text of a string value should be treated during unredaction
+#[derive(Clone, Copy, PartialEq)]
+enum FieldKind {
+    /// Plain text (e.g. `text_delta.text`)
+    Text,
+    /// A fragment of JSON text (e.g. `input_json_delta.partial_json`)
+    JsonFragment,
+}
+
+/// A streamed delta string inside an SSE event
+struct DeltaField {
+    /// Identifies the content block the delta belongs to
+    key: String,
+    /// JSON pointer to the delta string inside the event
+    pointer: String,
+    kind: FieldKind,
+}
+
+/// Event used to emit held-back text when a content block ends
+struct EventTemplate {
+    other_lines: Vec<String>,
+    data_prefix: String,
+    json: Value,
+    pointer: String,
+}
+
+/// Text held back for a content block because it may be the start of a placeholder
+struct DeltaCarry {
+    text: String,
+    template: EventTemplate,
+}
+
+/// Stateful unredactor for a single streamed response
+pub struct StreamingUnredactor {
+    /// (placeholder, original) pairs for plain text, longest placeholder first
+    text_pairs: Vec<(String, String)>,
+    /// Same pairs, JSON-escaped, for JSON fragment strings
+    fragment_pairs: Vec<(String, String)>,
+    /// Bytes received that do not yet form a complete SSE event
+    pending: Vec<u8>,
+    /// Held-back delta text keyed by content block
+    carries: HashMap<String, DeltaCarry>,
+    /// Order in which carries were created (for deterministic flushing)
+    carry_order: Vec<String>,
+}
+
+impl StreamingUnredactor {
+    pub fn new(replacements: &HashMap<String, String>) -> Self {
+        let mut text_pairs: Vec<(String, String)> = replacements
+            .iter()
+            .filter(|(placeholder, _)| !placeholder.is_empty())
+            .map(|(placeholder, original)| (placeholder.clone(), original.clone()))
+            .collect();
+        text_pairs.sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+
+        let fragment_pairs = text_pairs
+            .iter()
+            .map(|(placeholder, original)| (json_escape(placeholder), json_escape(original)))
+            .collect();
+
+        Self {
+            text_pairs,
+            fragment_pairs,
+            pending: Vec::new(),
+            carries: HashMap::new(),
+            carry_order: Vec::new(),
+        }
+    }
+
+    /// Feed an upstream chunk, returning the bytes that can be forwarded now
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<u8> {
+        if self.text_pairs.is_empty() {
+            return chunk.to_vec();
+        }
+
+        self.pending.extend_from_slice(chunk);
+
+        let mut output = String::new();
+        while let Some(end) = find_event_end(&self.pending) {
+            let event_bytes: Vec<u8> = self.pending.drain(..end).collect();
+            let event = String::from_utf8_lossy(&event_bytes).to_string();
+            output.push_str(&self.process_event(&event));
+        }
+        output.into_bytes()
+    }
+
+    /// Flush everything still buffered at the end of the stream
+    pub fn finish(&mut self) -> Vec<u8> {
+        if self.text_pairs.is_empty() {
+            return Vec::new();
+        }
+
+        let mut output = String::new();
+        let keys: Vec<String> = self.carry_order.clone();
+        for key in keys {
+            output.push_str(&self.flush_carry(&key));
+        }
+
+        if !self.pending.is_empty() {
+            let rest = String::from_utf8_lossy(&self.pending).to_string();
+            self.pending.clear();
+            output.push_str(&replace_all(&rest, &self.text_pairs).0);
+        }
+        output.into_bytes()
+    }
+
+    /// Process one complete SSE event (including its terminating blank line)
+    fn process_event(&mut self, event: &str) -> String {
+        let lines: Vec<&str> = event.lines().collect();
+        let data_lines: Vec<usize> = lines
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| l.starts_with("data:"))
+            .map(|(i, _)| i)
+            .collect();
+
+        // Only single-line JSON data events are rewritten structurally
+        let parsed = if data_lines.len() == 1 {
+            let line = lines[data_lines[0]];
+            let (prefix, payload) = split_data_line(line);
+            serde_json::from_str::<Value>(payload)
+                .ok()
+                .filter(|v| v.is_object())
+                .map(|v| (prefix.to_string(), v))
+        } else {
+            None
+        };
+
+        let (data_prefix, mut json) = match parsed {
+            Some(p) => p,
+            None => {
+                // "[DONE]" and other non-JSON payloads end every open block
+                let mut output = String::new();
+                if lines.iter().any(|l| split_data_line(l).1.trim() == "[DONE]") {
+                    let keys: Vec<String> = self.carry_order.clone();
+                    for key in keys {
+                        output.push_str(&self.flush_carry(&key));
+                    }
+                }
+                output.push_str(&replace_all(event, &self.text_pairs).0);
+                return output;
+            }
+        };
+
+        let data_index = data_lines[0];
+        let other_lines: Vec<String> = lines
+            .iter()
+            .enumerate()
+            .filter(|(i, l)| *i != data_index && !l.trim().is_empty())
+            .map(|(_, l)| l.to_string())
+            .collect();
+
+        let fields = delta_fields(&json);
+        let finished = finished_keys(&json, &self.carry_order);
+        let mut output = String::new();
+
+        // Carries of blocks ending here that get no delta in this event are
+        // emitted as their own event, ahead of the ending event
+        for key in &finished {
+            if !fields.iter().any(|f| &f.key == key) {
+                output.push_str(&self.flush_carry(key));
+            }
+        }
+
+        let mut changed = false;
+
+        // Unredact every complete string outside of the delta fields
+        let delta_pointers: Vec<&str> = fields.iter().map(|f| f.pointer.as_str()).collect();
+        changed |= self.unredact_value(&mut json, "", None, &delta_pointers);
+
+        for field in &fields {
+            let delta = match json.pointer(&field.pointer).and_then(|v| v.as_str()) {
+                Some(s) => s.to_string(),
+                None => continue,
+            };
+            let is_final = finished.contains(&field.key);
+            let carried = self
+                .carries
+                .remove(&field.key)
+                .map(|c| c.text)
+                .unwrap_or_default();
+            self.carry_order.retain(|k| k != &field.key);
+
+            let combined = format!("{}{}", carried, delta);
+            let pairs = match field.kind {
+                FieldKind::Text => &self.text_pairs,
+                FieldKind::JsonFragment => &self.fragment_pairs,
+            };
+            let (replaced, safe_from) = replace_all(&combined, pairs);
+            let hold = if is_final {
+                0
+            } else {
+                held_suffix_len(&replaced[safe_from..], pairs)
+            };
+            let emit = replaced[..replaced.len() - hold].to_string();
+            let held = replaced[replaced.len() - hold..].to_string();
+
+            if hold > 0 {
+                let template = EventTemplate {
+                    other_lines: other_lines.clone(),
+                    data_prefix: data_prefix.clone(),
+                    json: template_json(&json, field, &fields),
+                    pointer: field.pointer.clone(),
+                };
+                self.carries.insert(field.key.clone(), DeltaCarry { text: held, template });
+                self.carry_order.push(field.key.clone());
+            }
+
+            if emit != delta {
+                if let Some(slot) = json.pointer_mut(&field.pointer) {
+                    *slot = Value::String(emit);
+                }
+                changed = true;
+            }
+        }
+
+        // A single-field delta event whose text is entirely held back is dropped
+        let all_empty = !fields.is_empty()
+            && fields.iter().all(|f| {
+                json.pointer(&f.pointer)
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.is_empty())
+                    .unwrap_or(false)
+            });
+        if changed && all_empty && fields.len() == 1 {
+            return output;
+        }
+
+        if changed {
+            output.push_str(&render_event(&other_lines, &data_prefix, &json));
+        } else {
+            output.push_str(event);
+        }
+        output
+    }
+
+    /// Emit the held-back text of a content block as a standalone event
+    fn flush_carry(&mut self, key: &str) -> String {
+        self.carry_order.retain(|k| k != key);
+        let carry = match self.carries.remove(key) {
+            Some(c) => c,
+            None => return String::new(),
+        };
+        if carry.text.is_empty() {
+            return String::new();
+        }
+
+        let mut json = carry.template.json;
+        if let Some(slot) = json.pointer_mut(&carry.template.pointer) {
+            *slot = Value::String(carry.text);
+        }
+        render_event(&carry.template.other_lines, &carry.template.data_prefix, &json)
+    }
+
+    /// Recursively unredact complete string values, skipping delta fields.
+    /// Returns true if anything changed.
+    fn unredact_value(
+        &self,
+        value: &mut Value,
+        pointer: &str,
+        key: Option<&str>,
+        skip: &[&str],
+    ) -> bool {
+        if skip.contains(&pointer) {
+            return false;
+        }
+
+        match value {
+            Value::String(s) => {
+                let pairs = if key.map(|k| JSON_FRAGMENT_KEYS.contains(&k)).unwrap_or(false) {
+                    &self.fragment_pairs
+                } else {
+                    &self.text_pairs
+                };
+                let (replaced, _) = replace_all(s, pairs);
+                if &replaced != s {
+                    *s = replaced;
+                    true
+                } else {
+                    false
+                }
+            }
+            Value::Array(arr) => {
+                let mut changed = false;
+                for (i, item) in arr.iter_mut().enumerate() {
+                    let child = format!("{}/{}", pointer, i);
+                    changed |= self.unredact_value(item, &child, key, skip);
+                }
+                changed
+            }
+            Value::Object(obj) => {
+                let mut changed = false;
+                for (k, v) in obj.iter_mut() {
+                    let child = format!("{}/{}", pointer, escape_pointer_token(k));
+                    changed |= self.unredact_value(v, &child, Some(k.as_str()), skip);
+                }
+                changed
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Unredact a complete (non-streamed) response body.
+/// JSON bodies are unredacted value-by-value so originals are escaped correctly;
+/// anything else falls back to plain text replacement.
+pub fn unredact_complete_body(body: &str, replacements: &HashMap<String, String>) -> String {
+    if replacements.is_empty() {
+        return body.to_string();
+    }
+
+    let unredactor = StreamingUnredactor::new(replacements);
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut json) if json.is_object() || json.is_array() => {
+            if unredactor.unredact_value(&mut json, "", None, &[]) {
+                serde_json::to_string(&json).unwrap_or_else(|_| body.to_string())
+            } else {
+                body.to_string()
+            }
+        }
+        _ => replace_all(body, &unredactor.text_pairs).0,
+    }
+}
+
+/// Find the end (exclusive) of the first complete SSE event in the buffer
+fn find_event_end(buf: &[u8]) -> Option<usize> {
+    let mut i = 0;
+    while i < buf.len() {
+        if buf[i] == b'\n' {
+            if buf.get(i + 1) == Some(&b'\n') {
+                return Some(i + 2);
+            }
+            if buf.get(i + 1) == Some(&b'\r') && buf.get(i + 2) == Some(&b'\n') {
+                return Some(i + 3);
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split "data: {...}" into ("data: ", "{...}")
+fn split_data_line(line: &str) -> (&str, &str) {
+    let rest = match line.strip_prefix("data:") {
+        Some(r) => r,
+        None => return ("", line),
+    };
+    let prefix_len = if rest.starts_with(' ') { 6 } else { 5 };
+    (&line[..prefix_len], line[prefix_len..].trim_end_matches('\r'))
+}
+
+fn render_event(other_lines: &[String], data_prefix: &str, json: &Value) -> String {
+    let mut event = String::new();
+    for line in other_lines {
+        event.push_str(line);
+        event.push('\n');
+    }
+    event.push_str(data_prefix);
+    event.push_str(&serde_json::to_string(json).unwrap_or_default());
+    event.push_str("\n\n");
+    event
+}
+
+/// Locate streamed delta strings in a Claude, Codex (Responses API) or
+/// OpenAI chat completions event
+fn delta_fields(json: &Value) -> Vec<DeltaField> {
+    let mut fields = Vec::new();
+    let event_type = json.get("type").and_then(|t| t.as_str()).unwrap_or("");
+
+    // Claude: content_block_delta with text / partial_json / thinking
+    if event_type == "content_block_delta" {
+        let index = json.get("index").and_then(|v| v.as_i64()).unwrap_or(0);
+        let delta_type = json
+            .pointer("/delta/type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+        let field = match delta_type {
+            "text_delta" => Some(("/delta/text", FieldKind::Text)),
+            "thinking_delta" => Some(("/delta/thinking", FieldKind::Text)),
+            "input_json_delta" => Some(("/delta/partial_json", FieldKind::JsonFragment)),
+            _ => None,
+        };
+        if let Some((pointer, kind)) = field {
+            fields.push(DeltaField {
+                key: format!("claude:{}", index),
+                pointer: pointer.to_string(),
+                kind,
+            });
+        }
+        return fields;
+    }
+
+    // Codex: response.*.delta events carry a string "delta"
+    if event_type.starts_with("response.") && event_type.ends_with(".delta") {
+        if json.get("delta").map(|d| d.is_string()).unwrap_or(false) {
+            let base = &event_type[..event_type.len() - ".delta".len()];
+            let kind = if base == "response.function_call_arguments" {
+                FieldKind::JsonFragment
+            } else {
+                FieldKind::Text
+            };
+            fields.push(DeltaField {
+                key: responses_key(base, json),
+                pointer: "/delta".to_string(),
+                kind,
+            });
+        }
+        return fields;
+    }
+
+    // OpenAI chat completions: choices[].delta.content / tool_calls[].function.arguments
+    if let Some(choices) = json.get("choices").and_then(|c| c.as_array()) {
+        for (pos, choice) in choices.iter().enumerate() {
+            let choice_index = choice.get("index").and_then(|v| v.as_i64()).unwrap_or(pos as i64);
+            let delta = match choice.get("delta") {
+                Some(d) => d,
+                None => continue,
+            };
+            for text_key in ["content", "reasoning_content", "reasoning"] {
+                if delta.get(text_key).map(|v| v.is_string()).unwrap_or(false) {
+                    fields.push(DeltaField {
+                        key: format!("chat:{}:{}", choice_index, text_key),
+                        pointer: format!("/choices/{}/delta/{}", pos, text_key),
+                        kind: FieldKind::Text,
+                    });
+                }
+            }
+            if let Some(tool_calls) = delta.get("tool_calls").and_then(|t| t.as_array()) {
+                for (tpos, tool_call) in tool_calls.iter().enumerate() {
+                    let tool_index = tool_call.get("index").and_then(|v| v.as_i64()).unwrap_or(tpos as i64);
+                    if tool_call
+                        .pointer("/function/arguments")
+                        .map(|v| v.is_string())
+                        .unwrap_or(false)
+                    {
+                        fields.push(DeltaField {
+                            key: format!("chat:{}:tool:{}", choice_index, tool_index),
+                            pointer: format!("/choices/{}/delta/tool_calls/{}/function/arguments", pos, tpos),
+                            kind: FieldKind::JsonFragment,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    fields
+}
+
+/// Keys of content blocks that end with this event
+fn finished_keys(json: &Value, open_keys: &[String]) -> Vec<String> {
+    let event_type = json.get("type").and_then(|t| t.as_str()).unwrap_or("");
+
+    match event_type {
+        "content_block_stop" => {
+            let index = json.get("index").and_then(|v| v.as_i64()).unwrap_or(0);
+            return vec![format!("claude:{}", index)];
+        }
+        "message_stop" | "response.completed" | "response.failed" | "response.incomplete" => {
+            return open_keys.to_vec();
+        }
+        _ => {}
+    }
+
+    if event_type.starts_with("response.") && event_type.ends_with(".done") {
+        let base = &event_type[..event_type.len() - ".done".len()];
+        return vec![responses_key(base, json)];
+    }
+
+    let mut keys = Vec::new();
+    if let Some(choices) = json.get("choices").and_then(|c| c.as_array()) {
+        for (pos, choice) in choices.iter().enumerate() {
+            let finished = choice
+                .get("finish_reason")
+                .map(|v| !v.is_null())
+                .unwrap_or(false);
+            if finished {
+                let choice_index = choice.get("index").and_then(|v| v.as_i64()).unwrap_or(pos as i64);
+                let prefix = format!("chat:{}:", choice_index);
+                keys.extend(open_keys.iter().filter(|k| k.starts_with(&prefix)).cloned());
+                // Deltas in this same event are final too
+                for field in delta_fields(json) {
+                    if field.key.starts_with(&prefix) && !keys.contains(&field.key) {
+                        keys.push(field.key);
+                    }
+                }
+            }
+        }
+    }
+    keys
+}
+
+/// Content block key for Codex (Responses API) delta/done events
+fn responses_key(base: &str, json: &Value) -> String {
+    let part = |name: &str| -> String {
+        match json.get(name) {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Number(n)) => n.to_string(),
+            _ => String::new(),
+        }
+    };
+    format!(
+        "responses:{}:{}:{}:{}",
+        base,
+        part("item_id"),
+        part("content_index"),
+        part("summary_index")
+    )
+}
+
+/// Build the event used to flush held-back text for `field`: every other
+/// delta is emptied and fields that must not be repeated are dropped
+fn template_json(json: &Value, field: &DeltaField, fields: &[DeltaField]) -> Value {
+    let mut template = json.clone();
+    for other in fields {
+        if other.pointer != field.pointer {
+            if let Some(slot) = template.pointer_mut(&other.pointer) {
+                *slot = Value::String(String::new());
+            }
+        }
+    }
+
+    if field.key.starts_with("chat:") {
+        if let Some(obj) = template.as_object_mut() {
+            obj.remove("usage");
+        }
+        if let Some(choices) = template.get_mut("choices").and_then(|c| c.as_array_mut()) {
+            for choice in choices.iter_mut() {
+                if let Some(obj) = choice.as_object_mut() {
+                    obj.insert("finish_reason".to_string(), Value::Null);
+                }
+                if let Some(delta) = choice.get_mut("delta").and_then(|d| d.as_object_mut()) {
+                    delta.remove("role");
+                    if let Some(tool_calls) = delta.get_mut("tool_calls").and_then(|t| t.as_array_mut()) {
+                        for tool_call in tool_calls.iter_mut() {
+                            if let Some(tc) = tool_call.as_object_mut() {
+                                tc.remove("id");
+                                tc.remove("type");
+                                if let Some(function) = tc.get_mut("function").and_then(|f| f.as_object_mut()) {
+                                    function.remove("name");
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    template
+}
+
+/// Replace every placeholder occurrence in one left-to-right pass.
+/// Returns the new text and the byte offset after the last replacement.
+fn replace_all(text: &str, pairs: &[(String, String)]) -> (String, usize) {
+    if pairs.is_empty() {
+        return (text.to_string(), 0);
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut safe_from = 0;
+    let mut pos = 0;
+
+    loop {
+        // Earliest match wins; pairs are sorted longest-first for ties
+        let mut best: Option<(usize, &(String, String))> = None;
+        for pair in pairs {
+            if let Some(found) = text[pos..].find(pair.0.as_str()) {
+                let start = pos + found;
+                if best.map(|(b, _)| start < b).unwrap_or(true) {
+                    best = Some((start, pair));
+                }
+            }
+        }
+
+        match best {
+            Some((start, (placeholder, original))) => {
+                output.push_str(&text[pos..start]);
+                output.push_str(original);
+                pos = start + placeholder.len();
+                safe_from = output.len();
+            }
+            None => break,
+        }
+    }
+
+    output.push_str(&text[pos..]);
+    (output, safe_from)
+}
+
+/// Length of the longest suffix of `text` that is a proper prefix of a placeholder
+fn held_suffix_len(text: &str, pairs: &[(String, String)]) -> usize {
+    let max_len = pairs.iter().map(|(p, _)| p.len()).max().unwrap_or(0);
+    let limit = max_len.saturating_sub(1).min(text.len());
+
+    for len in (1..=limit).rev() {
+        let start = text.len() - len;
+        if !text.is_char_boundary(start) {
+            continue;
+        }
+        let suffix = &text[start..];
+        if pairs.iter().any(|(p, _)| p.len() > len && p.starts_with(suffix)) {
+            return len;
+        }
+    }
+    0
+}
+
+/// Escape a string for embedding inside a JSON string literal (without quotes)
+fn json_escape(s: &str) -> String {
+    let quoted = serde_json::to_string(s).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Escape a key for use as a JSON pointer reference token
+fn escape_pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn replacements(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(p, o)| (p.to_string(), o.to_string()))
+            .collect()
+    }
+
+    fn run(unredactor: &mut StreamingUnredactor, chunks: &[&str]) -> String {
+        let mut out = Vec::new();
+        for chunk in chunks {
+            out.extend(unredactor.push(chunk.as_bytes()));
+        }
+        out.extend(unredactor.finish());
+        String::from_utf8(out).unwrap()
+    }
+
+    fn claude_text(stream: &str) -> String {
+        stream
+            .lines()
+            .filter_map(|l| l.strip_prefix("data: "))
+            .filter_map(|d| serde_json::from_str::<Value>(d).ok())
+            .filter_map(|v| v.pointer("/delta/text").and_then(|t| t.as_str()).map(String::from))
+            .collect()
+    }
+
+    #[test]
+    fn test_placeholder_split_across_network_chunks() {
+        let mut u = StreamingUnredactor::new(&replacements(&[("sk-FAKE123456", "sk-REAL999999")]));
+        let out = run(
+            &mut u,
+            &[
+                "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"key sk-FA",
+                "KE123456 ok\"}}\n\n",
+            ],
+        );
+        assert_eq!(claude_text(&out), "key sk-REAL999999 ok");
+        assert!(out.starts_with("event: content_block_delta\n"));
+    }
+
+    #[test]
+    fn test_placeholder_split_across_deltas() {
+        let mut u = StreamingUnredactor::new(&replacements(&[("sk-FAKE123456", "sk-REAL999999")]));
+        let delta = |t: &str| {
+            format!(
+                "event: content_block_delta\ndata: {}\n\n",
+                serde_json::json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": t}})
+            )
+        };
+        let stop = "event: content_block_stop\ndata: {\"type\":\"content_block_stop\",\"index\":0}\n\n";
+        let out = run(&mut u, &[&delta("key sk-FA"), &delta("KE12"), &delta("3456 done sk-"), stop]);
+        assert_eq!(claude_text(&out), "key sk-REAL999999 done sk-");
+        // Held-back "sk-" is flushed as its own delta before the block stops
+        assert!(out.find("\"text\":\"sk-\"").unwrap() < out.find("event: content_block_stop").unwrap());
+    }
+
+    #[test]
+    fn test_original_escaped_in_partial_json() {
+        let original = "-----BEGIN KEY-----\nabc\"def\n-----END KEY-----";
+        let placeholder = "-----QWERT XYZ-----\nqwe\"rty\n-----ASD XYZ-----";
+        let mut u = StreamingUnredactor::new(&replacements(&[(placeholder, original)]));
+
+        let tool_input = serde_json::json!({"content": placeholder}).to_string();
+        let (a, b) = tool_input.split_at(20);
+        let delta = |t: &str| {
+            format!(
+                "data: {}\n\n",
+                serde_json::json!({"type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": t}})
+            )
+        };
+        let out = run(&mut u, &[&delta(a), &delta(b)]);
+
+        let partial: String = out
+            .lines()
+            .filter_map(|l| l.strip_prefix("data: "))
+            .filter_map(|d| serde_json::from_str::<Value>(d).ok())
+            .filter_map(|v| v.pointer("/delta/partial_json").and_then(|t| t.as_str()).map(String::from))
+            .collect();
+        let input: Value = serde_json::from_str(&partial).unwrap();
+        assert_eq!(input["content"], original);
+    }
+
+    #[test]
+    fn test_openai_tool_arguments_and_done() {
+        let mut u = StreamingUnredactor::new(&replacements(&[("AKIAFAKEFAKEFAKE0000", "AKIAREALREALREAL1111")]));
+        let first = "data: {\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"type\":\"function\",\"function\":{\"name\":\"run\",\"arguments\":\"{\\\"k\\\":\\\"AKIAFAKE\"}}]}}]}\n\n";
+        let last = "data: {\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"FAKEFAKE0000\\\"}\"}}]},\"finish_reason\":\"tool_calls\"}]}\n\n";
+        let out = run(&mut u, &[first, last, "data: [DONE]\n\n"]);
+
+        let args: String = out
+            .lines()
+            .filter_map(|l| l.strip_prefix("data: "))
+            .filter_map(|d| serde_json::from_str::<Value>(d).ok())
+            .filter_map(|v| {
+                v.pointer("/choices/0/delta/tool_calls/0/function/arguments")
+                    .and_then(|t| t.as_str())
+                    .map(String::from)
+            })
+            .collect();
+        assert_eq!(args, "{\"k\":\"AKIAREALREALREAL1111\"}");
+        assert!(out.ends_with("data: [DONE]\n\n"));
+    }
+
+    #[test]
+    fn test_no_replacements_passes_bytes_through() {
+        let mut u = StreamingUnredactor::new(&HashMap::new());
+        assert_eq!(u.push(b"data: {\"a\":1}"), b"data: {\"a\":1}".to_vec());
+        assert!(u.finish().is_empty());
+    }
+
+    #[test]
+    fn test_complete_body_escapes_originals() {
+        let body = serde_json::json!({"content": [{"type": "text", "text": "key: FAKE-123"}]}).to_string();
+        let out = unredact_complete_body(&body, &replacements(&[("FAKE-123", "a\"b\nc")]));
+        let json: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(json["content"][0]["text"], "key: a\"b\nc");
+    }
+}
@@ -0,0 +1,274 @@
+// DLP Text Views
+//
+// A literal scan misses secrets that are encoded or padded: base64, hex or
+// percent-encoding, fullwidth / compatibility characters, or zero-width
+// characters sprinkled inside a value. The engine also scans views of the text
+// where these are undone:
+// - Normalized: NFKC (per character), zero-width characters and soft hyphens removed
+// - Decoded: long base64, hex and percent-encoded runs, kept only when they
+//   decode to readable text
+// Each view byte maps back to a span of the original text, so a match in a view
+// becomes a match on the original characters. Base64 and hex runs map as a whole:
+// it is the encoded form that gets redacted.
+
+use base64::alphabet;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+use regex::Regex;
+use std::sync::LazyLock;
+use unicode_normalization::UnicodeNormalization;
+
+/// Shortest base64 run decoded (15 bytes of data)
+const MIN_BASE64_RUN: usize = 20;
+/// Shortest hex run decoded (16 bytes of data)
+const MIN_HEX_RUN: usize = 32;
+/// Longer runs (images, archives) are not decoded
+const MAX_DECODED_RUN: usize = 64 * 1024;
+/// Decoded views scanned per string
+const MAX_DECODED_VIEWS: usize = 256;
+
+static BASE64_RUN_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"[A-Za-z0-9+/_-]{{{},}}={{0,2}}", MIN_BASE64_RUN)).unwrap());
+static HEX_RUN_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"\b[0-9a-fA-F]{{{},}}\b", MIN_HEX_RUN)).unwrap());
+static URL_RUN_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"[^\s"'<>%]*(?:%[0-9A-Fa-f]{2}[^\s"'<>%]*)+"#).unwrap());
+
+/// How a view was derived from the original text
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ViewKind {
+    Normalized,
+    Base64,
+    Hex,
+    Url,
+}
+
+impl ViewKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViewKind::Normalized => "normalized",
+            ViewKind::Base64 => "base64",
+            ViewKind::Hex => "hex",
+            ViewKind::Url => "url",
+        }
+    }
+}
+
+/// Where the bytes of a view come from in the original text
+enum Origins {
+    /// Every byte comes from one span (a decoded base64 or hex run)
+    Run(usize, usize),
+    /// Original span of each byte
+    Bytes(Vec<(usize, usize)>),
+}
+
+/// A transformed copy of (part of) a string
+pub struct TextView {
+    pub kind: ViewKind,
+    pub text: String,
+    origins: Origins,
+}
+
+impl TextView {
+    /// Span of the original text that view bytes `start..end` come from
+    pub fn original_span(&self, start: usize, end: usize) -> (usize, usize) {
+        match &self.origins {
+            Origins::Run(run_start, run_end) => (*run_start, *run_end),
+            Origins::Bytes(spans) => (spans[start].0, spans[end - 1].1),
+        }
+    }
+}
+
+/// Views of text worth scanning besides the text itself (empty for plain text)
+pub fn text_views(text: &str) -> Vec<TextView> {
+    let mut views: Vec<TextView> = Vec::new();
+    views.extend(normalized_view(text));
+
+    let decoded = decoded_runs(text, &BASE64_RUN_RE, ViewKind::Base64, base64_decode)
+        .chain(decoded_runs(text, &HEX_RUN_RE, ViewKind::Hex, |run| hex::decode(run).ok()))
+        .chain(url_views(text));
+    views.extend(decoded.take(MAX_DECODED_VIEWS));
+    views
+}
+
+/// Characters that render as nothing and are used to split values
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}' | '\u{180E}' | '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' | '\u{FEFF}'
+    )
+}
+
+/// NFKC-normalized text without invisible characters (None if nothing changes)
+/// Characters are normalized one at a time so each output byte keeps its origin
+fn normalized_view(text: &str) -> Option<TextView> {
+    if text.is_ascii() {
+        return None;
+    }
+
+    let mut normalized = String::with_capacity(text.len());
+    let mut spans: Vec<(usize, usize)> = Vec::with_capacity(text.len());
+    for (i, c) in text.char_indices() {
+        if is_invisible(c) {
+            continue;
+        }
+        if c.is_ascii() {
+            normalized.push(c);
+        } else {
+            normalized.extend(std::iter::once(c).nfkc());
+        }
+        spans.resize(normalized.len(), (i, i + c.len_utf8()));
+    }
+
+    if normalized == text {
+        return None;
+    }
+    Some(TextView {
+        kind: ViewKind::Normalized,
+        text: normalized,
+        origins: Origins::Bytes(spans),
+    })
+}
+
+/// Decoded runs of an encoding that turn out to be readable text
+fn decoded_runs<'a>(
+    text: &'a str,
+    run_re: &'a Regex,
+    kind: ViewKind,
+    decode: impl Fn(&str) -> Option<Vec<u8>> + 'a,
+) -> impl Iterator<Item = TextView> + 'a {
+    run_re
+        .find_iter(text)
+        .filter(|m| m.len() <= MAX_DECODED_RUN)
+        .filter_map(move |m| {
+            let decoded = decode(m.as_str())?;
+            let decoded = String::from_utf8(decoded).ok().filter(|d| is_readable(d))?;
+            Some(TextView {
+                kind,
+                text: decoded,
+                origins: Origins::Run(m.start(), m.end()),
+            })
+        })
+}
+
+/// Percent-decoded runs; each decoded byte maps to its escape or literal character
+fn url_views(text: &str) -> impl Iterator<Item = TextView> + '_ {
+    URL_RUN_RE
+        .find_iter(text)
+        .filter(|m| m.len() <= MAX_DECODED_RUN)
+        .filter_map(|m| {
+            let run = m.as_str().as_bytes();
+            let mut bytes: Vec<u8> = Vec::with_capacity(run.len());
+            let mut spans: Vec<(usize, usize)> = Vec::with_capacity(run.len());
+            let mut i = 0;
+            while i < run.len() {
+                let escaped = if run[i] == b'%' {
+                    run.get(i + 1..i + 3).and_then(hex_byte)
+                } else {
+                    None
+                };
+                let start = m.start() + i;
+                match escaped {
+                    Some(b) => {
+                        bytes.push(b);
+                        spans.push((start, start + 3));
+                        i += 3;
+                    }
+                    None => {
+                        bytes.push(run[i]);
+                        spans.push((start, start + 1));
+                        i += 1;
+                    }
+                }
+            }
+            let decoded = String::from_utf8(bytes).ok().filter(|d| is_readable(d))?;
+            Some(TextView {
+                kind: ViewKind::Url,
+                text: decoded,
+                origins: Origins::Bytes(spans),
+            })
+        })
+}
+
+/// Value of two hex digits
+fn hex_byte(digits: &[u8]) -> Option<u8> {
+    if !digits.iter().all(u8::is_ascii_hexdigit) {
+        return None;
+    }
+    u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()
+}
+
+/// Text without control characters other than line breaks and tabs
+fn is_readable(text: &str) -> bool {
+    !text.is_empty() && !text.chars().any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+}
+
+/// Decodes padded and unpadded base64 (trailing bits of odd-length data ignored)
+const LENIENT: GeneralPurposeConfig = GeneralPurposeConfig::new()
+    .with_decode_padding_mode(DecodePaddingMode::Indifferent)
+    .with_decode_allow_trailing_bits(true);
+const STANDARD_LENIENT: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, LENIENT);
+const URL_SAFE_LENIENT: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, LENIENT);
+
+/// Decode standard or URL-safe base64, padded or not
+fn base64_decode(encoded: &str) -> Option<Vec<u8>> {
+    let engine = if encoded.contains(['-', '_']) { &URL_SAFE_LENIENT } else { &STANDARD_LENIENT };
+    engine.decode(encoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view_of(text: &str, kind: ViewKind) -> Option<TextView> {
+        text_views(text).into_iter().find(|v| v.kind == kind)
+    }
+
+    #[test]
+    fn test_plain_text_has_no_views() {
+        assert!(text_views("just a normal sentence about src/components/settings/page").is_empty());
+    }
+
+    #[test]
+    fn test_normalized_view_maps_back() {
+        // Fullwidth letters and a zero-width space inside the value
+        let text = "key: ｓｋ-ant\u{200B}-abc";
+        let view = view_of(text, ViewKind::Normalized).unwrap();
+        assert_eq!(view.text, "key: sk-ant-abc");
+
+        let start = view.text.find("sk").unwrap();
+        let (s, e) = view.original_span(start, view.text.len());
+        assert_eq!(&text[s..e], "ｓｋ-ant\u{200B}-abc");
+    }
+
+    #[test]
+    fn test_decoded_views() {
+        // base64("password=hunter2-correct-horse")
+        let text = "blob cGFzc3dvcmQ9aHVudGVyMi1jb3JyZWN0LWhvcnNl end";
+        let view = view_of(text, ViewKind::Base64).unwrap();
+        assert_eq!(view.text, "password=hunter2-correct-horse");
+        let (s, e) = view.original_span(0, 8);
+        assert_eq!(&text[s..e], "cGFzc3dvcmQ9aHVudGVyMi1jb3JyZWN0LWhvcnNl");
+
+        // Padded, and URL-safe alphabet
+        assert_eq!(base64_decode("aGk/Pz8="), Some(b"hi???".to_vec()));
+        assert_eq!(base64_decode("aGk_Pz8"), Some(b"hi???".to_vec()));
+
+        let text = "id 70617373776f72643d68756e74657232 ok";
+        assert_eq!(view_of(text, ViewKind::Hex).unwrap().text, "password=hunter2");
+
+        let text = "GET /login?pw=hunter%32%21&x=1";
+        let view = view_of(text, ViewKind::Url).unwrap();
+        assert_eq!(view.text, "/login?pw=hunter2!&x=1");
+        let start = view.text.find("hunter").unwrap();
+        let (s, e) = view.original_span(start, start + "hunter2!".len());
+        assert_eq!(&text[s..e], "hunter%32%21");
+    }
+
+    #[test]
+    fn test_binary_runs_are_skipped() {
+        // sha256 digest: decodes to bytes that are not text
+        let text = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+        assert!(view_of(text, ViewKind::Hex).is_none());
+    }
+}
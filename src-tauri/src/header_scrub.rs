@@ -0,0 +1,164 @@
+// Header Scrubbing Policy
+//
+// Masks credentials in request/response headers before they are stored in the
+// requests table. Sensitive headers come from a built-in list plus user-added
+// names (settings key `header_scrub_custom_names`). Masks are fingerprinted
+// with the per-install salt of detection fingerprints.
+
+use crate::database::open_connection;
+use crate::detection_storage::{get_or_create_salt, FINGERPRINT_SALT_KEY};
+use crate::secret_masking::mask_header_value;
+use rusqlite::Connection;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Header names that are always scrubbed
+pub const BUILTIN_SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "x-api-key",
+    "api-key",
+    "x-goog-api-key",
+    "cookie",
+    "set-cookie",
+    "x-auth-token",
+    "x-access-token",
+    "x-csrf-token",
+    "x-llmwatcher-token",
+];
+
+/// Name suffixes that mark a header as sensitive (e.g. "x-session-token")
+/// Suffixes rather than substrings, so rate limit headers such as
+/// "anthropic-ratelimit-tokens-remaining" stay readable
+pub const BUILTIN_SENSITIVE_SUFFIXES: &[&str] = &[
+    "api-key",
+    "apikey",
+    "token",
+    "secret",
+    "password",
+    "session",
+];
+
+/// Settings key for user-added header names (JSON array)
+const CUSTOM_NAMES_KEY: &str = "header_scrub_custom_names";
+
+#[derive(Clone)]
+pub struct HeaderScrubPolicy {
+    custom_names: HashSet<String>,
+    /// Fingerprint salt for masked values
+    salt: Vec<u8>,
+}
+
+impl HeaderScrubPolicy {
+    /// Create a policy with the built-in list and the given extra names
+    pub fn new(custom_names: &[String], salt: Vec<u8>) -> Self {
+        Self {
+            custom_names: custom_names
+                .iter()
+                .map(|n| n.trim().to_ascii_lowercase())
+                .filter(|n| !n.is_empty())
+                .collect(),
+            salt,
+        }
+    }
+
+    /// Load the policy with user-added names from the database
+    pub fn load() -> Self {
+        match open_connection() {
+            Ok(conn) => Self::load_with(&conn),
+            // Nothing is stored without a database; any salt will do
+            Err(_) => Self::new(&[], rand::random::<[u8; 32]>().to_vec()),
+        }
+    }
+
+    /// Load the policy using an already open connection
+    pub fn load_with(conn: &Connection) -> Self {
+        let salt = get_or_create_salt(conn, FINGERPRINT_SALT_KEY).unwrap_or_else(|e| {
+            eprintln!("[PROXY] Failed to load fingerprint salt: {}", e);
+            rand::random::<[u8; 32]>().to_vec()
+        });
+        Self::new(&read_custom_header_names(conn), salt)
+    }
+
+    /// Check whether a header should be scrubbed
+    pub fn is_sensitive(&self, name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        BUILTIN_SENSITIVE_HEADERS.contains(&name.as_str())
+            || BUILTIN_SENSITIVE_SUFFIXES.iter().any(|f| name.ends_with(f))
+            || self.custom_names.contains(&name)
+    }
+
+    /// Mask sensitive values in a header map in place
+    /// Returns true if anything changed
+    pub fn scrub_map(&self, headers: &mut HashMap<String, String>) -> bool {
+        let mut changed = false;
+        for (name, value) in headers.iter_mut() {
+            if self.is_sensitive(name) {
+                let masked = mask_header_value(name, value, &self.salt);
+                if masked != *value {
+                    *value = masked;
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Scrub a stored headers JSON object
+    /// Returns None if the JSON is not a header map or nothing changed
+    pub fn scrub_json(&self, headers_json: &str) -> Option<String> {
+        let mut map: HashMap<String, String> = serde_json::from_str(headers_json).ok()?;
+        if !self.scrub_map(&mut map) {
+            return None;
+        }
+        // Sorted keys keep the stored JSON stable
+        let sorted: BTreeMap<String, String> = map.into_iter().collect();
+        serde_json::to_string(&sorted).ok()
+    }
+}
+
+/// Get user-added sensitive header names
+pub fn get_custom_header_names() -> Vec<String> {
+    match open_connection() {
+        Ok(conn) => read_custom_header_names(&conn),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn read_custom_header_names(conn: &Connection) -> Vec<String> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        rusqlite::params![CUSTOM_NAMES_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+    .and_then(|v| serde_json::from_str(&v).ok())
+    .unwrap_or_default()
+}
+
+/// Save user-added sensitive header names
+pub fn save_custom_header_names(names: &[String]) -> Result<(), String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for name in names {
+        let name = name.trim().to_ascii_lowercase();
+        if name.is_empty() {
+            continue;
+        }
+        if axum::http::HeaderName::from_bytes(name.as_bytes()).is_err() {
+            return Err(format!("Invalid header name '{}'", name));
+        }
+        if !cleaned.contains(&name) {
+            cleaned.push(name);
+        }
+    }
+
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    let json = serde_json::to_string(&cleaned).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        rusqlite::params![CUSTOM_NAMES_KEY, json],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
@@ -8,14 +8,31 @@ mod builtin_patterns;
 mod commands;
 mod cursor_hooks;
 mod database;
+mod detection_storage;
+mod dictionary;
 mod dlp;
+mod dlp_engine;
+mod dlp_exceptions;
 mod dlp_pattern_config;
+mod dlp_stream;
+mod dlp_views;
+mod header_scrub;
+mod known_secrets;
+mod pattern_corpus;
 mod pattern_utils;
 mod proxy;
+mod proxy_auth;
+mod redaction_vault;
 mod requestresponsemetadata;
+mod retro_scan;
+mod rule_import;
+mod secret_masking;
+mod validators;
 
 use database::get_port_from_db;
+use dlp::DlpPatternRegistry;
 use dlp_pattern_config::DEFAULT_PORT;
+use redaction_vault::RedactionVault;
 use std::sync::{Arc, Mutex};
 use tauri::{
     tray::{TrayIconBuilder, TrayIconEvent, MouseButton, MouseButtonState},
@@ -73,6 +90,14 @@ pub static RESTART_SENDER: std::sync::LazyLock<Arc<Mutex<Option<watch::Sender<bo
 pub static PROXY_STATUS: std::sync::LazyLock<Arc<Mutex<ProxyStatus>>> =
     std::sync::LazyLock::new(|| Arc::new(Mutex::new(ProxyStatus::Starting)));
 
+// Global compiled DLP pattern registry (shared by the proxy, Cursor hooks and commands)
+pub static DLP_PATTERNS: std::sync::LazyLock<DlpPatternRegistry> =
+    std::sync::LazyLock::new(DlpPatternRegistry::new);
+
+// Per-session DLP placeholders (see redaction_vault.rs)
+pub static REDACTION_VAULT: std::sync::LazyLock<RedactionVault> =
+    std::sync::LazyLock::new(RedactionVault::new);
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Initialize reverse proxy port from environment variable or database
@@ -153,13 +178,11 @@ pub fn run() {
                     // Prevent the window from closing, hide it instead
                     api.prevent_close();
                     let app = window.app_handle();
-                    hide_window(&app);
+                    hide_window(app);
                 }
-                WindowEvent::Focused(false) => {
-                    // Hide tray popup when it loses focus (click outside)
-                    if window.label() == "tray_popup" {
-                        let _ = window.hide();
-                    }
+                // Hide tray popup when it loses focus (click outside)
+                WindowEvent::Focused(false) if window.label() == "tray_popup" => {
+                    let _ = window.hide();
                 }
                 _ => {}
             }
@@ -181,16 +204,48 @@ pub fn run() {
             commands::get_proxy_status,
             commands::save_port_setting,
             commands::restart_proxy,
+            commands::get_proxy_access_settings,
+            commands::save_proxy_access_settings,
+            commands::regenerate_proxy_access_token,
+            commands::get_header_scrub_settings,
+            commands::save_header_scrub_names,
             commands::get_dlp_settings,
             commands::add_dlp_pattern,
             commands::update_dlp_pattern,
             commands::toggle_dlp_pattern,
             commands::delete_dlp_pattern,
+            commands::import_dlp_dictionary,
+            commands::diff_dlp_dictionary,
+            commands::reset_builtin_dlp_pattern,
+            commands::import_dlp_rules,
+            commands::get_dlp_rule_imports,
+            commands::resync_dlp_rule_import,
+            commands::delete_dlp_rule_import,
+            commands::add_dlp_known_secret_source,
+            commands::get_dlp_known_secret_sources,
+            commands::resync_dlp_known_secret_source,
+            commands::delete_dlp_known_secret_source,
+            commands::get_dlp_vault_ttl_hours,
+            commands::save_dlp_vault_ttl_hours,
+            commands::clear_dlp_vault,
             commands::get_dlp_detection_stats,
+            commands::get_dlp_shadow_stats,
             commands::get_dlp_detections_for_request,
             commands::get_dlp_action_setting,
             commands::save_dlp_action_setting,
+            commands::get_detection_storage_mode,
+            commands::save_detection_storage_mode,
             commands::test_dlp_pattern,
+            commands::run_dlp_pattern_corpora,
+            commands::create_dlp_exception_from_detection,
+            commands::add_dlp_exception,
+            commands::get_dlp_exceptions,
+            commands::revoke_dlp_exception,
+            commands::start_dlp_retro_scan,
+            commands::cancel_dlp_retro_scan,
+            commands::get_dlp_retro_scans,
+            commands::get_dlp_retro_scan_results,
+            commands::delete_dlp_retro_scan,
             // Tool call commands
             commands::get_tool_calls_for_request,
             commands::get_tool_call_stats,
@@ -0,0 +1,289 @@
+// DLP Regression Corpus
+//
+// Each pattern group carries sample texts it must match and texts it must not
+// match (`must_match` / `must_not_match` columns, JSON arrays). Builtins also
+// run the stock samples from builtin_patterns.rs, so an edited builtin is
+// still held to what the library expects of it.
+//
+// Samples are matched exactly as the proxy would: the stored definition is
+// compiled and scanned by the matching engine, decoded views included. A
+// must-match sample passes if the group reports any value in it, a
+// must-not-match sample if it reports none. Saving a pattern runs its corpus;
+// the settings page can run every corpus at once.
+
+use crate::builtin_patterns::get_builtin_patterns;
+use crate::dlp::{compile_stored_patterns, CompiledDlpPattern};
+use crate::dlp_engine::DlpMatchEngine;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Samples longer than this are shortened in error messages
+const SAMPLE_PREVIEW_CHARS: usize = 60;
+
+/// Prefix of the error returned when a save breaks the corpus
+pub const CORPUS_FAILED: &str = "Regression corpus failed";
+
+/// Samples of one pattern group
+#[derive(Debug, Default, Clone)]
+pub struct Corpus {
+    pub must_match: Vec<String>,
+    pub must_not_match: Vec<String>,
+}
+
+impl Corpus {
+    /// Corpus stored with a pattern, plus the stock samples of a builtin
+    pub fn from_row(name: &str, is_builtin: bool, must_match: Option<&str>, must_not_match: Option<&str>) -> Self {
+        let parse = |json: Option<&str>| -> Vec<String> {
+            json.and_then(|j| serde_json::from_str(j).ok()).unwrap_or_default()
+        };
+        let mut corpus = Corpus {
+            must_match: parse(must_match),
+            must_not_match: parse(must_not_match),
+        };
+
+        if let Some(stock) = get_builtin_patterns().iter().find(|p| is_builtin && p.name == name) {
+            corpus.must_match.extend(stock.must_match.iter().map(|s| s.to_string()));
+            corpus.must_not_match.extend(stock.must_not_match.iter().map(|s| s.to_string()));
+        }
+        corpus
+    }
+
+    fn len(&self) -> usize {
+        self.must_match.len() + self.must_not_match.len()
+    }
+}
+
+/// A sample the pattern got wrong
+#[derive(Debug, Clone, Serialize)]
+pub struct CorpusFailure {
+    pub sample: String,
+    /// True if the sample should have matched, false if it should not have
+    pub must_match: bool,
+    /// Values the pattern found in the sample
+    pub found: Vec<String>,
+}
+
+/// Corpus run of one pattern group
+#[derive(Debug, Serialize)]
+pub struct CorpusResult {
+    pub pattern_id: i64,
+    pub pattern_name: String,
+    pub is_builtin: bool,
+    pub enabled: bool,
+    pub samples: usize,
+    pub passed: bool,
+    pub failures: Vec<CorpusFailure>,
+    /// The stored definition does not compile
+    pub error: Option<String>,
+}
+
+impl CorpusResult {
+    /// One-line description of what failed, for error messages
+    pub fn summary(&self) -> String {
+        if let Some(error) = &self.error {
+            return format!("{} for '{}': {}", CORPUS_FAILED, self.pattern_name, error);
+        }
+        let failures: Vec<String> = self
+            .failures
+            .iter()
+            .map(|f| {
+                if f.must_match {
+                    format!("no match in \"{}\"", preview(&f.sample))
+                } else {
+                    format!("matched {} in \"{}\"", f.found.join(", "), preview(&f.sample))
+                }
+            })
+            .collect();
+        format!("{} for '{}': {}", CORPUS_FAILED, self.pattern_name, failures.join("; "))
+    }
+}
+
+/// Corpus runs of every pattern group
+#[derive(Debug, Serialize)]
+pub struct CorpusReport {
+    pub patterns_checked: usize,
+    pub patterns_failed: usize,
+    pub samples: usize,
+    /// Pattern groups without any sample (not run)
+    pub patterns_without_corpus: usize,
+    /// Failing groups first
+    pub results: Vec<CorpusResult>,
+}
+
+fn preview(sample: &str) -> String {
+    let line = sample.replace('\n', "\\n");
+    if line.chars().count() <= SAMPLE_PREVIEW_CHARS {
+        return line;
+    }
+    let short: String = line.chars().take(SAMPLE_PREVIEW_CHARS).collect();
+    format!("{}...", short)
+}
+
+/// Distinct values the pattern reports in a text
+fn find_values(engine: &DlpMatchEngine, pattern: &[CompiledDlpPattern], text: &str) -> Vec<String> {
+    let mut values: Vec<String> = Vec::new();
+    for m in engine.find_matches_with_views(pattern, text) {
+        let value = text[m.start..m.end].to_string();
+        if !values.contains(&value) {
+            values.push(value);
+        }
+    }
+    values
+}
+
+/// Run a corpus against a compiled pattern group; returns the failing samples
+pub fn run_corpus(pattern: &CompiledDlpPattern, corpus: &Corpus) -> Vec<CorpusFailure> {
+    // Shadow patterns are matched the same way, just not enforced
+    let mut pattern = pattern.clone();
+    pattern.shadow = false;
+    let patterns = [pattern];
+    let engine = DlpMatchEngine::build(&patterns);
+
+    let mut failures = Vec::new();
+    for sample in &corpus.must_match {
+        if find_values(&engine, &patterns, sample).is_empty() {
+            failures.push(CorpusFailure {
+                sample: sample.clone(),
+                must_match: true,
+                found: Vec::new(),
+            });
+        }
+    }
+    for sample in &corpus.must_not_match {
+        let found = find_values(&engine, &patterns, sample);
+        if !found.is_empty() {
+            failures.push(CorpusFailure {
+                sample: sample.clone(),
+                must_match: false,
+                found,
+            });
+        }
+    }
+    failures
+}
+
+/// id, name, is_builtin, enabled, must_match, must_not_match
+type CorpusRow = (i64, String, bool, bool, Option<String>, Option<String>);
+
+/// Run the corpora of the stored patterns selected by `condition` (SQL over `dlp_patterns`)
+fn run_stored(conn: &Connection, condition: &str) -> Result<Vec<CorpusResult>, String> {
+    let mut compiled: HashMap<i64, Result<CompiledDlpPattern, String>> = compile_stored_patterns(conn, condition)
+        .into_iter()
+        .map(|(id, _, pattern)| (id, pattern))
+        .collect();
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT id, name, is_builtin, enabled, must_match, must_not_match
+             FROM dlp_patterns WHERE {} ORDER BY is_builtin DESC, id",
+            condition
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows: Vec<CorpusRow> = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get::<_, i32>(2)? == 1,
+                row.get::<_, i32>(3)? == 1,
+                row.get(4)?,
+                row.get(5)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, name, is_builtin, enabled, must_match, must_not_match)| {
+            let corpus = Corpus::from_row(&name, is_builtin, must_match.as_deref(), must_not_match.as_deref());
+            let (failures, error) = match compiled.remove(&id) {
+                Some(Ok(pattern)) => (run_corpus(&pattern, &corpus), None),
+                Some(Err(e)) => (Vec::new(), Some(e)),
+                None => (Vec::new(), Some("Pattern could not be loaded".to_string())),
+            };
+            CorpusResult {
+                pattern_id: id,
+                pattern_name: name,
+                is_builtin,
+                enabled,
+                samples: corpus.len(),
+                passed: failures.is_empty() && error.is_none(),
+                failures,
+                error,
+            }
+        })
+        .collect())
+}
+
+/// Run the corpus of one stored pattern
+pub fn check_pattern(conn: &Connection, id: i64) -> Result<CorpusResult, String> {
+    run_stored(conn, &format!("id = {}", id))?
+        .pop()
+        .ok_or_else(|| "Pattern not found".to_string())
+}
+
+/// Run every stored corpus, builtins and disabled patterns included
+pub fn check_all(conn: &Connection) -> Result<CorpusReport, String> {
+    let (mut results, without): (Vec<CorpusResult>, Vec<CorpusResult>) =
+        run_stored(conn, "1 = 1")?.into_iter().partition(|r| r.samples > 0 || r.error.is_some());
+    // Failing groups first, otherwise in settings order
+    results.sort_by_key(|r| r.passed);
+
+    Ok(CorpusReport {
+        patterns_checked: results.len(),
+        patterns_failed: results.iter().filter(|r| !r.passed).count(),
+        samples: results.iter().map(|r| r.samples).sum(),
+        patterns_without_corpus: without.len(),
+        results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_corpora_pass() {
+        for builtin in get_builtin_patterns() {
+            let corpus = Corpus::from_row(builtin.name, true, None, None);
+            assert!(!corpus.must_match.is_empty(), "{} has no must-match samples", builtin.name);
+            let failures = run_corpus(&CompiledDlpPattern::builtin_for_test(builtin), &corpus);
+            assert!(failures.is_empty(), "{}: {:?}", builtin.name, failures);
+        }
+    }
+
+    #[test]
+    fn test_corpus_reports_regressions() {
+        let builtin = get_builtin_patterns().iter().find(|p| p.name == "US Social Security Numbers").unwrap();
+        let mut pattern = CompiledDlpPattern::builtin_for_test(builtin);
+        // An edit that drops the checksum lets invalid numbers through
+        pattern.validator = None;
+
+        let corpus = Corpus::from_row(
+            builtin.name,
+            true,
+            Some(r#"["ssn 536 22 1234"]"#),
+            None,
+        );
+        let failures = run_corpus(&pattern, &corpus);
+        let failed: Vec<(&str, bool)> = failures.iter().map(|f| (f.sample.as_str(), f.must_match)).collect();
+        assert_eq!(failed, vec![("SSN: 666-22-1234", false), ("part no. 078-05-1120", false)]);
+        assert_eq!(failures[0].found, vec!["666-22-1234"]);
+
+        let result = CorpusResult {
+            pattern_id: 1,
+            pattern_name: builtin.name.to_string(),
+            is_builtin: true,
+            enabled: true,
+            samples: corpus.len(),
+            passed: false,
+            failures,
+            error: None,
+        };
+        assert!(result.summary().starts_with(CORPUS_FAILED));
+        assert!(result.summary().contains("matched 666-22-1234 in \"SSN: 666-22-1234\""));
+    }
+}
@@ -3,22 +3,304 @@
 // This module provides common pattern compilation and matching utilities
 // used by both the DLP redaction engine (dlp.rs) and the test command (commands/dlp.rs).
 
+use crate::dictionary::{Dictionary, DictionaryOptions};
+use crate::validators::{Validator, VALIDATOR_NAMES};
 use regex::Regex;
-use std::collections::HashSet;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Default context window (characters before and after a match) for context checks
+pub const DEFAULT_CONTEXT_WINDOW: usize = 30;
+
+/// Largest context window a pattern can use
+pub const MAX_CONTEXT_WINDOW: usize = 1000;
+
+/// Default minimum token length for "entropy" patterns
+pub const DEFAULT_ENTROPY_MIN_LENGTH: i32 = 20;
+
+/// Tokens longer than this are never flagged by "entropy" patterns
+/// (keeps inline images and other large base64 payloads out of the detections)
+pub const ENTROPY_MAX_TOKEN_LEN: usize = 512;
+
+/// Options for the "entropy" pattern type (ignored by other types)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EntropyOptions {
+    /// Minimum Shannon entropy in bits per character (None = alphabet default)
+    pub threshold: Option<f64>,
+    /// Minimum token length (None = DEFAULT_ENTROPY_MIN_LENGTH)
+    pub min_length: Option<i32>,
+}
+
+/// What the negative patterns of a pattern are checked against
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NegativeScope {
+    /// The context window around a match
+    #[default]
+    Context,
+    /// The matched value itself (gitleaks allowlists, trufflehog exclusions)
+    Value,
+}
 
-/// Context window size (characters before and after a match) for negative pattern checking
-pub const NEGATIVE_CONTEXT_WINDOW: usize = 30;
+impl NegativeScope {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "context" => Some(Self::Context),
+            "value" => Some(Self::Value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Context => "context",
+            Self::Value => "value",
+        }
+    }
+}
 
 /// Result of compiling patterns - includes both positive and negative regexes
 #[derive(Clone)]
 pub struct CompiledPatterns {
     pub regexes: Vec<Regex>,
     pub negative_regexes: Vec<Regex>,
+    pub negative_scope: NegativeScope,
+    /// A match only counts if one of these appears in its context (empty = no requirement)
+    pub required_regexes: Vec<Regex>,
+    /// Characters before and after a match checked by the context rules
+    pub context_window: usize,
+    /// Minimum entropy per regex (entropy patterns only), aligned with `regexes`
+    pub entropy_thresholds: Vec<f64>,
+    /// Checksum every match must pass (validator patterns only)
+    pub validator: Option<Validator>,
+    /// Capture group to redact per regex, aligned with `regexes`
+    /// (empty or None = the whole match)
+    pub redact_groups: Vec<Option<usize>>,
+    /// Word list of a dictionary pattern (matched instead of `regexes`)
+    pub dictionary: Option<Arc<Dictionary>>,
+}
+
+impl CompiledPatterns {
+    /// Compile the entries of a dictionary pattern (no-op for other types)
+    pub fn with_dictionary(
+        mut self,
+        pattern_type: &str,
+        entries: &[String],
+        options: DictionaryOptions,
+    ) -> Result<Self, String> {
+        if pattern_type == "dictionary" {
+            self.dictionary = Dictionary::build(entries, options)?.map(Arc::new);
+        }
+        Ok(self)
+    }
+
+    /// Set the context window (None = default) and the required context
+    pub fn with_context(
+        mut self,
+        window: Option<i32>,
+        required_patterns: Option<&Vec<String>>,
+        required_pattern_type: Option<&str>,
+    ) -> Result<Self, String> {
+        self.context_window = resolve_context_window(window)?;
+        self.required_regexes = match required_patterns {
+            Some(required) => compile_patterns(required, required_pattern_type.unwrap_or("keyword"))?,
+            None => Vec::new(),
+        };
+        Ok(self)
+    }
+
+    /// Check negative patterns against the context (None) or the matched value
+    pub fn with_negative_scope(mut self, scope: Option<&str>) -> Result<Self, String> {
+        self.negative_scope = match scope.map(str::trim).filter(|s| !s.is_empty()) {
+            Some(s) => NegativeScope::parse(s)
+                .ok_or_else(|| format!("Unknown negative scope '{}'. Use 'context' or 'value'", s))?,
+            None => NegativeScope::Context,
+        };
+        Ok(self)
+    }
+
+    /// Scope matches to a named capture group (see `resolve_redact_group`)
+    pub fn with_redact_group(mut self, pattern_type: &str, group: Option<&str>) -> Result<Self, String> {
+        self.redact_groups = resolve_redact_group(&self.regexes, pattern_type, group)?;
+        Ok(self)
+    }
+}
+
+/// Token alphabets for "entropy" patterns: (name, candidate regex, default threshold)
+/// MIN in the candidate regex is replaced by the minimum token length.
+/// Thresholds sit below each alphabet's maximum (6 bits for base64, 4 for hex)
+/// so random tokens pass while words, paths and identifiers do not
+const ENTROPY_ALPHABETS: &[(&str, &str, f64)] = &[
+    ("base64", r"[A-Za-z0-9+/_-]{MIN,}={0,2}", 4.5),
+    ("hex", r"\b[0-9a-fA-F]{MIN,}\b", 3.0),
+];
+
+/// Compile "entropy" pattern entries (alphabet names, e.g. "base64", "hex") into
+/// candidate token regexes and their entropy thresholds
+pub fn compile_entropy_patterns(
+    alphabets: &[String],
+    options: &EntropyOptions,
+) -> Result<(Vec<Regex>, Vec<f64>), String> {
+    let min_length = options.min_length.unwrap_or(DEFAULT_ENTROPY_MIN_LENGTH);
+    if min_length < 8 {
+        return Err("Minimum length must be at least 8 characters".to_string());
+    }
+    if let Some(threshold) = options.threshold {
+        if !(0.0..=8.0).contains(&threshold) {
+            return Err("Entropy threshold must be between 0 and 8 bits per character".to_string());
+        }
+    }
+
+    let mut regexes = Vec::new();
+    let mut thresholds = Vec::new();
+
+    for alphabet in alphabets {
+        let alphabet = alphabet.trim().to_ascii_lowercase();
+        if alphabet.is_empty() {
+            continue;
+        }
+
+        let (_, body, default_threshold) = ENTROPY_ALPHABETS
+            .iter()
+            .find(|(name, _, _)| *name == alphabet)
+            .ok_or_else(|| format!("Unknown entropy alphabet '{}'. Use 'base64' or 'hex'", alphabet))?;
+
+        let regex = Regex::new(&body.replace("MIN", &min_length.to_string()))
+            .map_err(|e| format!("Invalid entropy alphabet '{}': {}", alphabet, e))?;
+        regexes.push(regex);
+        thresholds.push(options.threshold.unwrap_or(*default_threshold));
+    }
+
+    Ok((regexes, thresholds))
+}
+
+/// Shannon entropy of a string in bits per character
+pub fn shannon_entropy(s: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    let mut total = 0usize;
+    for c in s.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+        total += 1;
+    }
+    if total == 0 {
+        return 0.0;
+    }
+
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Check whether a candidate token passes an entropy pattern's threshold
+pub fn is_high_entropy_token(token: &str, threshold: f64) -> bool {
+    token.len() <= ENTROPY_MAX_TOKEN_LEN && shannon_entropy(token) >= threshold
+}
+
+/// Resolve the validator of a "validator" pattern (None for other types)
+pub fn resolve_validator(pattern_type: &str, validator: Option<&str>) -> Result<Option<Validator>, String> {
+    if pattern_type != "validator" {
+        return Ok(None);
+    }
+    match validator.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(name) => Validator::parse(name).map(Some).ok_or_else(|| {
+            format!("Unknown validator '{}'. Use one of: {}", name, VALIDATOR_NAMES.join(", "))
+        }),
+        None => Err("Validator patterns need a validator".to_string()),
+    }
+}
+
+/// Resolve a named capture group (or a group number, as in gitleaks'
+/// `secretGroup`) to its index in each regex.
+/// Only the group is matched and replaced; regexes that do not declare it keep
+/// using the whole match, but at least one regex has to declare it
+pub fn resolve_redact_group(
+    regexes: &[Regex],
+    pattern_type: &str,
+    group: Option<&str>,
+) -> Result<Vec<Option<usize>>, String> {
+    let Some(group) = group.map(str::trim).filter(|g| !g.is_empty()) else {
+        return Ok(Vec::new());
+    };
+    if pattern_type != "regex" && pattern_type != "validator" {
+        return Err("A redact group can only be used with regex and validator patterns".to_string());
+    }
+
+    let number = group.parse::<usize>().ok();
+    let indices: Vec<Option<usize>> = regexes
+        .iter()
+        .map(|re| match number {
+            Some(n) => (n > 0 && n < re.captures_len()).then_some(n),
+            None => re.capture_names().position(|name| name == Some(group)),
+        })
+        .collect();
+    if indices.iter().all(Option::is_none) {
+        return Err(format!("No pattern declares the capture group '{}', e.g. (?P<{}>...)", group, group));
+    }
+    Ok(indices)
+}
+
+/// A regex match and the part of it that is checked and replaced
+/// (the redact group, or the whole match)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MatchSpan {
+    pub match_start: usize,
+    pub match_end: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Find the spans of a regex in text, scoped to a capture group if given.
+/// Matches where the group did not participate are skipped
+pub fn find_match_spans<'a>(
+    regex: &'a Regex,
+    redact_group: Option<usize>,
+    text: &'a str,
+) -> Box<dyn Iterator<Item = MatchSpan> + 'a> {
+    match redact_group {
+        None => Box::new(regex.find_iter(text).map(|m| MatchSpan {
+            match_start: m.start(),
+            match_end: m.end(),
+            start: m.start(),
+            end: m.end(),
+        })),
+        Some(idx) => Box::new(regex.captures_iter(text).filter_map(move |caps| {
+            let whole = caps.get(0)?;
+            let group = caps.get(idx)?;
+            Some(MatchSpan {
+                match_start: whole.start(),
+                match_end: whole.end(),
+                start: group.start(),
+                end: group.end(),
+            })
+        })),
+    }
+}
+
+/// Type-specific checks on a single match, after its regex matched:
+/// the entropy threshold of the regex (entropy patterns) and the checksum
+/// validator (validator patterns)
+pub fn passes_match_checks(
+    matched: &str,
+    regex_idx: usize,
+    entropy_thresholds: &[f64],
+    validator: Option<Validator>,
+) -> bool {
+    if let Some(&threshold) = entropy_thresholds.get(regex_idx) {
+        if !is_high_entropy_token(matched, threshold) {
+            return false;
+        }
+    }
+    validator.is_none_or(|v| v.validate(matched))
 }
 
 /// Compile a list of patterns into regexes
 /// - For "keyword" type: patterns are escaped and made case-insensitive
 /// - For "regex" type: patterns are used as-is
+///
 /// Returns an error if any pattern is invalid
 pub fn compile_patterns(
     patterns: &[String],
@@ -53,8 +335,18 @@ pub fn compile_pattern_set(
     pattern_type: &str,
     negative_patterns: Option<&Vec<String>>,
     negative_pattern_type: Option<&str>,
+    entropy: &EntropyOptions,
+    validator: Option<&str>,
 ) -> Result<CompiledPatterns, String> {
-    let regexes = compile_patterns(patterns, pattern_type)?;
+    let validator = resolve_validator(pattern_type, validator)?;
+    let (regexes, entropy_thresholds) = if pattern_type == "entropy" {
+        compile_entropy_patterns(patterns, entropy)?
+    } else if pattern_type == "dictionary" {
+        // Entries are compiled separately (see `CompiledPatterns::with_dictionary`)
+        (Vec::new(), Vec::new())
+    } else {
+        (compile_patterns(patterns, pattern_type)?, Vec::new())
+    };
 
     let negative_regexes = match negative_patterns {
         Some(neg_patterns) => {
@@ -67,47 +359,85 @@ pub fn compile_pattern_set(
     Ok(CompiledPatterns {
         regexes,
         negative_regexes,
+        negative_scope: NegativeScope::Context,
+        required_regexes: Vec::new(),
+        context_window: DEFAULT_CONTEXT_WINDOW,
+        entropy_thresholds,
+        validator,
+        redact_groups: Vec::new(),
+        dictionary: None,
     })
 }
 
-/// Extract context around a match position in text
-/// Returns: [up to 30 chars before] + [match] + [up to 30 chars after]
-pub fn get_match_context(text: &str, start: usize, end: usize) -> String {
-    let chars: Vec<char> = text.chars().collect();
-    let text_len = chars.len();
-
-    // Convert byte positions to char positions
-    let char_start = text[..start].chars().count();
-    let char_end = text[..end].chars().count();
+/// Context window of a pattern (None or 0 = the default)
+pub fn resolve_context_window(window: Option<i32>) -> Result<usize, String> {
+    match window.filter(|w| *w > 0) {
+        None => Ok(DEFAULT_CONTEXT_WINDOW),
+        Some(w) if w as usize <= MAX_CONTEXT_WINDOW => Ok(w as usize),
+        Some(_) => Err(format!("Context window must be at most {} characters", MAX_CONTEXT_WINDOW)),
+    }
+}
 
-    // Calculate context boundaries
-    let context_start = char_start.saturating_sub(NEGATIVE_CONTEXT_WINDOW);
-    let context_end = (char_end + NEGATIVE_CONTEXT_WINDOW).min(text_len);
+/// Extract context around a match position in text
+/// Returns: [up to `window` chars before] + [match] + [up to `window` chars after]
+pub fn get_match_context(text: &str, start: usize, end: usize, window: usize) -> &str {
+    // Only walk the window around the match so large texts are not re-scanned per match
+    let context_start = text[..start]
+        .char_indices()
+        .rev()
+        .take(window)
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(start);
+    let context_end = text[end..]
+        .char_indices()
+        .nth(window)
+        .map(|(i, _)| end + i)
+        .unwrap_or(text.len());
+
+    &text[context_start..context_end]
+}
 
-    // Extract context
-    chars[context_start..context_end].iter().collect()
+/// Outcome of checking a match against its surrounding context
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContextCheck {
+    Passed,
+    /// A negative pattern (index into the negative regexes) appears in the
+    /// context, or in the value itself with `NegativeScope::Value`
+    Excluded(usize),
+    /// None of the required context patterns appears in the context
+    MissingRequired,
 }
 
-/// Check if a specific match should be excluded based on its surrounding context
-/// Extracts context window around the match and checks if any negative pattern matches
-pub fn is_match_excluded_by_context(
+/// Check a match against the context window around it: it is dropped if any
+/// negative pattern appears there (or in the value, depending on the scope),
+/// or if required context is set and none of it appears there
+pub fn check_match_context(
     text: &str,
     match_start: usize,
     match_end: usize,
+    window: usize,
     negative_regexes: &[Regex],
-) -> bool {
-    if negative_regexes.is_empty() {
-        return false;
+    negative_scope: NegativeScope,
+    required_regexes: &[Regex],
+) -> ContextCheck {
+    if negative_regexes.is_empty() && required_regexes.is_empty() {
+        return ContextCheck::Passed;
     }
 
-    let context = get_match_context(text, match_start, match_end);
+    let context = get_match_context(text, match_start, match_end, window);
+    let negative_target = match negative_scope {
+        NegativeScope::Context => context,
+        NegativeScope::Value => &text[match_start..match_end],
+    };
 
-    for neg_re in negative_regexes {
-        if neg_re.is_match(&context) {
-            return true;
-        }
+    if let Some(idx) = negative_regexes.iter().position(|re| re.is_match(negative_target)) {
+        return ContextCheck::Excluded(idx);
     }
-    false
+    if !required_regexes.is_empty() && !required_regexes.iter().any(|re| re.is_match(context)) {
+        return ContextCheck::MissingRequired;
+    }
+    ContextCheck::Passed
 }
 
 /// Count unique characters in a string
@@ -115,53 +445,137 @@ pub fn count_unique_chars(s: &str) -> usize {
     s.chars().collect::<HashSet<_>>().len()
 }
 
+/// A match as it would be rewritten: `value` is replaced, `before` and
+/// `after` are the rest of the regex match, which is kept
+#[derive(Clone, Debug, Serialize)]
+pub struct MatchHighlight {
+    pub before: String,
+    pub value: String,
+    pub after: String,
+}
+
+/// Why a candidate match was kept or dropped (shown by the pattern tester)
+#[derive(Clone, Debug, Serialize)]
+pub struct CandidateOutcome {
+    pub value: String,
+    pub kept: bool,
+    pub reason: String,
+}
+
 /// Match result containing all unique matches
 pub struct MatchResult {
     pub matches: Vec<String>,
+    /// First occurrence of each match, aligned with `matches`
+    pub highlights: Vec<MatchHighlight>,
+    /// Every distinct candidate with the reason it was kept or dropped
+    pub candidates: Vec<CandidateOutcome>,
 }
 
-/// Collect all matches from regexes with context-aware negative pattern filtering
-/// - First finds all positive matches
-/// - For each match, checks if any negative pattern matches within its context window
-/// - Applies min_unique_chars filter to individual matches
+/// Collect all matches of a compiled pattern set with context-aware filtering
+/// - First finds all positive matches (scoped to the redact group, if any,
+///   plus the entries of a dictionary)
+/// - For each match, checks its context window for negative and required context
+/// - Applies min_unique_chars (and the entropy threshold or checksum validator
+///   of entropy/validator patterns) to individual matches
 /// - Returns unique matches (deduplicated)
-pub fn collect_matches_with_negative_context(
-    text: &str,
-    regexes: &[Regex],
-    negative_regexes: &[Regex],
-    min_unique_chars: i32,
-) -> MatchResult {
+pub fn collect_matches_with_context(text: &str, compiled: &CompiledPatterns, min_unique_chars: i32) -> MatchResult {
     let mut all_matches: Vec<String> = Vec::new();
+    let mut highlights: Vec<MatchHighlight> = Vec::new();
+    let mut candidates: Vec<CandidateOutcome> = Vec::new();
     let mut seen: HashSet<String> = HashSet::new();
 
-    for regex in regexes {
-        for m in regex.find_iter(text) {
-            let matched = m.as_str().to_string();
-
-            if seen.contains(&matched) {
-                continue;
-            }
-
-            // Check if this match should be excluded based on its context
-            if is_match_excluded_by_context(text, m.start(), m.end(), negative_regexes) {
-                continue;
-            }
+    let regex_spans = compiled.regexes.iter().enumerate().flat_map(|(regex_idx, regex)| {
+        let redact_group = compiled.redact_groups.get(regex_idx).copied().flatten();
+        find_match_spans(regex, redact_group, text).map(move |span| (span, regex_idx))
+    });
+    let dictionary_spans = compiled.dictionary.iter().flat_map(|dictionary| {
+        dictionary.find_spans(text).into_iter().map(|(start, end)| {
+            let span = MatchSpan {
+                match_start: start,
+                match_end: end,
+                start,
+                end,
+            };
+            (span, 0)
+        })
+    });
+
+    for (span, regex_idx) in regex_spans.chain(dictionary_spans) {
+        let matched = text[span.start..span.end].to_string();
+
+        if matched.is_empty() || seen.contains(&matched) {
+            continue;
+        }
 
-            // Validate min_unique_chars
-            if min_unique_chars > 0 {
-                let unique_count = count_unique_chars(&matched);
-                if (unique_count as i32) < min_unique_chars {
-                    continue;
+        let dropped = if !passes_match_checks(&matched, regex_idx, &compiled.entropy_thresholds, compiled.validator) {
+            Some(match compiled.validator {
+                Some(v) => format!("fails the {} check", v.as_str()),
+                None => "entropy below the threshold".to_string(),
+            })
+        } else {
+            match check_match_context(
+                text,
+                span.start,
+                span.end,
+                compiled.context_window,
+                &compiled.negative_regexes,
+                compiled.negative_scope,
+                &compiled.required_regexes,
+            ) {
+                ContextCheck::Excluded(idx) => Some(match compiled.negative_scope {
+                    NegativeScope::Context => format!(
+                        "negative pattern `{}` within {} characters",
+                        compiled.negative_regexes[idx].as_str(),
+                        compiled.context_window
+                    ),
+                    NegativeScope::Value => {
+                        format!("negative pattern `{}` in the value", compiled.negative_regexes[idx].as_str())
+                    }
+                }),
+                ContextCheck::MissingRequired => Some(format!(
+                    "no required context within {} characters",
+                    compiled.context_window
+                )),
+                ContextCheck::Passed if min_unique_chars > 0
+                    && (count_unique_chars(&matched) as i32) < min_unique_chars =>
+                {
+                    Some(format!("fewer than {} unique characters", min_unique_chars))
                 }
+                ContextCheck::Passed => None,
             }
+        };
 
-            seen.insert(matched.clone());
-            all_matches.push(matched);
+        // The same value may be kept at another position, so only kept values are final
+        if let Some(reason) = dropped {
+            if !candidates.iter().any(|c| c.value == matched) {
+                candidates.push(CandidateOutcome {
+                    value: matched,
+                    kept: false,
+                    reason,
+                });
+            }
+            continue;
         }
+
+        seen.insert(matched.clone());
+        candidates.retain(|c| c.value != matched);
+        candidates.push(CandidateOutcome {
+            value: matched.clone(),
+            kept: true,
+            reason: "passed all checks".to_string(),
+        });
+        highlights.push(MatchHighlight {
+            before: text[span.match_start..span.start].to_string(),
+            value: matched.clone(),
+            after: text[span.end..span.match_end].to_string(),
+        });
+        all_matches.push(matched);
     }
 
     MatchResult {
         matches: all_matches,
+        highlights,
+        candidates,
     }
 }
 
@@ -212,7 +626,7 @@ mod tests {
     fn test_get_match_context() {
         let text = "prefix text before KEY123 text after suffix";
         // KEY123 starts at position 19, ends at 25
-        let context = get_match_context(text, 19, 25);
+        let context = get_match_context(text, 19, 25, DEFAULT_CONTEXT_WINDOW);
         // Should include up to 30 chars before and after
         assert!(context.contains("KEY123"));
         assert!(context.contains("before"));
@@ -226,10 +640,17 @@ mod tests {
         // "sk-prod456" should NOT be excluded (no test in context)
         // Note: Keys must be >60 chars apart so their context windows don't overlap
         let text = "testing key: sk-test123 and here is some padding text that ensures the keys are far apart so production key: sk-prod456 works";
-        let pos_regexes = compile_patterns(&vec![r"sk-[a-z0-9]+".to_string()], "regex").unwrap();
-        let neg_regexes = compile_patterns(&vec!["test".to_string()], "keyword").unwrap();
-
-        let result = collect_matches_with_negative_context(text, &pos_regexes, &neg_regexes, 0);
+        let compiled = compile_pattern_set(
+            &[r"sk-[a-z0-9]+".to_string()],
+            "regex",
+            Some(&vec!["test".to_string()]),
+            Some("keyword"),
+            &EntropyOptions::default(),
+            None,
+        )
+        .unwrap();
+
+        let result = collect_matches_with_context(text, &compiled, 0);
 
         // Only sk-prod456 should remain (sk-test123 excluded due to "testing" in context)
         assert_eq!(result.matches.len(), 1);
@@ -242,7 +663,7 @@ mod tests {
         let text = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaXXXXXXbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
         // XXXXXX is at position 42-48 (0-indexed)
         // Context should be 30 chars before (positions 12-42) + match + 30 chars after
-        let context = get_match_context(text, 42, 48);
+        let context = get_match_context(text, 42, 48, DEFAULT_CONTEXT_WINDOW);
 
         // Context should not include chars before position 12
         assert!(context.len() <= 30 + 6 + 30); // 30 before + match + 30 after
@@ -257,8 +678,129 @@ mod tests {
 
     #[test]
     fn test_collect_matches() {
-        let regexes = compile_patterns(&vec![r"\d+".to_string()], "regex").unwrap();
-        let result = collect_matches_with_negative_context("123 456 123", &regexes, &[], 0);
+        let compiled =
+            compile_pattern_set(&[r"\d+".to_string()], "regex", None, None, &EntropyOptions::default(), None).unwrap();
+        let result = collect_matches_with_context("123 456 123", &compiled, 0);
         assert_eq!(result.matches.len(), 2); // unique: 123, 456
     }
+
+    #[test]
+    fn test_shannon_entropy() {
+        assert_eq!(shannon_entropy(""), 0.0);
+        assert_eq!(shannon_entropy("aaaa"), 0.0);
+        assert!((shannon_entropy("abcd") - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_entropy_patterns_find_random_tokens_only() {
+        let alphabets = vec!["base64".to_string(), "hex".to_string()];
+        let compiled = compile_pattern_set(&alphabets, "entropy", None, None, &EntropyOptions::default(), None).unwrap();
+        let text = "token=q7Vx2LmN9pRt4KwZ8bYc3HdJ6fGs and hex 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822c                     but not handle_request_response_builder or aaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+        let result = collect_matches_with_context(text, &compiled, 0);
+        assert_eq!(
+            result.matches,
+            vec!["q7Vx2LmN9pRt4KwZ8bYc3HdJ6fGs", "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822c"]
+        );
+    }
+
+    #[test]
+    fn test_entropy_options_are_validated() {
+        let alphabets = vec!["base32".to_string()];
+        assert!(compile_entropy_patterns(&alphabets, &EntropyOptions::default()).is_err());
+
+        let alphabets = vec!["hex".to_string()];
+        let short = EntropyOptions { threshold: None, min_length: Some(4) };
+        assert!(compile_entropy_patterns(&alphabets, &short).is_err());
+        let strict = EntropyOptions { threshold: Some(3.5), min_length: Some(32) };
+        let (regexes, thresholds) = compile_entropy_patterns(&alphabets, &strict).unwrap();
+        assert!(!regexes[0].is_match("9f86d081884c7d659a2f"));
+        assert_eq!(thresholds, vec![3.5]);
+    }
+
+    #[test]
+    fn test_validator_patterns_keep_valid_numbers_only() {
+        let candidates = vec![r"\b\d{4}(?:[ -]?\d{4}){3}\b".to_string()];
+        let compiled =
+            compile_pattern_set(&candidates, "validator", None, None, &EntropyOptions::default(), Some("luhn")).unwrap();
+        let text = "card 4111 1111 1111 1111, order 4111 1111 1111 1112";
+        let result = collect_matches_with_context(text, &compiled, 0);
+        assert_eq!(result.matches, vec!["4111 1111 1111 1111"]);
+
+        assert!(compile_pattern_set(&candidates, "validator", None, None, &EntropyOptions::default(), None).is_err());
+        assert!(compile_pattern_set(&candidates, "validator", None, None, &EntropyOptions::default(), Some("crc32")).is_err());
+    }
+
+    #[test]
+    fn test_redact_group_scopes_matches() {
+        let patterns = vec![r"(?i)password\s*=\s*(?P<secret>\S+)".to_string(), r"sk-[a-z0-9]{6}".to_string()];
+        let compiled = compile_pattern_set(&patterns, "regex", None, None, &EntropyOptions::default(), None)
+            .unwrap()
+            .with_redact_group("regex", Some("secret"))
+            .unwrap();
+        assert_eq!(compiled.redact_groups, vec![Some(1), None]);
+
+        // min_unique_chars applies to the group, not to "password = "
+        let text = "password = hunter2 and PASSWORD=aaaa with sk-abc123";
+        let result = collect_matches_with_context(text, &compiled, 3);
+        assert_eq!(result.matches, vec!["hunter2", "sk-abc123"]);
+        assert_eq!(result.highlights[0].before, "password = ");
+        assert_eq!(result.highlights[0].after, "");
+
+        let compiled = compile_pattern_set(&patterns, "regex", None, None, &EntropyOptions::default(), None).unwrap();
+        assert!(compiled.clone().with_redact_group("regex", Some("token")).is_err());
+        assert!(compiled.with_redact_group("keyword", Some("secret")).is_err());
+    }
+
+    #[test]
+    fn test_required_context_and_window() {
+        let patterns = vec![r"\b[A-Za-z0-9]{12}\b".to_string()];
+        let required = vec!["password".to_string(), "secret".to_string()];
+        let compiled = compile_pattern_set(&patterns, "regex", None, None, &EntropyOptions::default(), None)
+            .unwrap()
+            .with_context(Some(10), Some(&required), Some("keyword"))
+            .unwrap();
+        assert_eq!(compiled.context_window, 10);
+
+        let text = "password: Xk2mP9qL4vZ8 but the order id is Ab3dE5gH7jK9";
+        let result = collect_matches_with_context(text, &compiled, 0);
+        assert_eq!(result.matches, vec!["Xk2mP9qL4vZ8"]);
+        assert_eq!(result.candidates.len(), 2);
+        assert!(result.candidates[0].kept);
+        assert!(!result.candidates[1].kept);
+        assert_eq!(result.candidates[1].reason, "no required context within 10 characters");
+
+        // Negative context wins over required context
+        let negative = vec!["example".to_string()];
+        let compiled = compile_pattern_set(&patterns, "regex", Some(&negative), Some("keyword"), &EntropyOptions::default(), None)
+            .unwrap()
+            .with_context(None, Some(&required), Some("keyword"))
+            .unwrap();
+        assert_eq!(compiled.context_window, DEFAULT_CONTEXT_WINDOW);
+        let result = collect_matches_with_context("example password: Xk2mP9qL4vZ8", &compiled, 0);
+        assert!(result.matches.is_empty());
+        assert!(result.candidates[0].reason.starts_with("negative pattern"));
+
+        assert_eq!(
+            check_match_context("secret Xk2mP9qL4vZ8", 7, 19, 10, &[], NegativeScope::Context, &compiled.required_regexes),
+            ContextCheck::Passed
+        );
+        assert!(resolve_context_window(Some(MAX_CONTEXT_WINDOW as i32 + 1)).is_err());
+    }
+
+    #[test]
+    fn test_negative_scope_value() {
+        let patterns = vec![r"acme_[a-z0-9]{8}".to_string()];
+        let negative = vec!["(?i)example".to_string()];
+        let compiled = compile_pattern_set(&patterns, "regex", Some(&negative), Some("regex"), &EntropyOptions::default(), None)
+            .unwrap()
+            .with_negative_scope(Some("value"))
+            .unwrap();
+
+        // A stopword next to the value no longer drops it; one inside it does
+        let result = collect_matches_with_context("example key: acme_a1b2c3d4, acme_examplex", &compiled, 0);
+        assert_eq!(result.matches, vec!["acme_a1b2c3d4"]);
+        assert_eq!(result.candidates[1].reason, "negative pattern `(?i)example` in the value");
+
+        assert!(compiled.with_negative_scope(Some("file")).is_err());
+    }
 }
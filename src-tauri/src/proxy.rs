@@ -3,17 +3,22 @@
 use crate::backends::custom::CustomBackendSettings;
 use crate::backends::{Backend, ClaudeBackend, CodexBackend, CustomBackend};
 use crate::cursor_hooks::create_cursor_hooks_router;
-use crate::database::{get_dlp_action_from_db, get_last_notification_time, set_last_notification_time, Database, DLP_ACTION_BLOCKED, DLP_ACTION_PASSED, DLP_ACTION_REDACTED, DLP_ACTION_RATELIMITED, DLP_ACTION_NOTIFY_RATELIMIT};
-use crate::dlp::{apply_dlp_redaction, apply_dlp_unredaction, DlpDetection};
+use crate::database::{get_bind_address_from_db, get_last_notification_time, open_connection, set_last_notification_time, Database, DLP_ACTION_BLOCKED, DLP_ACTION_PASSED, DLP_ACTION_RATELIMITED, DLP_ACTION_NOTIFY_RATELIMIT};
+use crate::dlp::{apply_dlp_redaction, apply_dlp_unredaction, most_severe_action, DlpDetection, DlpPatternAction, DlpPatternRegistry};
+use crate::dlp_exceptions::ExceptionContext;
+use crate::dlp_stream::StreamingUnredactor;
 use crate::dlp_pattern_config::get_db_path;
+use crate::header_scrub::HeaderScrubPolicy;
+use crate::proxy_auth::{require_access_token, ProxyAuthState};
 use crate::requestresponsemetadata::ResponseMetadata;
-use crate::{PROXY_PORT, PROXY_STATUS, RESTART_SENDER, ProxyStatus};
+use crate::{DLP_PATTERNS, PROXY_PORT, PROXY_STATUS, REDACTION_VAULT, RESTART_SENDER, ProxyStatus};
 use tauri::{AppHandle, Emitter};
 
 use axum::{
     body::{Body, Bytes},
     extract::{Request, State},
     http::{HeaderMap, HeaderValue, Method, StatusCode},
+    middleware,
     response::{IntoResponse, Response},
     routing::get,
     Router,
@@ -23,7 +28,8 @@ use futures::StreamExt;
 use reqwest::Client;
 use std::collections::HashMap;
 use std::io::Read;
-use std::net::SocketAddr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::net::TcpListener;
@@ -74,21 +80,23 @@ impl RateLimiter {
     }
 }
 
-/// Convert axum HeaderMap to JSON string
-fn headers_to_json(headers: &HeaderMap) -> String {
-    let map: HashMap<String, String> = headers
+/// Convert axum HeaderMap to JSON string, masking credentials
+fn headers_to_json(headers: &HeaderMap, policy: &HeaderScrubPolicy) -> String {
+    let mut map: HashMap<String, String> = headers
         .iter()
         .map(|(k, v)| (k.as_str().to_string(), v.to_str().unwrap_or("").to_string()))
         .collect();
+    policy.scrub_map(&mut map);
     serde_json::to_string(&map).unwrap_or_else(|_| "{}".to_string())
 }
 
-/// Convert reqwest HeaderMap to JSON string
-fn reqwest_headers_to_json(headers: &reqwest::header::HeaderMap) -> String {
-    let map: HashMap<String, String> = headers
+/// Convert reqwest HeaderMap to JSON string, masking credentials
+fn reqwest_headers_to_json(headers: &reqwest::header::HeaderMap, policy: &HeaderScrubPolicy) -> String {
+    let mut map: HashMap<String, String> = headers
         .iter()
         .map(|(k, v)| (k.as_str().to_string(), v.to_str().unwrap_or("").to_string()))
         .collect();
+    policy.scrub_map(&mut map);
     serde_json::to_string(&map).unwrap_or_else(|_| "{}".to_string())
 }
 
@@ -113,6 +121,62 @@ fn format_detection_patterns(detections: &[DlpDetection]) -> String {
     pattern_names.join(", ")
 }
 
+/// Status code for `requests.dlp_action`: the most severe detection action,
+/// otherwise notify-ratelimit if flagged, otherwise passed
+fn dlp_status_code(detections: &[DlpDetection], notify_ratelimit: bool) -> i32 {
+    match most_severe_action(detections) {
+        Some(action) => action.status_code(),
+        None if notify_ratelimit => DLP_ACTION_NOTIFY_RATELIMIT,
+        None => DLP_ACTION_PASSED,
+    }
+}
+
+/// Unix time of the last notify-action desktop notification
+static LAST_DLP_NOTIFICATION: AtomicU64 = AtomicU64::new(0);
+
+/// Show a desktop notification for notify-action detections
+/// At most one notification per minute, since agents resend the whole history every turn
+pub fn notify_dlp_detections(app_handle: &AppHandle, source: &str, detections: &[DlpDetection]) {
+    let notified: Vec<DlpDetection> = detections
+        .iter()
+        .filter(|d| d.is_enforced() && d.action == DlpPatternAction::Notify)
+        .cloned()
+        .collect();
+    if notified.is_empty() {
+        return;
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let last = LAST_DLP_NOTIFICATION.load(Ordering::Relaxed);
+    if now.saturating_sub(last) < 60
+        || LAST_DLP_NOTIFICATION
+            .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+            .is_err()
+    {
+        return;
+    }
+
+    let body = format!(
+        "{}: sensitive data sent ({})",
+        source,
+        format_detection_patterns(&notified)
+    );
+    let app_handle = app_handle.clone();
+    // Send notification in background to not block the request
+    tokio::spawn(async move {
+        use tauri_plugin_notification::NotificationExt;
+        let _ = app_handle
+            .notification()
+            .builder()
+            .title("LLMwatcher")
+            .body(body)
+            .show();
+    });
+}
+
 /// Estimate token count from text (words * 1.5)
 fn estimate_tokens(text: &str) -> u32 {
     let word_count = text.split_whitespace().count();
@@ -148,6 +212,8 @@ struct ProxyState {
     db: Database,
     backend: Arc<dyn Backend>,
     rate_limiter: RateLimiter,
+    dlp_patterns: DlpPatternRegistry,
+    header_scrub: Arc<HeaderScrubPolicy>,
     app_handle: AppHandle,
 }
 
@@ -192,7 +258,7 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request) -> impl In
 
     let request_body_str = String::from_utf8_lossy(&body_bytes).to_string();
     let req_meta = backend.parse_request_metadata(&request_body_str);
-    let request_headers_json = headers_to_json(&headers);
+    let request_headers_json = headers_to_json(&headers, &state.header_scrub);
     let should_log = backend.should_log(&request_body_str);
 
     // Track if we should use notify-ratelimit status (token limit exceeded in notify mode)
@@ -218,7 +284,7 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request) -> impl In
             let resp_meta = ResponseMetadata::default();
             let _ = db.log_request(
                 backend.name(),
-                &method.to_string(),
+                method.as_ref(),
                 &full_path,
                 "Messages",
                 &request_body_str,
@@ -266,7 +332,7 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request) -> impl In
                 let resp_meta = ResponseMetadata::default();
                 let _ = db.log_request(
                     backend.name(),
-                    &method.to_string(),
+                    method.as_ref(),
                     &full_path,
                     "Messages",
                     &request_body_str,
@@ -320,8 +386,23 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request) -> impl In
     let dlp_enabled = backend.is_dlp_enabled();
 
     // Apply DLP redaction to request body (only if DLP is enabled)
+    // The snapshot is taken once so the whole request sees one consistent pattern set
     let dlp_result = if dlp_enabled {
-        apply_dlp_redaction(&request_body_str)
+        let dlp_snapshot = state.dlp_patterns.snapshot();
+        // Placeholders are kept per agent session, so a value gets the same one every turn
+        let session_key = backend
+            .session_key(&request_body_str, &headers)
+            .map(|key| format!("{}:{}", backend.name(), key));
+        let mut vault = REDACTION_VAULT.open_session(session_key);
+        let context = ExceptionContext {
+            backend: backend.name(),
+            file_path: None,
+        };
+        let result = apply_dlp_redaction(&request_body_str, &dlp_snapshot, backend.dlp_scan_scope(), &context, &mut vault);
+        if let Some(write) = REDACTION_VAULT.commit(vault) {
+            tokio::task::spawn_blocking(move || REDACTION_VAULT.persist(write));
+        }
+        result
     } else {
         // No DLP - pass through unchanged
         crate::dlp::DlpRedactionResult {
@@ -333,15 +414,22 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request) -> impl In
     let redacted_body = dlp_result.redacted_body;
     let dlp_replacements = dlp_result.replacements;
 
-    // Check if we should block (instead of redact) when DLP detections are found
-    let dlp_action = get_dlp_action_from_db();
-    if dlp_enabled && dlp_action == "block" && !dlp_result.detections.is_empty() {
+    notify_dlp_detections(&state.app_handle, backend.name(), &dlp_result.detections);
+
+    // Block the request if any matching pattern has the block action
+    if most_severe_action(&dlp_result.detections) == Some(DlpPatternAction::Block) {
+        let blocking: Vec<DlpDetection> = dlp_result
+            .detections
+            .iter()
+            .filter(|d| d.is_enforced() && d.action == DlpPatternAction::Block)
+            .cloned()
+            .collect();
         println!(
             "[PROXY] Blocking request due to DLP detections: {} patterns",
-            dlp_result.detections.len()
+            blocking.len()
         );
 
-        let pattern_names = format_detection_patterns(&dlp_result.detections);
+        let pattern_names = format_detection_patterns(&blocking);
         let error_body = if backend.name() == "codex" {
             create_codex_error_response(&pattern_names)
         } else {
@@ -350,12 +438,12 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request) -> impl In
 
         // Log the blocked request
         if backend.should_log(&request_body_str) {
-            let request_headers_json = headers_to_json(&headers);
+            let request_headers_json = headers_to_json(&headers, &state.header_scrub);
             let resp_meta = ResponseMetadata::default();
 
             if let Ok(request_id) = db.log_request(
                 backend.name(),
-                &method.to_string(),
+                method.as_ref(),
                 &full_path,
                 "Messages",
                 &request_body_str,
@@ -463,45 +551,45 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request) -> impl In
         let req_body_clone = request_body_str.clone();
         let status_code = status.as_u16();
         let req_meta_clone = req_meta.clone();
-        let dlp_replacements_clone = dlp_replacements.clone();
         let dlp_detections_clone = dlp_result.detections.clone();
         let headers_clone = headers.clone();
-        let request_headers_json = headers_to_json(&headers);
-        let response_headers_json = reqwest_headers_to_json(&resp_headers);
+        let request_headers_json = headers_to_json(&headers, &state.header_scrub);
+        let response_headers_json = reqwest_headers_to_json(&resp_headers, &state.header_scrub);
         let notify_ratelimit_clone = notify_ratelimit;
 
-        let collected_chunks: Arc<std::sync::Mutex<Vec<String>>> =
-            Arc::new(std::sync::Mutex::new(Vec::new()));
-        let chunks_for_stream = collected_chunks.clone();
-        let dlp_for_stream = dlp_replacements.clone();
+        // Placeholders can be split across chunks and SSE deltas, so unredaction
+        // is stateful for the lifetime of the stream
+        let mut unredactor = StreamingUnredactor::new(&dlp_replacements);
 
         println!("[PROXY] Starting streaming response...");
-        let stream = response.bytes_stream().map(move |result| {
-            match result {
-                Ok(bytes) => {
-                    let chunk_str = String::from_utf8_lossy(&bytes).to_string();
-                    chunks_for_stream.lock().unwrap().push(chunk_str.clone());
-
-                    // Apply DLP unredaction to each chunk
-                    let unredacted_chunk = apply_dlp_unredaction(&chunk_str, &dlp_for_stream);
-                    Ok(Bytes::from(unredacted_chunk))
-                }
-                Err(e) => {
-                    println!("[PROXY] Stream error: {}", e);
-                    Err(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
+        let logged_stream = async_stream::stream! {
+            // Collect exactly what the client receives (already unredacted)
+            let mut collected: Vec<u8> = Vec::new();
+            let mut upstream = response.bytes_stream();
+            while let Some(result) = upstream.next().await {
+                match result {
+                    Ok(bytes) => {
+                        let unredacted = unredactor.push(&bytes);
+                        if !unredacted.is_empty() {
+                            collected.extend_from_slice(&unredacted);
+                            yield Ok(Bytes::from(unredacted));
+                        }
+                    }
+                    Err(e) => {
+                        println!("[PROXY] Stream error: {}", e);
+                        yield Err(std::io::Error::other(e.to_string()));
+                    }
                 }
             }
-        });
 
-        let logged_stream = async_stream::stream! {
-            let mut inner = std::pin::pin!(stream);
-            while let Some(item) = inner.next().await {
-                yield item;
+            let remaining = unredactor.finish();
+            if !remaining.is_empty() {
+                collected.extend_from_slice(&remaining);
+                yield Ok(Bytes::from(remaining));
             }
 
             let latency_ms = start_time.elapsed().as_millis() as u64;
-            let response_body = collected_chunks.lock().unwrap().join("");
-            let unredacted_response = apply_dlp_unredaction(&response_body, &dlp_replacements_clone);
+            let unredacted_response = String::from_utf8_lossy(&collected).to_string();
             let resp_meta = backend_clone.parse_response_metadata(&unredacted_response, true);
 
             // Only log if backend says we should
@@ -513,15 +601,7 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request) -> impl In
                     &headers_clone,
                 );
 
-                // Determine dlp_action: notify-ratelimit if flagged and no DLP detections,
-                // otherwise redacted if detections, otherwise passed
-                let dlp_action_value = if notify_ratelimit_clone && dlp_detections_clone.is_empty() {
-                    DLP_ACTION_NOTIFY_RATELIMIT
-                } else if dlp_detections_clone.is_empty() {
-                    DLP_ACTION_PASSED
-                } else {
-                    DLP_ACTION_REDACTED
-                };
+                let dlp_action_value = dlp_status_code(&dlp_detections_clone, notify_ratelimit_clone);
 
                 if let Ok(request_id) = db_clone.log_request(
                     &backend_name,
@@ -602,18 +682,10 @@ async fn proxy_handler(State(state): State<ProxyState>, req: Request) -> impl In
             );
 
             // Convert headers to JSON
-            let request_headers_json = headers_to_json(&headers);
-            let response_headers_json = reqwest_headers_to_json(&resp_headers);
-
-            // Determine dlp_action: notify-ratelimit if flagged and no DLP detections,
-            // otherwise redacted if detections, otherwise passed
-            let dlp_action_value = if notify_ratelimit && dlp_result.detections.is_empty() {
-                DLP_ACTION_NOTIFY_RATELIMIT
-            } else if dlp_result.detections.is_empty() {
-                DLP_ACTION_PASSED
-            } else {
-                DLP_ACTION_REDACTED
-            };
+            let request_headers_json = headers_to_json(&headers, &state.header_scrub);
+            let response_headers_json = reqwest_headers_to_json(&resp_headers, &state.header_scrub);
+
+            let dlp_action_value = dlp_status_code(&dlp_result.detections, notify_ratelimit);
 
             if let Ok(request_id) = db.log_request(
                 backend.name(),
@@ -681,8 +753,16 @@ pub async fn start_proxy_server(app_handle: AppHandle) {
             Err(e) => eprintln!("Failed to cleanup old data: {}", e),
         }
 
+        // Drop DLP placeholders of sessions idle for longer than the vault TTL
+        REDACTION_VAULT.load_settings();
+        let purged = REDACTION_VAULT.purge_expired();
+        if purged > 0 {
+            println!("[DLP] Purged {} expired vault entries", purged);
+        }
+
         // Spawn background compression worker
         // Runs every 5 minutes, compresses in short bursts to avoid blocking live requests
+        // (and drops expired DLP vault sessions and re-syncs changed rule imports)
         {
             let db_for_compression = db.clone();
             tokio::spawn(async move {
@@ -700,6 +780,20 @@ pub async fn start_proxy_server(app_handle: AppHandle) {
                             println!("[DB] Background compression maintenance completed");
                         }
                     }
+
+                    let _ = tokio::task::spawn_blocking(|| REDACTION_VAULT.purge_expired()).await;
+
+                    // Rule files edited while the proxy runs take effect without a restart
+                    let _ = tokio::task::spawn_blocking(|| {
+                        let Ok(conn) = open_connection() else {
+                            return;
+                        };
+                        let synced = crate::rule_import::sync_changed_rule_imports(&conn);
+                        if synced > 0 {
+                            println!("[DLP] Re-synced {} changed rule import(s)", synced);
+                            DLP_PATTERNS.reload();
+                        }
+                    }).await;
                 }
             });
         }
@@ -707,6 +801,27 @@ pub async fn start_proxy_server(app_handle: AppHandle) {
         // Create shared rate limiter
         let rate_limiter = RateLimiter::new();
 
+        // Pick up changes to imported gitleaks / trufflehog rule files
+        if let Ok(conn) = open_connection() {
+            let synced = crate::rule_import::sync_changed_rule_imports(&conn);
+            if synced > 0 {
+                println!("[DLP] Re-synced {} changed rule import(s)", synced);
+            }
+
+            // Known secrets may have been rotated since the last start
+            let values = crate::known_secrets::sync_all_sources(&conn);
+            if values > 0 {
+                println!("[DLP] Loaded {} known secret value(s)", values);
+            }
+        }
+
+        // Compile DLP patterns now that builtins have been seeded
+        let dlp_patterns = DLP_PATTERNS.clone();
+        dlp_patterns.reload();
+
+        // Load header scrubbing policy (built-in + user-added sensitive headers)
+        let header_scrub = Arc::new(HeaderScrubPolicy::load());
+
         // Load predefined backend settings
         let claude_settings = db
             .get_predefined_backend_settings("claude")
@@ -740,14 +855,18 @@ pub async fn start_proxy_server(app_handle: AppHandle) {
         // Create states for each backend
         let claude_state = ProxyState {
             db: db.clone(),
+            dlp_patterns: dlp_patterns.scoped(claude_backend.dlp_scope()),
             backend: claude_backend,
             rate_limiter: rate_limiter.clone(),
+            header_scrub: header_scrub.clone(),
             app_handle: app_handle.clone(),
         };
         let codex_state = ProxyState {
             db: db.clone(),
+            dlp_patterns: dlp_patterns.scoped(codex_backend.dlp_scope()),
             backend: codex_backend,
             rate_limiter: rate_limiter.clone(),
+            header_scrub: header_scrub.clone(),
             app_handle: app_handle.clone(),
         };
 
@@ -779,7 +898,9 @@ pub async fn start_proxy_server(app_handle: AppHandle) {
         let cursor_hooks_router = create_cursor_hooks_router(
             db.clone(),
             rate_limiter.clone(),
+            dlp_patterns.scoped(cursor_hooks_settings.dlp_scope()),
             cursor_hooks_settings,
+            app_handle.clone(),
         );
 
         // Build base app with builtin backends
@@ -790,7 +911,7 @@ pub async fn start_proxy_server(app_handle: AppHandle) {
             .nest("/cursor_hook", cursor_hooks_router);
 
         // Load and add custom backends
-        let custom_backends = Database::new(&get_db_path())
+        let custom_backends = Database::new(get_db_path())
             .ok()
             .and_then(|db| db.get_enabled_custom_backends().ok())
             .unwrap_or_default();
@@ -822,8 +943,10 @@ pub async fn start_proxy_server(app_handle: AppHandle) {
 
             let custom_state = ProxyState {
                 db: db.clone(),
+                dlp_patterns: dlp_patterns.scoped(custom_backend.dlp_scope()),
                 backend: custom_backend,
                 rate_limiter: rate_limiter.clone(),
+                header_scrub: header_scrub.clone(),
                 app_handle: app_handle.clone(),
             };
             let custom_router = Router::new()
@@ -833,7 +956,22 @@ pub async fn start_proxy_server(app_handle: AppHandle) {
             app = app.nest(&route_path, custom_router);
         }
 
-        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        // Every route is also reachable behind /t/<token> for clients that can only
+        // carry the access token in their base URL (nest_service keeps the nested
+        // backend fallbacks, which `nest` would drop)
+        let bind_address: IpAddr = get_bind_address_from_db()
+            .parse()
+            .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let auth = ProxyAuthState::load(bind_address);
+        println!(
+            "[PROXY] Access token {}",
+            if auth.is_required() { "required" } else { "optional" }
+        );
+        let app = Router::new()
+            .nest_service("/t/:token", app.clone())
+            .merge(app)
+            .layer(middleware::from_fn_with_state(auth, require_access_token));
+        let addr = SocketAddr::new(bind_address, port);
         let listener = match TcpListener::bind(addr).await {
             Ok(l) => l,
             Err(e) => {
@@ -852,7 +990,25 @@ pub async fn start_proxy_server(app_handle: AppHandle) {
                 continue;
             }
         };
-        println!("Proxy server running on http://0.0.0.0:{}", port);
+        println!("Proxy server running on http://{}", addr);
+
+        // On the default loopback address also listen on ::1, since `localhost`
+        // may resolve to IPv6 first (best effort, not every system has ::1)
+        let loopback_v6_listener = if bind_address == IpAddr::V4(Ipv4Addr::LOCALHOST) {
+            let addr_v6 = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), port);
+            match TcpListener::bind(addr_v6).await {
+                Ok(l) => {
+                    println!("Proxy server running on http://{}", addr_v6);
+                    Some(l)
+                }
+                Err(e) => {
+                    println!("[PROXY] Not listening on {}: {}", addr_v6, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
         // Set status to running
         {
             let mut status = PROXY_STATUS.lock().unwrap();
@@ -864,29 +1020,55 @@ pub async fn start_proxy_server(app_handle: AppHandle) {
         }));
 
         // Create shutdown channel
-        let (tx, mut rx) = watch::channel(false);
+        let (tx, rx) = watch::channel(false);
         {
             let mut sender = RESTART_SENDER.lock().unwrap();
             *sender = Some(tx);
         }
 
-        // Run server with graceful shutdown
-        let server = axum::serve(listener, app).with_graceful_shutdown(async move {
-            loop {
-                rx.changed().await.ok();
-                if *rx.borrow() {
-                    println!("Received restart signal, shutting down proxy server...");
-                    break;
-                }
-            }
+        // Run server(s) with graceful shutdown
+        // ConnectInfo lets the access-token middleware log who was rejected
+        let make_service = app.into_make_service_with_connect_info::<SocketAddr>();
+
+        let loopback_v6_server = loopback_v6_listener.map(|listener_v6| {
+            let rx_v6 = rx.clone();
+            let make_service = make_service.clone();
+            tokio::spawn(async move {
+                axum::serve(listener_v6, make_service)
+                    .with_graceful_shutdown(wait_for_restart_signal(rx_v6))
+                    .await
+            })
         });
 
+        let server = axum::serve(listener, make_service)
+            .with_graceful_shutdown(wait_for_restart_signal(rx.clone()));
+
         if let Err(e) = server.await {
             eprintln!("Proxy server error: {}", e);
         }
 
+        // Wait for the ::1 listener too so the port is free before rebinding
+        if let Some(handle) = loopback_v6_server {
+            if let Ok(Err(e)) = handle.await {
+                eprintln!("Proxy server error: {}", e);
+            }
+        }
+
         println!("Proxy server stopped, restarting with new configuration...");
         // Small delay before restart
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
     }
 }
+
+/// Resolves once a restart has been requested
+async fn wait_for_restart_signal(mut rx: watch::Receiver<bool>) {
+    loop {
+        if rx.changed().await.is_err() {
+            break;
+        }
+        if *rx.borrow() {
+            println!("Received restart signal, shutting down proxy server...");
+            break;
+        }
+    }
+}
@@ -0,0 +1,244 @@
+// Proxy Access Control
+//
+// The proxy binds to loopback by default. When an access token is required
+// (always, on a non-loopback address), every request must present it, either in the `X-LLMWatcher-Token` header or
+// as a `/t/<token>` path prefix (for clients that can only be configured with
+// a base URL, e.g. ANTHROPIC_BASE_URL / OPENAI_BASE_URL).
+
+use crate::database::{get_or_create_access_token, get_require_token_from_db};
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, OriginalUri, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+/// Header clients can use to present the access token
+pub const ACCESS_TOKEN_HEADER: &str = "x-llmwatcher-token";
+
+/// Path prefix clients can use to present the access token: /t/<token>/...
+pub const TOKEN_PATH_PREFIX: &str = "/t/";
+
+/// Access settings loaded when the proxy (re)starts
+#[derive(Clone)]
+pub struct ProxyAuthState {
+    token: Arc<String>,
+    required: bool,
+}
+
+impl ProxyAuthState {
+    /// A token is required on any address other machines can reach, whatever
+    /// the setting says
+    pub fn new(token: String, required: bool, bind_address: IpAddr) -> Self {
+        Self {
+            token: Arc::new(token),
+            required: required || !bind_address.is_loopback(),
+        }
+    }
+
+    pub fn load(bind_address: IpAddr) -> Self {
+        let token = get_or_create_access_token().unwrap_or_else(|e| {
+            eprintln!("[PROXY] Failed to load access token: {}", e);
+            String::new()
+        });
+        Self::new(token, get_require_token_from_db(), bind_address)
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    fn is_valid(&self, presented: &str) -> bool {
+        !self.token.is_empty() && tokens_match(presented, &self.token)
+    }
+}
+
+/// Generate a new random access token
+pub fn generate_access_token() -> String {
+    let bytes: [u8; 24] = rand::random();
+    hex::encode(bytes)
+}
+
+/// Base URL clients should be configured with (always carries the path token,
+/// so installed clients keep working when the token requirement is turned on)
+pub fn client_base_url(port: u16, route: &str) -> Result<String, String> {
+    let token = get_or_create_access_token()?;
+    Ok(format!("http://localhost:{}{}{}{}", port, TOKEN_PATH_PREFIX, token, route))
+}
+
+/// Constant-time comparison so the token cannot be guessed byte by byte
+fn tokens_match(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Hide the path token when logging a path
+fn redact_path_token(path: &str) -> String {
+    match path.strip_prefix(TOKEN_PATH_PREFIX) {
+        Some(rest) => match rest.find('/') {
+            Some(idx) => format!("{}***{}", TOKEN_PATH_PREFIX, &rest[idx..]),
+            None => format!("{}***", TOKEN_PATH_PREFIX),
+        },
+        None => path.to_string(),
+    }
+}
+
+/// Middleware that rejects requests without a valid access token
+/// - A `/t/<token>` path prefix is always checked, even when tokens are optional
+/// - The token header is removed before the request is proxied upstream or logged
+/// - The health check (`/`) is always allowed
+pub async fn require_access_token(
+    State(auth): State<ProxyAuthState>,
+    ConnectInfo(remote): ConnectInfo<SocketAddr>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let path = req
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+
+    let header_token = req
+        .headers_mut()
+        .remove(ACCESS_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok().map(|s| s.to_string()));
+
+    let authorized = match path.strip_prefix(TOKEN_PATH_PREFIX) {
+        Some(rest) => auth.is_valid(rest.split('/').next().unwrap_or("")),
+        None if path == "/" || !auth.required => true,
+        None => header_token.as_deref().map(|t| auth.is_valid(t)).unwrap_or(false),
+    };
+
+    if authorized {
+        return next.run(req).await;
+    }
+
+    println!(
+        "[PROXY] Rejected unauthenticated request from {}: {} {}",
+        remote,
+        req.method(),
+        redact_path_token(&path)
+    );
+
+    let error_body = serde_json::json!({
+        "error": {
+            "message": "Missing or invalid LLMWatcher access token",
+            "type": "authentication_error",
+            "code": "invalid_access_token"
+        }
+    })
+    .to_string();
+
+    Response::builder()
+        .status(StatusCode::UNAUTHORIZED)
+        .header("Content-Type", "application/json")
+        .body(Body::from(error_body))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::connect_info::MockConnectInfo;
+    use axum::{middleware, routing::post, Router};
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    fn auth(required: bool) -> ProxyAuthState {
+        ProxyAuthState {
+            token: Arc::new(TOKEN.to_string()),
+            required,
+        }
+    }
+
+    /// Same layering as the proxy: every route also behind /t/<token>
+    fn app(auth: ProxyAuthState) -> Router {
+        // Echo whether the token header reached the proxied service
+        let messages = Router::new().route(
+            "/v1/messages",
+            post(|req: Request| async move {
+                if req.headers().contains_key(ACCESS_TOKEN_HEADER) { "leaked" } else { "ok" }
+            }),
+        );
+        let app = Router::new().route("/", axum::routing::get(|| async { "up" })).nest("/claude", messages);
+        Router::new()
+            .nest_service("/t/:token", app.clone())
+            .merge(app)
+            .layer(middleware::from_fn_with_state(auth, require_access_token))
+            .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 4000))))
+    }
+
+    async fn send(auth: ProxyAuthState, method: &str, path: &str, header: Option<&str>) -> (StatusCode, String) {
+        let mut req = Request::builder().method(method).uri(path);
+        if let Some(token) = header {
+            req = req.header(ACCESS_TOKEN_HEADER, token);
+        }
+        let response = app(auth).oneshot(req.body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8_lossy(&body).to_string())
+    }
+
+    #[test]
+    fn test_tokens_match_and_path_redaction() {
+        assert!(tokens_match(TOKEN, TOKEN));
+        assert!(!tokens_match(TOKEN, &TOKEN[1..]));
+        assert!(!tokens_match("abc", "abd"));
+        assert!(tokens_match("", ""));
+        // An unset token never authorizes, not even an empty presented one
+        let unset = ProxyAuthState { token: Arc::new(String::new()), required: true };
+        assert!(!unset.is_valid(""));
+
+        assert_eq!(redact_path_token(&format!("/t/{}/claude/v1/messages", TOKEN)), "/t/***/claude/v1/messages");
+        assert_eq!(redact_path_token(&format!("/t/{}", TOKEN)), "/t/***");
+        assert_eq!(redact_path_token("/claude/v1/messages"), "/claude/v1/messages");
+    }
+
+    #[tokio::test]
+    async fn test_path_token_reaches_nested_service() {
+        let bad = send(auth(false), "POST", "/t/wrong/claude/v1/messages", None).await;
+        assert_eq!(bad.0, StatusCode::UNAUTHORIZED);
+        assert!(bad.1.contains("invalid_access_token"));
+
+        let good = send(auth(false), "POST", &format!("/t/{}/claude/v1/messages", TOKEN), None).await;
+        assert_eq!(good, (StatusCode::OK, "ok".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_header_token_when_required() {
+        assert_eq!(send(auth(true), "POST", "/claude/v1/messages", None).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(send(auth(true), "POST", "/claude/v1/messages", Some("wrong")).await.0, StatusCode::UNAUTHORIZED);
+        // The header is removed before the request is proxied
+        assert_eq!(
+            send(auth(true), "POST", "/claude/v1/messages", Some(TOKEN)).await,
+            (StatusCode::OK, "ok".to_string())
+        );
+        // Optional tokens: no token is fine, and a header is still stripped
+        assert_eq!(send(auth(false), "POST", "/claude/v1/messages", None).await.0, StatusCode::OK);
+        assert_eq!(send(auth(false), "POST", "/claude/v1/messages", Some("x")).await.1, "ok");
+        // The health check never needs a token
+        assert_eq!(send(auth(true), "GET", "/", None).await, (StatusCode::OK, "up".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_token_required_off_loopback() {
+        let exposed = ProxyAuthState::new(TOKEN.to_string(), false, IpAddr::from([0, 0, 0, 0]));
+        assert!(exposed.is_required());
+        assert_eq!(send(exposed.clone(), "POST", "/claude/v1/messages", None).await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            send(exposed, "POST", "/claude/v1/messages", Some(TOKEN)).await,
+            (StatusCode::OK, "ok".to_string())
+        );
+
+        // Loopback addresses keep the setting
+        assert!(!ProxyAuthState::new(TOKEN.to_string(), false, IpAddr::from([127, 0, 0, 1])).is_required());
+        assert!(!ProxyAuthState::new(TOKEN.to_string(), false, "::1".parse().unwrap()).is_required());
+    }
+}
@@ -0,0 +1,506 @@
+// Retroactive DLP Scan
+//
+// Runs a pattern set over request bodies already in the database, to find out
+// whether something a new pattern catches was sent before the pattern existed.
+// The set is either the current one (enabled and shadow patterns, known
+// secrets and exceptions, no backend scopes) or candidate patterns picked by
+// id, enabled or not. Every part of a request is scanned, whatever the
+// backend's scan scope was at the time: all of it reached the provider.
+// Cursor hook requests are scanned field by field (attached files are not
+// re-read, they may have changed since).
+//
+// Results go to `dlp_retro_detections`, one `dlp_retro_scans` row per scan,
+// so replayed detections never count as live ones or change a request's
+// status. Values are stored under the detection storage mode, like live ones.
+//
+// The scan runs on its own thread and connection, never the proxy's
+// `Database` (whose mutex live requests wait on). Requests are read in
+// id-ordered batches, each a short read that only decompresses the bodies of
+// its own rows. Progress is emitted after each batch; a cancel takes effect
+// before the next one.
+
+use crate::cursor_hooks::HOOKS_BACKEND;
+use crate::database::open_connection;
+use crate::detection_storage::DetectionValueEncoder;
+use crate::dlp::{
+    apply_dlp_redaction, check_dlp_patterns, compile_stored_patterns, DlpDetection, DlpPatternSnapshot, DlpScanScope,
+};
+use crate::dlp_exceptions::ExceptionContext;
+use crate::redaction_vault::VaultSession;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// Requests read and scanned per batch
+pub const BATCH_SIZE: i64 = 50;
+
+/// Event emitted with a `RetroScanProgress` after each batch and when a scan ends
+pub const PROGRESS_EVENT: &str = "dlp-retro-scan-progress";
+
+/// Every part of a request, since all of it was sent upstream
+const SENT_SCOPE: DlpScanScope = DlpScanScope {
+    user_messages: true,
+    tool_results: true,
+    system_prompt: true,
+    tool_definitions: true,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetroScanStatus {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+    /// Still marked running, but the app stopped before it finished
+    Interrupted,
+}
+
+impl RetroScanStatus {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "running" => Some(Self::Running),
+            "completed" => Some(Self::Completed),
+            "cancelled" => Some(Self::Cancelled),
+            "failed" => Some(Self::Failed),
+            "interrupted" => Some(Self::Interrupted),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Cancelled => "cancelled",
+            Self::Failed => "failed",
+            Self::Interrupted => "interrupted",
+        }
+    }
+}
+
+/// Which requests a scan covers and with which patterns
+#[derive(Clone, Debug)]
+pub struct RetroScanOptions {
+    /// Requests from this time on (RFC 3339)
+    pub since: String,
+    /// Only requests of this backend (None = all)
+    pub backend: Option<String>,
+    /// Candidate pattern ids (None = the current pattern set)
+    pub pattern_ids: Option<Vec<i64>>,
+    pub batch_size: i64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct RetroScanProgress {
+    pub scan_id: i64,
+    pub status: String,
+    pub requests_total: i64,
+    pub requests_scanned: i64,
+    pub detections: i64,
+    pub error: Option<String>,
+}
+
+/// Id and cancel flag of a scan
+type RunningScan = (i64, Arc<AtomicBool>);
+
+/// The scan in progress (one at a time)
+static RUNNING: LazyLock<Mutex<Option<RunningScan>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Id of the scan in progress, if any
+pub fn running_scan_id() -> Option<i64> {
+    RUNNING.lock().unwrap().as_ref().map(|(id, _)| *id)
+}
+
+/// Start a scan in the background; returns its id
+pub fn start(app_handle: AppHandle, options: RetroScanOptions) -> Result<i64, String> {
+    let mut running = RUNNING.lock().unwrap();
+    if running.is_some() {
+        return Err("A history scan is already running".to_string());
+    }
+
+    let conn = open_connection().map_err(|e| e.to_string())?;
+    // Live requests write to the same database
+    conn.busy_timeout(Duration::from_secs(5)).map_err(|e| e.to_string())?;
+
+    let current = crate::DLP_PATTERNS.snapshot();
+    let snapshot = match &options.pattern_ids {
+        Some(ids) => Arc::new(candidate_snapshot(&conn, ids)?.with_exceptions(current.exceptions.clone())),
+        None => current,
+    };
+    if snapshot.is_empty() {
+        return Err("No patterns to scan with".to_string());
+    }
+
+    let scan_id = create_scan(&conn, &options).map_err(|e| e.to_string())?;
+    let cancel = Arc::new(AtomicBool::new(false));
+    *running = Some((scan_id, cancel.clone()));
+    drop(running);
+
+    println!("[DLP] Retroactive scan {} started (requests since {})", scan_id, options.since);
+    std::thread::spawn(move || {
+        let progress = run_scan(&conn, scan_id, &snapshot, &options, &cancel, |p| {
+            let _ = app_handle.emit(PROGRESS_EVENT, p);
+        });
+        *RUNNING.lock().unwrap() = None;
+        println!(
+            "[DLP] Retroactive scan {} {}: {} requests, {} detections",
+            scan_id, progress.status, progress.requests_scanned, progress.detections
+        );
+        let _ = app_handle.emit(PROGRESS_EVENT, &progress);
+    });
+
+    Ok(scan_id)
+}
+
+/// Ask the running scan to stop after its current batch
+pub fn cancel(scan_id: i64) -> Result<(), String> {
+    match RUNNING.lock().unwrap().as_ref() {
+        Some((id, cancel)) if *id == scan_id => {
+            cancel.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+        _ => Err("Scan is not running".to_string()),
+    }
+}
+
+/// Stored patterns picked by id, all enforced (shadow mode does not matter here)
+fn candidate_snapshot(conn: &Connection, ids: &[i64]) -> Result<DlpPatternSnapshot, String> {
+    if ids.is_empty() {
+        return Err("No patterns selected".to_string());
+    }
+    let list: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    let mut patterns = Vec::new();
+    for (_, name, compiled) in compile_stored_patterns(conn, &format!("id IN ({})", list.join(", "))) {
+        let mut pattern = compiled.map_err(|e| format!("Pattern '{}': {}", name, e))?;
+        pattern.shadow = false;
+        if !pattern.is_empty() {
+            patterns.push(pattern);
+        }
+    }
+    Ok(DlpPatternSnapshot::new(0, patterns))
+}
+
+fn create_scan(conn: &Connection, options: &RetroScanOptions) -> Result<i64, rusqlite::Error> {
+    let requests_total: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM requests WHERE timestamp >= ?1 AND (?2 IS NULL OR backend = ?2)",
+        rusqlite::params![options.since, options.backend],
+        |row| row.get(0),
+    )?;
+    conn.execute(
+        "INSERT INTO dlp_retro_scans (started_at, status, since, backend, pattern_ids, requests_total)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            chrono::Utc::now().to_rfc3339(),
+            RetroScanStatus::Running.as_str(),
+            options.since,
+            options.backend,
+            options.pattern_ids.as_ref().map(|ids| serde_json::to_string(ids).unwrap_or_default()),
+            requests_total,
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// A stored request, as read for scanning
+struct StoredRequest {
+    id: i64,
+    timestamp: String,
+    backend: String,
+    body: String,
+    /// File a Cursor hook read (from the request's metadata)
+    file_path: Option<String>,
+}
+
+/// Scan every batch, then record how the scan ended
+pub(crate) fn run_scan(
+    conn: &Connection,
+    scan_id: i64,
+    snapshot: &DlpPatternSnapshot,
+    options: &RetroScanOptions,
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(&RetroScanProgress),
+) -> RetroScanProgress {
+    let mut progress = RetroScanProgress {
+        scan_id,
+        status: RetroScanStatus::Running.as_str().to_string(),
+        requests_total: conn
+            .query_row(
+                "SELECT requests_total FROM dlp_retro_scans WHERE id = ?1",
+                [scan_id],
+                |row| row.get(0),
+            )
+            .unwrap_or(0),
+        requests_scanned: 0,
+        detections: 0,
+        error: None,
+    };
+
+    let status = match scan_batches(conn, scan_id, snapshot, options, cancel, &mut progress, &mut on_progress) {
+        Ok(status) => status,
+        Err(e) => {
+            progress.error = Some(e.to_string());
+            RetroScanStatus::Failed
+        }
+    };
+    progress.status = status.as_str().to_string();
+
+    if let Err(e) = conn.execute(
+        "UPDATE dlp_retro_scans SET status = ?1, finished_at = ?2, error = ?3 WHERE id = ?4",
+        rusqlite::params![progress.status, chrono::Utc::now().to_rfc3339(), progress.error, scan_id],
+    ) {
+        eprintln!("[DLP] Failed to record end of retroactive scan {}: {}", scan_id, e);
+    }
+    progress
+}
+
+fn scan_batches(
+    conn: &Connection,
+    scan_id: i64,
+    snapshot: &DlpPatternSnapshot,
+    options: &RetroScanOptions,
+    cancel: &AtomicBool,
+    progress: &mut RetroScanProgress,
+    on_progress: &mut impl FnMut(&RetroScanProgress),
+) -> Result<RetroScanStatus, rusqlite::Error> {
+    let encoder = DetectionValueEncoder::load_with(conn)?;
+
+    // Start at the first request in range rather than walking older ids
+    let mut last_id: i64 = conn
+        .query_row(
+            "SELECT MIN(id) - 1 FROM requests WHERE timestamp >= ?1",
+            [&options.since],
+            |row| row.get::<_, Option<i64>>(0),
+        )?
+        .unwrap_or(0);
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(RetroScanStatus::Cancelled);
+        }
+
+        let batch = read_batch(conn, options, last_id)?;
+        let Some(last) = batch.last() else {
+            return Ok(RetroScanStatus::Completed);
+        };
+        last_id = last.id;
+
+        let found: Vec<(&StoredRequest, Vec<DlpDetection>)> =
+            batch.iter().map(|request| (request, scan_request(snapshot, request))).collect();
+        write_batch(conn, scan_id, &encoder, &found, last_id, progress)?;
+        on_progress(progress);
+    }
+}
+
+/// Next requests after `last_id`, oldest first
+fn read_batch(conn: &Connection, options: &RetroScanOptions, last_id: i64) -> Result<Vec<StoredRequest>, rusqlite::Error> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT id, timestamp, backend, request_body, json_extract(extra_metadata, '$.file_path')
+         FROM requests
+         WHERE id > ?1 AND timestamp >= ?2 AND (?3 IS NULL OR backend = ?3)
+         ORDER BY id LIMIT ?4",
+    )?;
+    let rows = stmt.query_map(
+        rusqlite::params![last_id, options.since, options.backend, options.batch_size],
+        |row| {
+            Ok(StoredRequest {
+                id: row.get(0)?,
+                timestamp: row.get(1)?,
+                backend: row.get(2)?,
+                body: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+                file_path: row.get(4)?,
+            })
+        },
+    )?;
+    rows.collect()
+}
+
+/// Detections in one stored request
+fn scan_request(snapshot: &DlpPatternSnapshot, request: &StoredRequest) -> Vec<DlpDetection> {
+    let context = ExceptionContext {
+        backend: &request.backend,
+        file_path: request.file_path.as_deref(),
+    };
+
+    if request.backend != HOOKS_BACKEND {
+        let mut vault = VaultSession::ephemeral();
+        return apply_dlp_redaction(&request.body, snapshot, SENT_SCOPE, &context, &mut vault).detections;
+    }
+
+    // Hook inputs are not chat requests: scan each field (prompt, file content, command, ...)
+    let Ok(input) = serde_json::from_str::<serde_json::Value>(&request.body) else {
+        return check_dlp_patterns(&request.body, snapshot, &context);
+    };
+    let mut texts = Vec::new();
+    collect_strings(&input, &mut texts);
+
+    let mut detections: Vec<DlpDetection> = Vec::new();
+    for text in texts {
+        for detection in check_dlp_patterns(text, snapshot, &context) {
+            if !detections.iter().any(|d| {
+                d.pattern_name == detection.pattern_name
+                    && d.action == detection.action
+                    && d.original_value == detection.original_value
+            }) {
+                detections.push(detection);
+            }
+        }
+    }
+    detections
+}
+
+fn collect_strings<'a>(value: &'a serde_json::Value, texts: &mut Vec<&'a str>) {
+    match value {
+        serde_json::Value::String(s) => texts.push(s),
+        serde_json::Value::Array(items) => items.iter().for_each(|v| collect_strings(v, texts)),
+        serde_json::Value::Object(fields) => fields.values().for_each(|v| collect_strings(v, texts)),
+        _ => {}
+    }
+}
+
+/// Store a batch's detections and the scan's progress in one transaction
+fn write_batch(
+    conn: &Connection,
+    scan_id: i64,
+    encoder: &DetectionValueEncoder,
+    found: &[(&StoredRequest, Vec<DlpDetection>)],
+    last_id: i64,
+    progress: &mut RetroScanProgress,
+) -> Result<(), rusqlite::Error> {
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut insert = tx.prepare_cached(
+            "INSERT INTO dlp_retro_detections (scan_id, request_id, request_timestamp, backend, pattern_name, pattern_type, original_value, message_index, value_fingerprint, value_storage, action, found_in, shadow, exception_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        )?;
+        for (request, detections) in found {
+            for detection in detections {
+                let (stored_value, fingerprint) = encoder.encode(&detection.original_value);
+                insert.execute(rusqlite::params![
+                    scan_id,
+                    request.id,
+                    request.timestamp,
+                    request.backend,
+                    detection.pattern_name,
+                    detection.pattern_type,
+                    stored_value,
+                    detection.message_index,
+                    fingerprint,
+                    encoder.mode.as_str(),
+                    detection.action.as_str(),
+                    detection.found_in.map(|v| v.as_str()),
+                    detection.shadow as i32,
+                    detection.exception_id,
+                ])?;
+                progress.detections += 1;
+            }
+        }
+    }
+
+    progress.requests_scanned += found.len() as i64;
+    tx.execute(
+        "UPDATE dlp_retro_scans SET requests_scanned = ?1, detections = ?2, last_request_id = ?3 WHERE id = ?4",
+        rusqlite::params![progress.requests_scanned, progress.detections, last_id, scan_id],
+    )?;
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+    use crate::dlp::{CompiledDlpPattern, DlpPatternAction};
+
+    fn test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        Database::create_schema(&conn).unwrap();
+
+        let requests = [
+            ("2026-01-01T00:00:00+00:00", "claude", r#"{"messages":[{"role":"user","content":"old sk-abcd0001"}]}"#, None),
+            ("2026-02-01T00:00:00+00:00", "claude", r#"{"system":"sk-abcd0002","messages":[{"role":"user","content":"hi"}]}"#, None),
+            ("2026-02-02T00:00:00+00:00", "codex", r#"{"input":[{"type":"message","role":"user","content":"nothing here"}]}"#, None),
+            ("2026-02-03T00:00:00+00:00", "cursor-hooks", r#"{"file_path":"/repo/.env","content":"KEY=sk-abcd0003"}"#, Some(r#"{"file_path":"/repo/.env"}"#)),
+        ];
+        for (timestamp, backend, body, metadata) in requests {
+            conn.execute(
+                "INSERT INTO requests (timestamp, backend, endpoint_name, method, path, request_body, extra_metadata)
+                 VALUES (?1, ?2, 'test', 'POST', '/', ?3, ?4)",
+                rusqlite::params![timestamp, backend, body, metadata],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn snapshot() -> DlpPatternSnapshot {
+        let pattern = CompiledDlpPattern {
+            action: DlpPatternAction::Block,
+            ..CompiledDlpPattern::for_test("API Keys", "regex", &[r"sk-[a-z0-9]{8}"])
+        };
+        DlpPatternSnapshot::new(1, vec![pattern])
+    }
+
+    fn options(batch_size: i64) -> RetroScanOptions {
+        RetroScanOptions {
+            since: "2026-01-15T00:00:00+00:00".to_string(),
+            backend: None,
+            pattern_ids: None,
+            batch_size,
+        }
+    }
+
+    #[test]
+    fn test_scan_covers_range_in_batches() {
+        let conn = test_db();
+        let scan_id = create_scan(&conn, &options(2)).unwrap();
+
+        let mut batches = Vec::new();
+        let progress = run_scan(&conn, scan_id, &snapshot(), &options(2), &AtomicBool::new(false), |p| {
+            batches.push(p.requests_scanned)
+        });
+        assert_eq!(batches, vec![2, 3]);
+        assert_eq!(progress.status, "completed");
+        assert_eq!((progress.requests_total, progress.requests_scanned, progress.detections), (3, 3, 2));
+
+        // The system prompt counts, the request before the range does not
+        let mut stmt = conn
+            .prepare("SELECT request_id, backend, value_storage FROM dlp_retro_detections ORDER BY id")
+            .unwrap();
+        let found: Vec<(i64, String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (2, "claude".to_string(), "masked".to_string()),
+                (4, "cursor-hooks".to_string(), "masked".to_string())
+            ]
+        );
+
+        let (status, scanned): (String, i64) = conn
+            .query_row(
+                "SELECT status, requests_scanned FROM dlp_retro_scans WHERE id = ?1",
+                [scan_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((status.as_str(), scanned), ("completed", 3));
+    }
+
+    #[test]
+    fn test_scan_stops_between_batches_when_cancelled() {
+        let conn = test_db();
+        let scan_id = create_scan(&conn, &options(1)).unwrap();
+
+        let cancel = AtomicBool::new(false);
+        let progress = run_scan(&conn, scan_id, &snapshot(), &options(1), &cancel, |_| {
+            cancel.store(true, Ordering::Relaxed)
+        });
+        assert_eq!(progress.status, "cancelled");
+        assert_eq!(progress.requests_scanned, 1);
+    }
+}
@@ -0,0 +1,736 @@
+// DLP Rule Import (gitleaks / trufflehog)
+//
+// Translates third-party secret scanner rule files into pattern groups:
+// - gitleaks `.toml` rulesets (`[[rules]]` with regex, entropy, keywords, allowlists)
+// - trufflehog custom detector `.yaml` files (`detectors:` with named regexes)
+// Each rule becomes one regex pattern group; allowlists and exclusions are
+// checked against the matched value and keywords become required context.
+// Rules that cannot be expressed (path-only rules, regex features the `regex`
+// crate lacks, ...) are reported instead of imported. Imports remember their file in `dlp_rule_imports` and
+// are re-synced when its contents change.
+
+use crate::pattern_utils::MAX_CONTEXT_WINDOW;
+use regex::Regex;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RuleFormat {
+    Gitleaks,
+    Trufflehog,
+}
+
+impl RuleFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "gitleaks" => Some(Self::Gitleaks),
+            "trufflehog" => Some(Self::Trufflehog),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gitleaks => "gitleaks",
+            Self::Trufflehog => "trufflehog",
+        }
+    }
+
+    /// Guess the format from the file extension
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = std::path::Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "toml" => Some(Self::Gitleaks),
+            "yaml" | "yml" => Some(Self::Trufflehog),
+            _ => None,
+        }
+    }
+}
+
+/// A translated rule, stored as one regex pattern group
+#[derive(Debug, Clone)]
+pub struct ImportedRule {
+    pub rule_id: String,
+    pub patterns: Vec<String>,
+    /// Checked against the matched value, not its context
+    pub negative_patterns: Vec<String>,
+    /// Keywords, one of which must appear near the match (empty = none)
+    pub required_context: Vec<String>,
+    pub min_unique_chars: i32,
+    /// Capture group holding the secret (gitleaks `secretGroup`), redacted instead of the whole match
+    pub redact_group: Option<String>,
+    /// Where the translation is looser than the source rule
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedRule {
+    pub rule_id: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct TranslatedRules {
+    pub rules: Vec<ImportedRule>,
+    pub skipped: Vec<SkippedRule>,
+    /// File-level notes (e.g. an `[extend]` that is not followed)
+    pub warnings: Vec<String>,
+}
+
+// ============================================================================
+// gitleaks
+// ============================================================================
+
+#[derive(Deserialize)]
+struct GitleaksConfig {
+    #[serde(default)]
+    extend: Option<toml::Value>,
+    #[serde(default)]
+    rules: Vec<GitleaksRule>,
+    /// Global allowlist (`[allowlist]`, or `[[allowlists]]` since v8.25)
+    #[serde(default)]
+    allowlist: Option<GitleaksAllowlist>,
+    #[serde(default)]
+    allowlists: Vec<GitleaksAllowlist>,
+}
+
+#[derive(Deserialize)]
+struct GitleaksRule {
+    id: String,
+    #[serde(default)]
+    regex: Option<String>,
+    #[serde(default, rename = "secretGroup")]
+    secret_group: Option<u32>,
+    #[serde(default)]
+    entropy: Option<f64>,
+    #[serde(default)]
+    keywords: Vec<String>,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default, rename = "skipReport")]
+    skip_report: bool,
+    #[serde(default)]
+    required: Option<toml::Value>,
+    #[serde(default)]
+    allowlist: Option<GitleaksAllowlist>,
+    #[serde(default)]
+    allowlists: Vec<GitleaksAllowlist>,
+}
+
+#[derive(Deserialize)]
+struct GitleaksAllowlist {
+    #[serde(default)]
+    condition: Option<String>,
+    #[serde(default)]
+    regexes: Vec<String>,
+    #[serde(default)]
+    stopwords: Vec<String>,
+    #[serde(default)]
+    paths: Vec<String>,
+    #[serde(default)]
+    commits: Vec<String>,
+    /// Global allowlists only: restrict to these rule ids
+    #[serde(default, rename = "targetRules")]
+    target_rules: Vec<String>,
+}
+
+fn translate_gitleaks(content: &str) -> Result<TranslatedRules, String> {
+    let config: GitleaksConfig =
+        toml::from_str(content).map_err(|e| format!("Invalid gitleaks config: {}", e))?;
+    let mut out = TranslatedRules::default();
+
+    if config.extend.is_some() {
+        out.warnings
+            .push("[extend] is not followed; only the rules in this file are imported".to_string());
+    }
+    let global: Vec<&GitleaksAllowlist> = config.allowlist.iter().chain(&config.allowlists).collect();
+
+    for rule in &config.rules {
+        let Some(regex) = rule.regex.as_deref().filter(|r| !r.trim().is_empty()) else {
+            let reason = if rule.path.is_some() {
+                "path-only rule (file paths do not apply to prompts)"
+            } else {
+                "no regex"
+            };
+            out.skip(&rule.id, reason);
+            continue;
+        };
+        if rule.skip_report || rule.required.is_some() {
+            out.skip(&rule.id, "composite rule (required/skipReport) not supported");
+            continue;
+        }
+
+        let mut notes = Vec::new();
+        if rule.path.is_some() {
+            notes.push("path condition ignored".to_string());
+        }
+
+        let mut negatives = Vec::new();
+        let rule_allowlists = rule.allowlist.iter().chain(&rule.allowlists);
+        let global_allowlists = global
+            .iter()
+            .copied()
+            .filter(|a| a.target_rules.is_empty() || a.target_rules.contains(&rule.id));
+        for allowlist in rule_allowlists.chain(global_allowlists) {
+            add_gitleaks_allowlist(allowlist, &mut negatives, &mut notes);
+        }
+
+        if let Some(imported) = out.finish_rule(
+            &rule.id,
+            vec![regex.to_string()],
+            negatives,
+            rule.entropy,
+            rule.secret_group.filter(|g| *g > 0),
+            notes,
+        ) {
+            imported.require_keywords(&rule.keywords);
+        }
+    }
+
+    out.warn_keyword_window();
+    Ok(out)
+}
+
+/// Allowlist regexes and stopwords become negative patterns on the value:
+/// gitleaks checks both against the secret, not the text around it
+fn add_gitleaks_allowlist(allowlist: &GitleaksAllowlist, negatives: &mut Vec<String>, notes: &mut Vec<String>) {
+    let has_location = !allowlist.paths.is_empty() || !allowlist.commits.is_empty();
+    if has_location {
+        // With AND, the allowlist also needs a path/commit match, which prompts never have
+        if allowlist.condition.as_deref().is_some_and(|c| c.eq_ignore_ascii_case("and")) {
+            notes.push("allowlist with an AND path/commit condition ignored".to_string());
+            return;
+        }
+        notes.push("allowlist paths/commits ignored".to_string());
+    }
+
+    negatives.extend(allowlist.regexes.iter().cloned());
+    // gitleaks compares stopwords case-insensitively
+    negatives.extend(allowlist.stopwords.iter().map(|w| format!("(?i){}", regex::escape(w))));
+}
+
+// ============================================================================
+// trufflehog
+// ============================================================================
+
+#[derive(Deserialize)]
+struct TrufflehogConfig {
+    #[serde(default)]
+    detectors: Vec<TrufflehogDetector>,
+}
+
+#[derive(Deserialize)]
+struct TrufflehogDetector {
+    name: String,
+    #[serde(default)]
+    keywords: Vec<String>,
+    #[serde(default)]
+    regex: BTreeMap<String, String>,
+    #[serde(default)]
+    entropy: Option<f64>,
+    #[serde(default)]
+    verify: Vec<serde_yaml_ng::Value>,
+    #[serde(default)]
+    exclude_words: Vec<String>,
+    #[serde(default)]
+    exclude_regexes_capture: Vec<String>,
+    #[serde(default)]
+    exclude_regexes_match: Vec<String>,
+}
+
+fn translate_trufflehog(content: &str) -> Result<TranslatedRules, String> {
+    let config: TrufflehogConfig =
+        serde_yaml_ng::from_str(content).map_err(|e| format!("Invalid trufflehog config: {}", e))?;
+    let mut out = TranslatedRules::default();
+
+    for detector in &config.detectors {
+        if detector.regex.is_empty() {
+            out.skip(&detector.name, "no regex");
+            continue;
+        }
+
+        let mut notes = Vec::new();
+        if detector.regex.len() > 1 {
+            notes.push("each named regex matches on its own (trufflehog requires all of them)".to_string());
+        }
+        if !detector.verify.is_empty() {
+            notes.push("verification endpoints are not called".to_string());
+        }
+
+        let patterns: Vec<String> = detector.regex.values().cloned().collect();
+        // trufflehog reports the first capture group; the whole match is redacted here
+        if patterns.iter().any(|p| Regex::new(p).is_ok_and(|re| re.captures_len() > 1)) {
+            notes.push("the whole match is detected, not just the secret group".to_string());
+        }
+        let negatives = detector
+            .exclude_regexes_match
+            .iter()
+            .chain(&detector.exclude_regexes_capture)
+            .cloned()
+            .chain(detector.exclude_words.iter().map(|w| regex::escape(w)))
+            .collect();
+
+        if let Some(imported) = out.finish_rule(&detector.name, patterns, negatives, detector.entropy, None, notes) {
+            imported.require_keywords(&detector.keywords);
+        }
+    }
+
+    out.warn_keyword_window();
+    Ok(out)
+}
+
+// ============================================================================
+// Translation
+// ============================================================================
+
+/// Translate a rule file's contents
+pub fn translate_rules(content: &str, format: RuleFormat) -> Result<TranslatedRules, String> {
+    match format {
+        RuleFormat::Gitleaks => translate_gitleaks(content),
+        RuleFormat::Trufflehog => translate_trufflehog(content),
+    }
+}
+
+/// A string with Shannon entropy H (bits/char) has at least 2^H distinct
+/// characters, so this never rejects a match the source tool would report
+pub fn entropy_to_min_unique_chars(entropy: f64) -> i32 {
+    2f64.powf(entropy.clamp(0.0, 8.0)).ceil() as i32
+}
+
+impl ImportedRule {
+    /// Keywords become required context: both tools skip a rule unless one of
+    /// its keywords (case-insensitive) appears in the scanned text
+    fn require_keywords(&mut self, keywords: &[String]) {
+        self.required_context = keywords
+            .iter()
+            .map(|k| k.trim())
+            .filter(|k| !k.is_empty())
+            .map(String::from)
+            .collect();
+    }
+}
+
+impl TranslatedRules {
+    /// Keywords are searched in the whole file by the source tools, but only
+    /// within the largest context window here
+    fn warn_keyword_window(&mut self) {
+        if self.rules.iter().any(|r| !r.required_context.is_empty()) {
+            self.warnings.push(format!(
+                "keywords must appear within {} characters of a match",
+                MAX_CONTEXT_WINDOW
+            ));
+        }
+    }
+
+    fn skip(&mut self, rule_id: &str, reason: &str) {
+        self.skipped.push(SkippedRule {
+            rule_id: rule_id.to_string(),
+            reason: reason.to_string(),
+        });
+    }
+
+    /// Validate a rule and add it; returns the added rule (None = skipped)
+    fn finish_rule(
+        &mut self,
+        rule_id: &str,
+        patterns: Vec<String>,
+        mut negatives: Vec<String>,
+        entropy: Option<f64>,
+        secret_group: Option<u32>,
+        mut notes: Vec<String>,
+    ) -> Option<&mut ImportedRule> {
+        if rule_id.trim().is_empty() {
+            self.skip("(unnamed)", "missing rule id");
+            return None;
+        }
+        if self.rules.iter().any(|r| r.rule_id == rule_id) {
+            self.skip(rule_id, "duplicate rule id");
+            return None;
+        }
+        for p in patterns.iter().chain(&negatives) {
+            if let Err(e) = Regex::new(p) {
+                // The last line of a regex syntax error names the problem
+                let error = e.to_string();
+                let detail = error.lines().last().unwrap_or_default().trim_start_matches("error: ");
+                self.skip(rule_id, &format!("unsupported regex `{}`: {}", p, detail));
+                return None;
+            }
+        }
+
+        if let Some(group) = secret_group {
+            let declared = patterns
+                .iter()
+                .any(|p| Regex::new(p).is_ok_and(|re| (group as usize) < re.captures_len()));
+            if !declared {
+                self.skip(rule_id, &format!("secretGroup {} is not a group of the regex", group));
+                return None;
+            }
+        }
+        let min_unique_chars = match entropy.filter(|e| *e > 0.0) {
+            Some(e) => {
+                let n = entropy_to_min_unique_chars(e);
+                notes.push(format!("entropy {} approximated as at least {} distinct characters", e, n));
+                n
+            }
+            None => 0,
+        };
+        let mut seen = HashSet::new();
+        negatives.retain(|n| seen.insert(n.clone()));
+
+        self.rules.push(ImportedRule {
+            rule_id: rule_id.to_string(),
+            patterns,
+            negative_patterns: negatives,
+            required_context: Vec::new(),
+            min_unique_chars,
+            redact_group: secret_group.map(|g| g.to_string()),
+            notes,
+        });
+        self.rules.last_mut()
+    }
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+/// Result of an import or re-sync, shown in the settings UI
+#[derive(Debug, Serialize)]
+pub struct RuleImportResult {
+    pub import_id: i64,
+    pub imported: usize,
+    pub removed: usize,
+    pub skipped: Vec<SkippedRule>,
+    /// "rule-id: note" for approximated rules, plus file-level warnings
+    pub notes: Vec<String>,
+}
+
+fn content_hash(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Import a rule file, or re-sync it if it was imported before
+pub fn import_rule_file(conn: &Connection, path: &str, format: Option<RuleFormat>) -> Result<RuleImportResult, String> {
+    let format = format
+        .or_else(|| RuleFormat::from_path(path))
+        .ok_or("Unknown rule file format. Use a gitleaks .toml or trufflehog .yaml file")?;
+
+    // Nothing is stored unless the file can be read and translated
+    let content = read_rule_file(path)?;
+    let translated = translate_rules(&content, format)?;
+    store_import(conn, path, format, &content_hash(&content), translated)
+}
+
+/// Re-read an imported rule file and update its pattern groups.
+/// Returns None if the file is unchanged (unless `force`)
+pub fn sync_rule_import(conn: &Connection, import_id: i64, force: bool) -> Result<Option<RuleImportResult>, String> {
+    let (path, format, previous_hash): (String, String, Option<String>) = conn
+        .query_row(
+            "SELECT path, format, content_hash FROM dlp_rule_imports WHERE id = ?1",
+            rusqlite::params![import_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .map_err(|_| "Import not found".to_string())?;
+    let format = RuleFormat::parse(&format).ok_or_else(|| format!("Unknown rule format '{}'", format))?;
+
+    let content = read_rule_file(&path)?;
+    let hash = content_hash(&content);
+    if !force && previous_hash.as_deref() == Some(hash.as_str()) {
+        return Ok(None);
+    }
+
+    let translated = translate_rules(&content, format)?;
+    store_import(conn, &path, format, &hash, translated).map(Some)
+}
+
+fn read_rule_file(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))
+}
+
+/// Upsert the import row and its pattern groups in one transaction
+fn store_import(
+    conn: &Connection,
+    path: &str,
+    format: RuleFormat,
+    hash: &str,
+    translated: TranslatedRules,
+) -> Result<RuleImportResult, String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let skipped_json = serde_json::to_string(&translated.skipped).unwrap_or_else(|_| "[]".to_string());
+
+    let store = || -> Result<(i64, usize), rusqlite::Error> {
+        let tx = conn.unchecked_transaction()?;
+        let import_id: i64 = tx.query_row(
+            "INSERT INTO dlp_rule_imports (path, format, content_hash, rule_count, skipped, created_at, synced_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)
+             ON CONFLICT(path) DO UPDATE SET format = ?2, content_hash = ?3, rule_count = ?4, skipped = ?5, synced_at = ?6
+             RETURNING id",
+            rusqlite::params![path, format.as_str(), hash, translated.rules.len() as i64, skipped_json, now],
+            |row| row.get(0),
+        )?;
+        let removed = apply_rules(&tx, import_id, &translated.rules)?;
+        tx.commit()?;
+        Ok((import_id, removed))
+    };
+    let (import_id, removed) = store().map_err(|e| e.to_string())?;
+
+    let mut notes = translated.warnings;
+    for rule in &translated.rules {
+        notes.extend(rule.notes.iter().map(|n| format!("{}: {}", rule.rule_id, n)));
+    }
+
+    Ok(RuleImportResult {
+        import_id,
+        imported: translated.rules.len(),
+        removed,
+        skipped: translated.skipped,
+        notes,
+    })
+}
+
+/// Re-sync every import whose file changed; returns the number re-synced
+pub fn sync_changed_rule_imports(conn: &Connection) -> usize {
+    let ids: Vec<i64> = match conn.prepare("SELECT id FROM dlp_rule_imports") {
+        Ok(mut stmt) => stmt
+            .query_map([], |row| row.get(0))
+            .map(|rows| rows.filter_map(|r| r.ok()).collect())
+            .unwrap_or_default(),
+        Err(_) => return 0,
+    };
+
+    let mut synced = 0;
+    for id in ids {
+        match sync_rule_import(conn, id, false) {
+            Ok(Some(_)) => synced += 1,
+            Ok(None) => {}
+            Err(e) => eprintln!("[DLP] Failed to re-sync rule import {}: {}", id, e),
+        }
+    }
+    synced
+}
+
+/// Remove an import and the pattern groups it created
+pub fn delete_rule_import(conn: &Connection, import_id: i64) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM dlp_patterns WHERE import_id = ?1",
+        rusqlite::params![import_id],
+    )?;
+    conn.execute(
+        "DELETE FROM dlp_rule_imports WHERE id = ?1",
+        rusqlite::params![import_id],
+    )?;
+    Ok(())
+}
+
+/// Upsert the import's pattern groups by rule id (keeping enabled and action)
+/// and delete the ones no longer in the file. Returns the number deleted
+fn apply_rules(tx: &Connection, import_id: i64, rules: &[ImportedRule]) -> Result<usize, rusqlite::Error> {
+    let existing: HashMap<String, i64> = {
+        let mut stmt = tx.prepare("SELECT import_rule_id, id FROM dlp_patterns WHERE import_id = ?1")?;
+        let rows = stmt.query_map(rusqlite::params![import_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+    let created_at = chrono::Utc::now().to_rfc3339();
+
+    for rule in rules {
+        let patterns_json = serde_json::to_string(&rule.patterns).unwrap_or_else(|_| "[]".to_string());
+        let (negative_type, negative_json, negative_scope) = if rule.negative_patterns.is_empty() {
+            (None, None, None)
+        } else {
+            (
+                Some("regex"),
+                Some(serde_json::to_string(&rule.negative_patterns).unwrap_or_else(|_| "[]".to_string())),
+                Some("value"),
+            )
+        };
+        let (required_json, required_type, context_window) = if rule.required_context.is_empty() {
+            (None, None, None)
+        } else {
+            (
+                Some(serde_json::to_string(&rule.required_context).unwrap_or_else(|_| "[]".to_string())),
+                Some("keyword"),
+                Some(MAX_CONTEXT_WINDOW as i32),
+            )
+        };
+
+        match existing.get(&rule.rule_id) {
+            Some(id) => {
+                tx.execute(
+                    "UPDATE dlp_patterns SET pattern_type = 'regex', patterns = ?1, negative_pattern_type = ?2,
+                            negative_patterns = ?3, negative_scope = ?4, min_unique_chars = ?5, redact_group = ?6,
+                            required_context = ?7, required_context_type = ?8, context_window = ?9 WHERE id = ?10",
+                    rusqlite::params![
+                        patterns_json,
+                        negative_type,
+                        negative_json,
+                        negative_scope,
+                        rule.min_unique_chars,
+                        rule.redact_group,
+                        required_json,
+                        required_type,
+                        context_window,
+                        id
+                    ],
+                )?;
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO dlp_patterns (name, pattern_type, patterns, negative_pattern_type, negative_patterns, negative_scope, enabled, min_occurrences, min_unique_chars, is_builtin, created_at, import_id, import_rule_id, redact_group, required_context, required_context_type, context_window)
+                     VALUES (?1, 'regex', ?2, ?3, ?4, ?5, 1, 1, ?6, 0, ?7, ?8, ?1, ?9, ?10, ?11, ?12)",
+                    rusqlite::params![
+                        rule.rule_id,
+                        patterns_json,
+                        negative_type,
+                        negative_json,
+                        negative_scope,
+                        rule.min_unique_chars,
+                        created_at,
+                        import_id,
+                        rule.redact_group,
+                        required_json,
+                        required_type,
+                        context_window
+                    ],
+                )?;
+            }
+        }
+    }
+
+    let current: HashSet<&str> = rules.iter().map(|r| r.rule_id.as_str()).collect();
+    let mut removed = 0;
+    for (rule_id, id) in &existing {
+        if !current.contains(rule_id.as_str()) {
+            removed += tx.execute("DELETE FROM dlp_patterns WHERE id = ?1", rusqlite::params![id])?;
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GITLEAKS: &str = r#"
+title = "acme"
+
+[extend]
+useDefault = true
+
+[[rules]]
+id = "acme-api-key"
+description = "ACME API key"
+regex = '''acme_[a-z0-9]{32}'''
+entropy = 3.5
+keywords = ["acme_"]
+
+[[rules.allowlists]]
+stopwords = ["EXAMPLE"]
+paths = ['''test/''']
+
+[[rules]]
+id = "acme-password"
+regex = '''(?i)acme_password\s*=\s*(\S{8,})'''
+secretGroup = 1
+
+[[rules]]
+id = "acme-lookahead"
+regex = '''token=(?!test)[a-z]{20}'''
+
+[[rules]]
+id = "pem-files"
+path = '''\.pem$'''
+
+[[allowlists]]
+targetRules = ["acme-api-key"]
+regexes = ['''acme_0{32}''']
+"#;
+
+    #[test]
+    fn test_gitleaks_translation() {
+        let out = translate_rules(GITLEAKS, RuleFormat::Gitleaks).unwrap();
+        assert_eq!(out.warnings.len(), 2);
+
+        assert_eq!(out.rules.len(), 2);
+        let rule = &out.rules[0];
+        assert_eq!(rule.rule_id, "acme-api-key");
+        assert_eq!(rule.patterns, vec!["acme_[a-z0-9]{32}"]);
+        assert_eq!(rule.negative_patterns, vec!["(?i)EXAMPLE", "acme_0{32}"]);
+        assert_eq!(rule.required_context, vec!["acme_"]);
+        assert_eq!(rule.min_unique_chars, 12);
+        assert!(rule.notes.iter().any(|n| n.contains("paths/commits ignored")));
+        assert_eq!(rule.redact_group, None);
+
+        // Only the secret group is redacted, so "acme_password =" stays readable
+        assert_eq!(out.rules[1].redact_group.as_deref(), Some("1"));
+        assert!(out.rules[1].notes.is_empty());
+        assert!(out.rules[1].required_context.is_empty());
+
+        let skipped: Vec<&str> = out.skipped.iter().map(|s| s.rule_id.as_str()).collect();
+        assert_eq!(skipped, vec!["acme-lookahead", "pem-files"]);
+        assert!(out.skipped[0].reason.contains("look-around"));
+    }
+
+    #[test]
+    fn test_trufflehog_translation() {
+        let yaml = r#"
+detectors:
+  - name: HogTokenDetector
+    keywords:
+      - hog
+    regex:
+      hogID: '\b(HOG[0-9A-Z]{17})\b'
+      hogToken: '[^A-Za-z0-9+\/]{0,1}([A-Za-z0-9+\/]{40})[^A-Za-z0-9+\/]{0,1}'
+    exclude_words:
+      - hog.example
+    verify:
+      - endpoint: http://localhost:8000/
+  - name: NoRegex
+    keywords: [x]
+"#;
+        let out = translate_rules(yaml, RuleFormat::Trufflehog).unwrap();
+        assert_eq!(out.rules.len(), 1);
+        let rule = &out.rules[0];
+        assert_eq!(rule.patterns.len(), 2);
+        assert_eq!(rule.negative_patterns, vec![r"hog\.example"]);
+        assert_eq!(rule.required_context, vec!["hog"]);
+        assert_eq!(rule.notes.len(), 3);
+        assert_eq!(out.skipped[0].rule_id, "NoRegex");
+    }
+
+    #[test]
+    fn test_entropy_bound_never_rejects_source_matches() {
+        assert_eq!(entropy_to_min_unique_chars(3.0), 8);
+        assert_eq!(entropy_to_min_unique_chars(3.5), 12);
+        // 11 distinct chars can reach at most log2(11) ~ 3.46 bits
+        assert!((11f64).log2() < 3.5);
+    }
+
+    #[test]
+    fn test_unreadable_file_stores_nothing() {
+        // No tables: any write before reading and translating would fail with a SQL error
+        let conn = Connection::open_in_memory().unwrap();
+        let dir = std::env::temp_dir().join(format!("rule-import-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let missing = dir.join("missing.toml");
+        let err = import_rule_file(&conn, missing.to_str().unwrap(), None).unwrap_err();
+        assert!(err.starts_with("Failed to read"), "{}", err);
+
+        let invalid = dir.join("invalid.toml");
+        std::fs::write(&invalid, "[[rules]\nid = ").unwrap();
+        let err = import_rule_file(&conn, invalid.to_str().unwrap(), None).unwrap_err();
+        assert!(err.starts_with("Invalid gitleaks config"), "{}", err);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(RuleFormat::from_path("/etc/gitleaks.TOML"), Some(RuleFormat::Gitleaks));
+        assert_eq!(RuleFormat::from_path("detectors.yml"), Some(RuleFormat::Trufflehog));
+        assert_eq!(RuleFormat::from_path("rules.json"), None);
+    }
+}
@@ -0,0 +1,258 @@
+// Checksum Validators for PII Patterns
+//
+// A "validator" pattern pairs candidate regexes with one of these checks, so a
+// match only counts when the value is structurally valid (e.g. a card number
+// with a correct Luhn digit), not merely the right length.
+
+/// Built-in validators, referenced by name from `dlp_patterns.validator`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Validator {
+    /// Payment card numbers (Luhn, 12-19 digits)
+    Luhn,
+    /// International Bank Account Numbers (country length + mod-97)
+    Iban,
+    /// US Social Security Numbers (area/group/serial rules)
+    UsSsn,
+    /// Indian Aadhaar numbers (12 digits, Verhoeff)
+    Aadhaar,
+    /// Canadian Social Insurance Numbers (9 digits, Luhn)
+    CaSin,
+}
+
+/// Validator names accepted in pattern settings
+pub const VALIDATOR_NAMES: &[&str] = &["luhn", "iban", "us_ssn", "aadhaar", "ca_sin"];
+
+impl Validator {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "luhn" => Some(Self::Luhn),
+            "iban" => Some(Self::Iban),
+            "us_ssn" => Some(Self::UsSsn),
+            "aadhaar" => Some(Self::Aadhaar),
+            "ca_sin" => Some(Self::CaSin),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Luhn => "luhn",
+            Self::Iban => "iban",
+            Self::UsSsn => "us_ssn",
+            Self::Aadhaar => "aadhaar",
+            Self::CaSin => "ca_sin",
+        }
+    }
+
+    /// Check a candidate match; spaces and hyphens are ignored
+    pub fn validate(&self, candidate: &str) -> bool {
+        let compact: String = candidate
+            .chars()
+            .filter(|c| *c != ' ' && *c != '-')
+            .collect();
+
+        match self {
+            Self::Luhn => {
+                is_digits(&compact, 12, 19) && !is_repeated_digit(&compact) && luhn_valid(&compact)
+            }
+            Self::Iban => iban_valid(&compact),
+            Self::UsSsn => is_digits(&compact, 9, 9) && ssn_valid(&compact),
+            Self::Aadhaar => {
+                is_digits(&compact, 12, 12)
+                    && !compact.starts_with(['0', '1'])
+                    && !is_repeated_digit(&compact)
+                    && verhoeff_valid(&compact)
+            }
+            Self::CaSin => {
+                is_digits(&compact, 9, 9)
+                    && !compact.starts_with(['0', '8'])
+                    && luhn_valid(&compact)
+            }
+        }
+    }
+}
+
+fn is_digits(s: &str, min_len: usize, max_len: usize) -> bool {
+    (min_len..=max_len).contains(&s.len()) && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// e.g. "0000000000000000", which passes Luhn
+fn is_repeated_digit(digits: &str) -> bool {
+    let first = digits.as_bytes()[0];
+    digits.bytes().all(|b| b == first)
+}
+
+/// Luhn (mod 10) check over ASCII digits
+pub fn luhn_valid(digits: &str) -> bool {
+    let sum: u32 = digits
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(i, b)| {
+            let d = (b - b'0') as u32;
+            if i % 2 == 1 {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+const VERHOEFF_D: [[u8; 10]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+    [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+    [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+    [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+    [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+    [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+    [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+    [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+    [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+];
+
+const VERHOEFF_P: [[u8; 10]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+    [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+    [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+    [9, 4, 5, 3, 1, 2, 6, 8, 7, 0],
+    [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+    [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+    [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+];
+
+/// Verhoeff check over ASCII digits (check digit last)
+pub fn verhoeff_valid(digits: &str) -> bool {
+    let mut c = 0u8;
+    for (i, b) in digits.bytes().rev().enumerate() {
+        c = VERHOEFF_D[c as usize][VERHOEFF_P[i % 8][(b - b'0') as usize] as usize];
+    }
+    c == 0
+}
+
+/// SSA rules: area not 000, 666 or 900-999; group not 00; serial not 0000
+fn ssn_valid(digits: &str) -> bool {
+    let area = &digits[0..3];
+    let group = &digits[3..5];
+    let serial = &digits[5..9];
+
+    // Well-known example numbers (wallet inserts, SSA ads) that are never valid
+    const KNOWN_INVALID: &[&str] = &["078051120", "219099999", "123456789"];
+
+    area != "000"
+        && area != "666"
+        && !area.starts_with('9')
+        && group != "00"
+        && serial != "0000"
+        && !KNOWN_INVALID.contains(&digits)
+}
+
+/// IBAN lengths by country code (SWIFT IBAN registry)
+const IBAN_LENGTHS: &[(&str, usize)] = &[
+    ("AD", 24), ("AE", 23), ("AL", 28), ("AT", 20), ("AZ", 28), ("BA", 20), ("BE", 16),
+    ("BG", 22), ("BH", 22), ("BR", 29), ("BY", 28), ("CH", 21), ("CR", 22), ("CY", 28),
+    ("CZ", 24), ("DE", 22), ("DK", 18), ("DO", 28), ("EE", 20), ("EG", 29), ("ES", 24),
+    ("FI", 18), ("FO", 18), ("FR", 27), ("GB", 22), ("GE", 22), ("GI", 23), ("GL", 18),
+    ("GR", 27), ("GT", 28), ("HR", 21), ("HU", 28), ("IE", 22), ("IL", 23), ("IQ", 23),
+    ("IS", 26), ("IT", 27), ("JO", 30), ("KW", 30), ("KZ", 20), ("LB", 28), ("LC", 32),
+    ("LI", 21), ("LT", 20), ("LU", 20), ("LV", 21), ("MC", 27), ("MD", 24), ("ME", 22),
+    ("MK", 19), ("MR", 27), ("MT", 31), ("MU", 30), ("NL", 18), ("NO", 15), ("PK", 24),
+    ("PL", 28), ("PS", 29), ("PT", 25), ("QA", 29), ("RO", 24), ("RS", 22), ("SA", 24),
+    ("SC", 31), ("SE", 24), ("SI", 19), ("SK", 24), ("SM", 27), ("ST", 25), ("SV", 28),
+    ("TL", 23), ("TN", 24), ("TR", 26), ("UA", 29), ("VA", 22), ("VG", 24), ("XK", 20),
+];
+
+/// Country length check plus ISO 7064 mod-97
+fn iban_valid(iban: &str) -> bool {
+    let iban = iban.to_ascii_uppercase();
+    if iban.len() < 15 || !iban.bytes().all(|b| b.is_ascii_alphanumeric()) {
+        return false;
+    }
+
+    let expected_len = IBAN_LENGTHS
+        .iter()
+        .find(|(country, _)| iban.starts_with(country))
+        .map(|(_, len)| *len);
+    if expected_len != Some(iban.len()) || !iban[2..4].bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+
+    // Move the country code and check digits to the end; letters become 10-35
+    let rearranged = iban[4..].bytes().chain(iban[..4].bytes());
+    let mut remainder: u32 = 0;
+    for b in rearranged {
+        let value = if b.is_ascii_digit() { (b - b'0') as u32 } else { (b - b'A') as u32 + 10 };
+        remainder = if value >= 10 {
+            (remainder * 100 + value) % 97
+        } else {
+            (remainder * 10 + value) % 97
+        };
+    }
+    remainder == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_luhn_cards() {
+        assert!(Validator::Luhn.validate("4111 1111 1111 1111"));
+        assert!(Validator::Luhn.validate("5500-0000-0000-0004"));
+        assert!(Validator::Luhn.validate("378282246310005"));
+        // Order ID with the same shape but a bad check digit
+        assert!(!Validator::Luhn.validate("4111 1111 1111 1112"));
+        assert!(!Validator::Luhn.validate("0000 0000 0000 0000"));
+    }
+
+    #[test]
+    fn test_iban() {
+        assert!(Validator::Iban.validate("GB82 WEST 1234 5698 7654 32"));
+        assert!(Validator::Iban.validate("DE89370400440532013000"));
+        assert!(!Validator::Iban.validate("GB82 WEST 1234 5698 7654 33"));
+        // Valid checksum shape but wrong length for the country
+        assert!(!Validator::Iban.validate("DE8937040044053201300"));
+    }
+
+    #[test]
+    fn test_us_ssn() {
+        assert!(Validator::UsSsn.validate("536-22-1234"));
+        assert!(!Validator::UsSsn.validate("000-22-1234"));
+        assert!(!Validator::UsSsn.validate("666-22-1234"));
+        assert!(!Validator::UsSsn.validate("912-22-1234"));
+        assert!(!Validator::UsSsn.validate("536-00-1234"));
+        assert!(!Validator::UsSsn.validate("536-22-0000"));
+        assert!(!Validator::UsSsn.validate("078-05-1120"));
+    }
+
+    #[test]
+    fn test_aadhaar_verhoeff() {
+        assert!(verhoeff_valid("2363"));
+        assert!(!verhoeff_valid("2364"));
+        assert!(Validator::Aadhaar.validate("2345 6789 0124"));
+        assert!(!Validator::Aadhaar.validate("2345 6789 0123"));
+        // Valid Verhoeff digit, but Aadhaar numbers never start with 0 or 1
+        assert!(verhoeff_valid("134567890129"));
+        assert!(!Validator::Aadhaar.validate("1345 6789 0129"));
+    }
+
+    #[test]
+    fn test_ca_sin() {
+        assert!(Validator::CaSin.validate("130 692 544"));
+        assert!(!Validator::CaSin.validate("130 692 545"));
+        // Luhn-valid, but 0xx numbers are not issued
+        assert!(luhn_valid("046454286"));
+        assert!(!Validator::CaSin.validate("046 454 286"));
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        for name in VALIDATOR_NAMES {
+            assert_eq!(Validator::parse(name).unwrap().as_str(), *name);
+        }
+    }
+}
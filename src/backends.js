@@ -1,4 +1,4 @@
-import { invoke, getCurrentPort, escapeHtml } from './utils.js';
+import { invoke, getProxyBaseUrl, escapeHtml } from './utils.js';
 
 // Store backends for editing
 let customBackends = [];
@@ -13,24 +13,123 @@ function parseSettings(settingsJson) {
       rate_limit_requests: settings.rate_limit_requests || 0,
       rate_limit_minutes: settings.rate_limit_minutes || 1,
       max_tokens_in_a_request: settings.max_tokens_in_a_request || 0,
-      action_for_max_tokens_in_a_request: settings.action_for_max_tokens_in_a_request || 'block'
+      action_for_max_tokens_in_a_request: settings.action_for_max_tokens_in_a_request || 'block',
+      dlp_include_patterns: settings.dlp_include_patterns || [],
+      dlp_exclude_patterns: settings.dlp_exclude_patterns || [],
+      dlp_action_override: settings.dlp_action_override || null,
+      dlp_scan_user_messages: settings.dlp_scan_user_messages !== false, // default true
+      dlp_scan_tool_results: settings.dlp_scan_tool_results !== false, // default true
+      dlp_scan_system_prompt: settings.dlp_scan_system_prompt === true,
+      dlp_scan_tool_definitions: settings.dlp_scan_tool_definitions === true,
+      dlp_session_header: settings.dlp_session_header || '',
+      dlp_session_field: settings.dlp_session_field || ''
     };
   } catch {
-    return { dlp_enabled: true, rate_limit_requests: 0, rate_limit_minutes: 1, max_tokens_in_a_request: 0, action_for_max_tokens_in_a_request: 'block' };
+    return defaultSettings();
   }
 }
 
+// Default settings for a new backend
+function defaultSettings() {
+  return {
+    dlp_enabled: true,
+    rate_limit_requests: 0,
+    rate_limit_minutes: 1,
+    max_tokens_in_a_request: 0,
+    action_for_max_tokens_in_a_request: 'block',
+    dlp_include_patterns: [],
+    dlp_exclude_patterns: [],
+    dlp_action_override: null,
+    dlp_scan_user_messages: true,
+    dlp_scan_tool_results: true,
+    dlp_scan_system_prompt: false,
+    dlp_scan_tool_definitions: false,
+    dlp_session_header: '',
+    dlp_session_field: ''
+  };
+}
+
 // Build settings JSON from form values
-function buildSettingsJson(dlpEnabled, rateRequests, rateMinutes, maxTokens, maxTokensAction) {
+function buildSettingsJson(dlpEnabled, rateRequests, rateMinutes, maxTokens, maxTokensAction, dlpScope) {
   return JSON.stringify({
     dlp_enabled: dlpEnabled,
     rate_limit_requests: rateRequests,
     rate_limit_minutes: rateMinutes,
     max_tokens_in_a_request: maxTokens,
-    action_for_max_tokens_in_a_request: maxTokensAction
+    action_for_max_tokens_in_a_request: maxTokensAction,
+    ...dlpScope
   });
 }
 
+// Split a comma-separated list of pattern names
+function parsePatternNames(value) {
+  return value.split(',').map(name => name.trim()).filter(name => name);
+}
+
+// Populate the pattern and scan scope fields of a modal (prefix: "backend" or "predefined-backend")
+function fillDlpScopeInputs(prefix, settings) {
+  document.getElementById(`${prefix}-dlp-include`).value = settings.dlp_include_patterns.join(', ');
+  document.getElementById(`${prefix}-dlp-exclude`).value = settings.dlp_exclude_patterns.join(', ');
+  document.getElementById(`${prefix}-dlp-action`).value = settings.dlp_action_override || '';
+  document.getElementById(`${prefix}-dlp-scan-user`).checked = settings.dlp_scan_user_messages;
+  document.getElementById(`${prefix}-dlp-scan-tool-results`).checked = settings.dlp_scan_tool_results;
+  document.getElementById(`${prefix}-dlp-scan-system`).checked = settings.dlp_scan_system_prompt;
+  document.getElementById(`${prefix}-dlp-scan-tools`).checked = settings.dlp_scan_tool_definitions;
+}
+
+// Read the pattern and scan scope fields of a modal
+function readDlpScopeInputs(prefix) {
+  return {
+    dlp_include_patterns: parsePatternNames(document.getElementById(`${prefix}-dlp-include`).value),
+    dlp_exclude_patterns: parsePatternNames(document.getElementById(`${prefix}-dlp-exclude`).value),
+    dlp_action_override: document.getElementById(`${prefix}-dlp-action`).value || null,
+    dlp_scan_user_messages: document.getElementById(`${prefix}-dlp-scan-user`).checked,
+    dlp_scan_tool_results: document.getElementById(`${prefix}-dlp-scan-tool-results`).checked,
+    dlp_scan_system_prompt: document.getElementById(`${prefix}-dlp-scan-system`).checked,
+    dlp_scan_tool_definitions: document.getElementById(`${prefix}-dlp-scan-tools`).checked
+  };
+}
+
+// Read where a custom backend's requests carry the session id (empty = none)
+function readSessionInputs() {
+  return {
+    dlp_session_header: document.getElementById('backend-dlp-session-header').value.trim() || null,
+    dlp_session_field: document.getElementById('backend-dlp-session-field').value.trim() || null
+  };
+}
+
+// Parts of a request scanned besides the defaults, or skipped from them
+function dlpScanScopeParts(settings) {
+  const parts = [];
+  if (!settings.dlp_scan_user_messages) parts.push('user messages not scanned');
+  if (!settings.dlp_scan_tool_results) parts.push('tool results not scanned');
+  if (settings.dlp_scan_system_prompt) parts.push('+ system prompt');
+  if (settings.dlp_scan_tool_definitions) parts.push('+ tool definitions');
+  return parts;
+}
+
+// Badge describing a backend's pattern and scan scope (empty when both are the defaults)
+function dlpScopeBadge(settings) {
+  if (!settings.dlp_enabled) return '';
+  const parts = [];
+  if (settings.dlp_include_patterns.length > 0) {
+    parts.push(`${settings.dlp_include_patterns.length} pattern(s)`);
+  }
+  if (settings.dlp_exclude_patterns.length > 0) {
+    parts.push(`${settings.dlp_exclude_patterns.length} skipped`);
+  }
+  if (settings.dlp_action_override) {
+    parts.push(`action: ${settings.dlp_action_override}`);
+  }
+  parts.push(...dlpScanScopeParts(settings));
+  if (parts.length === 0) return '';
+  const title = [
+    settings.dlp_include_patterns.length > 0 ? `Only: ${settings.dlp_include_patterns.join(', ')}` : '',
+    settings.dlp_exclude_patterns.length > 0 ? `Skipped: ${settings.dlp_exclude_patterns.join(', ')}` : ''
+  ].filter(t => t).join(' | ');
+  return `<span class="backend-setting-badge dlp-scope" title="${escapeHtml(title)}">${escapeHtml(parts.join(', '))}</span>`;
+}
+
 // Show status message
 function showBackendsStatus(message, type) {
   // Create or find status element
@@ -76,7 +175,7 @@ function renderBackends(backends) {
   const container = document.getElementById('backends-list');
   if (!container) return;
 
-  const port = getCurrentPort();
+  const baseUrl = getProxyBaseUrl();
 
   if (backends.length === 0) {
     container.innerHTML = `
@@ -100,6 +199,12 @@ function renderBackends(backends) {
       ? `<span class="backend-setting-badge token-limit">${settings.max_tokens_in_a_request} tokens (${settings.action_for_max_tokens_in_a_request})</span>`
       : '<span class="backend-setting-badge no-token-limit">No Token Limit</span>';
 
+    const scopeBadge = dlpScopeBadge(settings);
+    // Without a session id, placeholders change every request
+    const sessionBadge = settings.dlp_enabled && !settings.dlp_session_header && !settings.dlp_session_field
+      ? '<span class="backend-setting-badge dlp-scope" title="Set a session header or field to keep placeholders across turns">No session: new placeholders per request</span>'
+      : '';
+
     return `
     <div class="backend-item ${backend.enabled ? '' : 'disabled'}" data-id="${backend.id}">
       <div class="backend-info">
@@ -111,7 +216,7 @@ function renderBackends(backends) {
         <div class="backend-details">
           <div class="backend-url">
             <span class="backend-label">Proxy URL:</span>
-            <code>http://localhost:${port}/${escapeHtml(backend.name)}</code>
+            <code>${baseUrl}/${escapeHtml(backend.name)}</code>
           </div>
           <div class="backend-url">
             <span class="backend-label">Target:</span>
@@ -120,6 +225,8 @@ function renderBackends(backends) {
         </div>
         <div class="backend-settings-summary">
           ${dlpBadge}
+          ${scopeBadge}
+          ${sessionBadge}
           ${rateBadge}
           ${tokenBadge}
         </div>
@@ -205,7 +312,7 @@ function showBackendModal(backend = null) {
   title.textContent = backend ? 'Edit Backend' : 'Add Backend';
 
   // Parse existing settings or use defaults
-  const settings = backend ? parseSettings(backend.settings) : { dlp_enabled: true, rate_limit_requests: 0, rate_limit_minutes: 1, max_tokens_in_a_request: 0, action_for_max_tokens_in_a_request: 'block' };
+  const settings = backend ? parseSettings(backend.settings) : defaultSettings();
 
   // Reset/populate form
   document.getElementById('backend-id').value = backend ? backend.id : '';
@@ -216,6 +323,9 @@ function showBackendModal(backend = null) {
   rateMinutesInput.value = settings.rate_limit_minutes;
   maxTokensInput.value = settings.max_tokens_in_a_request;
   maxTokensActionInput.value = settings.action_for_max_tokens_in_a_request;
+  fillDlpScopeInputs('backend', settings);
+  document.getElementById('backend-dlp-session-header').value = settings.dlp_session_header;
+  document.getElementById('backend-dlp-session-field').value = settings.dlp_session_field;
 
   // If editing, disable name field (changing name not allowed)
   nameInput.disabled = !!backend;
@@ -251,7 +361,10 @@ async function saveBackend() {
   const maxTokensAction = document.getElementById('backend-max-tokens-action').value || 'block';
 
   // Build settings JSON
-  const settings = buildSettingsJson(dlpEnabled, rateRequests, Math.max(1, rateMinutes), maxTokens, maxTokensAction);
+  const settings = buildSettingsJson(dlpEnabled, rateRequests, Math.max(1, rateMinutes), maxTokens, maxTokensAction, {
+    ...readDlpScopeInputs('backend'),
+    ...readSessionInputs()
+  });
 
   // Validation
   if (!name) {
@@ -321,7 +434,7 @@ function renderPredefinedBackends(backends) {
   const container = document.getElementById('predefined-backends-list');
   if (!container) return;
 
-  const port = getCurrentPort();
+  const baseUrl = getProxyBaseUrl();
 
   container.innerHTML = backends.map(backend => {
     const settings = parseSettings(backend.settings);
@@ -340,9 +453,11 @@ function renderPredefinedBackends(backends) {
       ? ''
       : `<div class="backend-url">
           <span class="backend-label">Proxy URL:</span>
-          <code>http://localhost:${port}/${escapeHtml(backend.name)}</code>
+          <code>${baseUrl}/${escapeHtml(backend.name)}</code>
         </div>`;
 
+    const scopeBadge = dlpScopeBadge(settings);
+
     return `
     <div class="backend-item predefined" data-name="${escapeHtml(backend.name)}">
       <div class="backend-info">
@@ -359,6 +474,7 @@ function renderPredefinedBackends(backends) {
         </div>
         <div class="backend-settings-summary">
           ${dlpBadge}
+          ${scopeBadge}
           ${rateBadge}
           ${tokenBadge}
         </div>
@@ -410,6 +526,7 @@ function showPredefinedBackendModal(backend) {
   rateMinutesInput.value = settings.rate_limit_minutes;
   maxTokensInput.value = settings.max_tokens_in_a_request;
   maxTokensActionInput.value = settings.action_for_max_tokens_in_a_request;
+  fillDlpScopeInputs('predefined-backend', settings);
 
   modal.classList.add('show');
 }
@@ -429,7 +546,7 @@ async function savePredefinedBackend() {
   const maxTokens = parseInt(document.getElementById('predefined-backend-max-tokens').value) || 0;
   const maxTokensAction = document.getElementById('predefined-backend-max-tokens-action').value || 'block';
 
-  const settings = buildSettingsJson(dlpEnabled, rateRequests, Math.max(1, rateMinutes), maxTokens, maxTokensAction);
+  const settings = buildSettingsJson(dlpEnabled, rateRequests, Math.max(1, rateMinutes), maxTokens, maxTokensAction, readDlpScopeInputs('predefined-backend'));
 
   const saveBtn = document.getElementById('save-predefined-backend-btn');
   saveBtn.disabled = true;
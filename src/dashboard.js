@@ -7,7 +7,8 @@ import {
   formatNumber,
   colors,
   formatLatency,
-  shortenModel
+  shortenModel,
+  escapeHtml
 } from './utils.js';
 import { destroyCharts, createModelsChart, createTokenChart, createLatencyChart, createDlpChart, createToolInsightsChart } from './charts.js';
 
@@ -15,7 +16,7 @@ import { destroyCharts, createModelsChart, createTokenChart, createLatencyChart,
 let chartData = {};
 
 // Render dashboard HTML
-function renderDashboard(data, dlpStats, toolInsights) {
+function renderDashboard(data, dlpStats, toolInsights, shadowStats) {
   const { models, features, token_totals, recent_requests, latency_points } = data;
 
   const pct = (val) => features.total_requests > 0 ? Math.round((val / features.total_requests) * 100) : 0;
@@ -146,6 +147,7 @@ function renderDashboard(data, dlpStats, toolInsights) {
         <div class="card-header">
           <span>Detections</span>
           <div class="card-header-actions">
+            ${dlpStats && dlpStats.total_detections > 0 ? `<span class="badge">${dlpStats.total_detections} detections / ${dlpStats.unique_values} unique values</span>` : ''}
             <button class="expand-btn" data-chart="dlp" title="Expand"><svg xmlns="http://www.w3.org/2000/svg" width="16" height="16" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round"><polyline points="15 3 21 3 21 9"></polyline><polyline points="9 21 3 21 3 15"></polyline><line x1="21" y1="3" x2="14" y2="10"></line><line x1="3" y1="21" x2="10" y2="14"></line></svg></button>
           </div>
         </div>
@@ -153,6 +155,8 @@ function renderDashboard(data, dlpStats, toolInsights) {
           <div class="dlp-chart-container" id="dlp-chart">
             ${(!dlpStats || dlpStats.detections_by_pattern.length === 0) ? '<p class="empty-text">No detections</p>' : ''}
           </div>
+          ${renderRepeatedValues(dlpStats)}
+          ${renderShadowPatterns(shadowStats)}
         </div>
       </div>
     </div>
@@ -170,6 +174,49 @@ function renderDashboard(data, dlpStats, toolInsights) {
   `;
 }
 
+// Values detected more than once, grouped by fingerprint
+function renderRepeatedValues(dlpStats) {
+  const repeated = (dlpStats?.top_values || []).filter(v => v.count > 1);
+  if (repeated.length === 0) {
+    return '';
+  }
+  const rows = repeated.map(v => `
+    <div class="dlp-repeated-row">
+      <span class="dlp-repeated-value" title="Fingerprint ${escapeHtml(v.value_fingerprint)}">${escapeHtml(v.display_value || '#' + v.value_fingerprint)}</span>
+      <span class="dlp-repeated-pattern">${escapeHtml(v.pattern_name)}</span>
+      <span class="dlp-repeated-count">${v.count}&times; in ${v.request_count} requests</span>
+    </div>
+  `).join('');
+  return `<div class="dlp-repeated-values"><h4 class="dlp-section-title">Repeated Values</h4>${rows}</div>`;
+}
+
+// How often shadow patterns would have fired, with a few sample values each
+function renderShadowPatterns(shadowStats) {
+  const patterns = shadowStats?.patterns || [];
+  if (patterns.length === 0) {
+    return '';
+  }
+  const total = shadowStats.requests_total;
+  const rows = patterns.map(p => {
+    const share = total > 0 ? ` (${(p.request_count / total * 100).toFixed(1)}% of requests)` : '';
+    const samples = p.samples.map(s => `
+      <div class="dlp-repeated-row dlp-shadow-sample">
+        <span class="dlp-repeated-value" title="${escapeHtml(s.timestamp)}">${escapeHtml(s.original_value)}</span>
+        ${s.found_in ? `<span class="dlp-repeated-pattern">in ${escapeHtml(s.found_in)}</span>` : ''}
+      </div>
+    `).join('');
+    return `
+      <div class="dlp-repeated-row">
+        <span class="dlp-repeated-value">${escapeHtml(p.pattern_name)}${p.in_shadow ? '' : ' <span class="dlp-repeated-pattern">(no longer in shadow)</span>'}</span>
+        ${p.action ? `<span class="dlp-repeated-pattern">would ${escapeHtml(p.action)}</span>` : ''}
+        <span class="dlp-repeated-count">${p.count}&times; / ${p.unique_values} values in ${p.request_count} requests${share}</span>
+      </div>
+      ${samples}
+    `;
+  }).join('');
+  return `<div class="dlp-repeated-values"><h4 class="dlp-section-title">Shadow Patterns</h4>${rows}</div>`;
+}
+
 // Load dashboard
 export async function loadDashboard() {
   const content = document.getElementById('dashboard-content');
@@ -178,14 +225,15 @@ export async function loadDashboard() {
   destroyCharts();
 
   try {
-    // Load dashboard stats, DLP stats, and tool insights in parallel
-    const [data, dlpStats, toolInsights] = await Promise.all([
+    // Load dashboard stats, DLP stats, shadow pattern stats, and tool insights in parallel
+    const [data, dlpStats, toolInsights, shadowStats] = await Promise.all([
       invoke('get_dashboard_stats', { timeRange: currentTimeRange, backend: currentBackend }),
       invoke('get_dlp_detection_stats', { timeRange: currentTimeRange, backend: currentBackend }),
-      invoke('get_tool_call_insights', { timeRange: currentTimeRange, backend: currentBackend })
+      invoke('get_tool_call_insights', { timeRange: currentTimeRange, backend: currentBackend }),
+      invoke('get_dlp_shadow_stats', { timeRange: currentTimeRange, backend: currentBackend })
     ]);
 
-    if (data.total_requests === 0 && dlpStats.total_detections === 0 && toolInsights.tools.length === 0) {
+    if (data.total_requests === 0 && dlpStats.total_detections === 0 && toolInsights.tools.length === 0 && shadowStats.patterns.length === 0) {
       content.innerHTML = `
         <div class="empty-state">
           <h3>No data yet</h3>
@@ -198,7 +246,7 @@ export async function loadDashboard() {
       return;
     }
 
-    content.innerHTML = renderDashboard(data, dlpStats, toolInsights);
+    content.innerHTML = renderDashboard(data, dlpStats, toolInsights, shadowStats);
 
     // Store chart data for fullscreen recreation
     chartData = {
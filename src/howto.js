@@ -1,8 +1,8 @@
-import { invoke, getCurrentPort } from './utils.js';
+import { invoke, getProxyBaseUrl } from './utils.js';
 
 // Get instructions for each tool
 function getToolInstructions(tool) {
-  const port = getCurrentPort();
+  const baseUrl = getProxyBaseUrl();
 
   const instructions = {
     'claude-code': {
@@ -26,7 +26,7 @@ function getToolInstructions(tool) {
           <h4>What this does:</h4>
           <ul>
             <li>Sets <code>ANTHROPIC_BASE_URL</code> in <code>~/.claude/settings.json</code></li>
-            <li>All Claude Code sessions will route through the proxy at <code>http://localhost:${port}/claude</code></li>
+            <li>All Claude Code sessions will route through the proxy at <code>${baseUrl}/claude</code></li>
             <li>No need to restart Claude Code - changes take effect immediately</li>
           </ul>
         </div>
@@ -34,7 +34,7 @@ function getToolInstructions(tool) {
         <div class="claude-code-info" style="margin-top: 16px;">
           <h4>Manual setup:</h4>
           <p>Alternatively, run Claude Code with the proxy inline:</p>
-          <code>ANTHROPIC_BASE_URL="http://localhost:${port}/claude" claude</code>
+          <code>ANTHROPIC_BASE_URL="${baseUrl}/claude" claude</code>
         </div>
       `
     },
@@ -82,7 +82,7 @@ function getToolInstructions(tool) {
       title: 'Codex CLI',
       content: `
         <p>Run Codex CLI with the proxy inline:</p>
-        <code>OPENAI_BASE_URL="http://localhost:${port}/codex" codex</code>
+        <code>OPENAI_BASE_URL="${baseUrl}/codex" codex</code>
 
         <p style="margin-top: 24px;"><strong>Or install a shell function wrapper:</strong></p>
         <p class="howto-note">This creates a <code>codex</code> function that sets the proxy URL only when running codex, not globally.</p>
@@ -95,19 +95,19 @@ function getToolInstructions(tool) {
 
         <div class="shell-tab-content active" data-shell="bash">
           <p class="shell-config-path">~/.bashrc</p>
-          <code>codex() { OPENAI_BASE_URL="http://localhost:${port}/codex" command codex "$@"; }</code>
+          <code>codex() { OPENAI_BASE_URL="${baseUrl}/codex" command codex "$@"; }</code>
           <button class="btn btn-primary shell-action-btn" data-shell="bash" data-action="set">Set</button>
         </div>
 
         <div class="shell-tab-content" data-shell="zsh">
           <p class="shell-config-path">~/.zshrc</p>
-          <code>codex() { OPENAI_BASE_URL="http://localhost:${port}/codex" command codex "$@"; }</code>
+          <code>codex() { OPENAI_BASE_URL="${baseUrl}/codex" command codex "$@"; }</code>
           <button class="btn btn-primary shell-action-btn" data-shell="zsh" data-action="set">Set</button>
         </div>
 
         <div class="shell-tab-content" data-shell="fish">
           <p class="shell-config-path">~/.config/fish/functions/codex.fish</p>
-          <code>function codex; set -lx OPENAI_BASE_URL "http://localhost:${port}/codex"; command codex $argv; end</code>
+          <code>function codex; set -lx OPENAI_BASE_URL "${baseUrl}/codex"; command codex $argv; end</code>
           <button class="btn btn-primary shell-action-btn" data-shell="fish" data-action="set">Set</button>
         </div>
 
@@ -43,6 +43,8 @@ function highlightSearchTerms(text, searchTerm) {
 // Get DLP status info
 function getDlpStatus(dlpAction) {
   switch (dlpAction) {
+    case 6: return { label: 'Logged', class: 'logged' };
+    case 5: return { label: 'Notified', class: 'notified' };
     case 4: return { label: 'Notify-Ratelimit', class: 'notify-ratelimit' };
     case 3: return { label: 'Ratelimited', class: 'ratelimited' };
     case 2: return { label: 'Blocked', class: 'blocked' };
@@ -145,20 +147,30 @@ async function updateCardContent(card, index) {
   const jsonPre = card.querySelector('.log-json');
   const subtabsContainer = card.querySelector('.log-card-subtabs');
 
+  // False positive actions only belong to the detections tab
+  card.querySelector('.log-dlp-actions')?.remove();
+
   // Show/hide subtabs based on tab type
   if (activeTab === 'dlp') {
     subtabsContainer.style.display = 'none';
     // Fetch and display DLP detections
     try {
       const detections = await invoke('get_dlp_detections_for_request', { requestId: log.id });
+      renderDetectionActions(card, index, detections);
       if (detections.length === 0) {
         jsonPre.textContent = 'No detections for this request.';
       } else {
         const formatted = detections.map(d => ({
           pattern: d.pattern_name,
           type: d.pattern_type,
-          original: d.original_value,
-          replaced_with: d.placeholder,
+          original: d.original_value || '(not stored)',
+          replaced_with: d.placeholder || null,
+          placeholder_strategy: d.placeholder_strategy || null,
+          found_in: d.found_in || null,
+          action: d.action,
+          shadow: !!d.shadow,
+          exception_id: d.exception_id ?? null,
+          fingerprint: d.value_fingerprint,
           message_index: d.message_index
         }));
         jsonPre.textContent = JSON.stringify(formatted, null, 2);
@@ -197,6 +209,57 @@ async function updateCardContent(card, index) {
   }
 }
 
+// Render a "Mark false positive" action per detected value under the detections
+// (known secrets cannot be excepted)
+function renderDetectionActions(card, index, detections) {
+  const seen = new Set();
+  const rows = detections.filter(d => {
+    const key = `${d.pattern_name}|${d.value_fingerprint}`;
+    if (d.pattern_type === 'known_secret' || !d.value_fingerprint || seen.has(key)) return false;
+    seen.add(key);
+    return true;
+  });
+  // The user may have switched tabs while detections were loading
+  if (rows.length === 0 || card.querySelector('.log-tab.active').dataset.tab !== 'dlp') return;
+
+  const actions = document.createElement('div');
+  actions.className = 'log-dlp-actions';
+  actions.innerHTML = rows.map(d => `
+    <div class="log-dlp-action-row">
+      <span class="log-dlp-action-label">${escapeHtml(d.pattern_name)}: <code>${escapeHtml(d.original_value || d.value_fingerprint)}</code></span>
+      ${d.exception_id
+        ? `<span class="log-dlp-excepted">Allowed by exception #${d.exception_id}</span>`
+        : `<button class="log-dlp-exception-btn" data-detection-id="${d.id}">Mark false positive</button>`}
+    </div>
+  `).join('');
+  card.querySelector('.log-card-content').appendChild(actions);
+
+  actions.querySelectorAll('.log-dlp-exception-btn').forEach(btn => {
+    btn.addEventListener('click', async () => {
+      const detection = rows.find(d => d.id === parseInt(btn.dataset.detectionId));
+      if (!confirm(`Stop acting on this value for "${detection.pattern_name}"? The exception can be revoked in Settings.`)) return;
+      btn.disabled = true;
+      try {
+        await invoke('create_dlp_exception_from_detection', {
+          detectionId: detection.id,
+          anyPattern: false,
+          matchBackend: false,
+          matchFile: false,
+          expiresAt: null,
+          reason: `False positive in request #${currentLogs[index].id}`
+        });
+        btn.replaceWith(Object.assign(document.createElement('span'), {
+          className: 'log-dlp-excepted',
+          textContent: 'Exception created'
+        }));
+      } catch (err) {
+        btn.disabled = false;
+        alert('Failed to create exception: ' + err);
+      }
+    });
+  });
+}
+
 // Copy both request and response as tuple
 async function copyLogData(index, tab) {
   const log = currentLogs[index];
@@ -218,8 +281,14 @@ async function copyLogData(index, tab) {
       data = detections.map(d => ({
         pattern: d.pattern_name,
         type: d.pattern_type,
-        original: d.original_value,
-        replaced_with: d.placeholder,
+        original: d.original_value || '(not stored)',
+        replaced_with: d.placeholder || null,
+        placeholder_strategy: d.placeholder_strategy || null,
+        found_in: d.found_in || null,
+        action: d.action,
+        shadow: !!d.shadow,
+        exception_id: d.exception_id ?? null,
+        fingerprint: d.value_fingerprint,
         message_index: d.message_index
       }));
     } catch {
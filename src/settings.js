@@ -1,4 +1,4 @@
-import { invoke, getCurrentPort, setCurrentPort, escapeHtml } from './utils.js';
+import { invoke, getCurrentPort, setCurrentPort, setCurrentAccessToken, escapeHtml } from './utils.js';
 
 // Tauri event listener
 const { listen } = window.__TAURI__.event;
@@ -139,6 +139,184 @@ async function savePortSetting() {
   }
 }
 
+// ============ Proxy Access Settings ============
+
+// Show the current access token
+function renderAccessToken(token) {
+  const display = document.getElementById('access-token-display');
+  if (display) {
+    display.textContent = token ? `Token: ${token}` : '';
+  }
+}
+
+// Load bind address and access token settings from backend
+async function loadProxyAccessSettings() {
+  try {
+    const settings = await invoke('get_proxy_access_settings');
+    setCurrentAccessToken(settings.access_token);
+
+    const bindInput = document.getElementById('bind-address-input');
+    if (bindInput) {
+      bindInput.value = settings.bind_address;
+    }
+    const toggle = document.getElementById('require-token-toggle');
+    if (toggle) {
+      toggle.checked = settings.require_token;
+    }
+    renderAccessToken(settings.access_token);
+  } catch (error) {
+    console.error('Failed to load proxy access settings:', error);
+  }
+}
+
+// Save bind address / token requirement and restart proxy
+async function saveProxyAccessSettings() {
+  const bindInput = document.getElementById('bind-address-input');
+  const toggle = document.getElementById('require-token-toggle');
+  const saveBtn = document.getElementById('save-access-btn');
+  const bindAddress = bindInput.value.trim();
+
+  saveBtn.disabled = true;
+  saveBtn.textContent = 'Saving...';
+
+  try {
+    await invoke('save_proxy_access_settings', {
+      bindAddress,
+      requireToken: toggle.checked
+    });
+
+    showSettingsStatus('Restarting proxy server...', 'info');
+    await invoke('restart_proxy');
+    showSettingsStatus(`Proxy server now listening on ${bindAddress}`, 'success');
+  } catch (error) {
+    showSettingsStatus(`Failed: ${error}`, 'error');
+  } finally {
+    saveBtn.disabled = false;
+    saveBtn.textContent = 'Save & Restart';
+  }
+}
+
+// Generate a new access token and restart proxy
+async function regenerateAccessToken() {
+  if (!confirm('Generate a new access token? Claude Code, Codex and Cursor integrations must be installed again.')) {
+    return;
+  }
+
+  try {
+    const token = await invoke('regenerate_proxy_access_token');
+    setCurrentAccessToken(token);
+    renderAccessToken(token);
+    await invoke('restart_proxy');
+    showSettingsStatus('New access token generated. Re-install your tool integrations.', 'success');
+  } catch (error) {
+    showSettingsStatus(`Failed: ${error}`, 'error');
+  }
+}
+
+// ============ Header Scrubbing ============
+
+// Load sensitive header names from backend
+async function loadHeaderScrubSettings() {
+  try {
+    const settings = await invoke('get_header_scrub_settings');
+    const builtin = document.getElementById('scrub-headers-builtin');
+    if (builtin) {
+      const suffixes = settings.builtin_suffixes.map(s => `*${s}`);
+      builtin.textContent = [...settings.builtin_names, ...suffixes].join(', ');
+    }
+    const input = document.getElementById('scrub-headers-input');
+    if (input) {
+      input.value = settings.custom_names.join(', ');
+    }
+  } catch (error) {
+    console.error('Failed to load header scrub settings:', error);
+  }
+}
+
+// Save user-added sensitive header names
+async function saveHeaderScrubSettings() {
+  const input = document.getElementById('scrub-headers-input');
+  const names = input.value.split(',').map(n => n.trim()).filter(n => n.length > 0);
+
+  try {
+    const scrubbed = await invoke('save_header_scrub_names', { names });
+    await invoke('restart_proxy');
+    showSettingsStatus(`Sensitive headers saved. ${scrubbed} stored requests updated.`, 'success');
+    loadHeaderScrubSettings();
+  } catch (error) {
+    showSettingsStatus(`Failed: ${error}`, 'error');
+  }
+}
+
+// ============ Detection Storage Mode ============
+
+// Load how detected values are stored
+async function loadDetectionStorageMode() {
+  try {
+    const mode = await invoke('get_detection_storage_mode');
+    const select = document.getElementById('detection-storage-select');
+    if (select) {
+      select.value = mode;
+    }
+  } catch (error) {
+    console.error('Failed to load detection storage mode:', error);
+  }
+}
+
+// Save how detected values are stored (also applied to existing detections)
+async function saveDetectionStorageMode(mode) {
+  try {
+    const updated = await invoke('save_detection_storage_mode', { mode });
+    showSettingsStatus(
+      `Storage mode saved. ${updated} stored detections updated.`,
+      'success',
+      'detection-storage-status'
+    );
+  } catch (error) {
+    showSettingsStatus(`Failed to save: ${error}`, 'error', 'detection-storage-status');
+    loadDetectionStorageMode();
+  }
+}
+
+// ============ Redaction Vault ============
+
+// Load how long sessions keep their placeholders
+async function loadDlpVaultTtl() {
+  try {
+    const hours = await invoke('get_dlp_vault_ttl_hours');
+    const select = document.getElementById('dlp-vault-ttl-select');
+    if (select) {
+      // Keep a custom value selectable
+      if (!select.querySelector(`option[value="${hours}"]`)) {
+        select.add(new Option(`${hours} hours`, String(hours)));
+      }
+      select.value = String(hours);
+    }
+  } catch (error) {
+    console.error('Failed to load vault TTL:', error);
+  }
+}
+
+async function saveDlpVaultTtl(hours) {
+  try {
+    await invoke('save_dlp_vault_ttl_hours', { hours: parseInt(hours, 10) });
+    showSettingsStatus('Placeholder memory saved.', 'success', 'dlp-vault-status');
+  } catch (error) {
+    showSettingsStatus(`Failed to save: ${error}`, 'error', 'dlp-vault-status');
+    loadDlpVaultTtl();
+  }
+}
+
+// Forget every session's placeholders
+async function clearDlpVault() {
+  try {
+    await invoke('clear_dlp_vault');
+    showSettingsStatus('Stored placeholders cleared.', 'success', 'dlp-vault-status');
+  } catch (error) {
+    showSettingsStatus(`Failed to clear: ${error}`, 'error', 'dlp-vault-status');
+  }
+}
+
 // ============ DLP Action Setting ============
 
 const DLP_ACTION_DESCRIPTIONS = {
@@ -272,6 +450,22 @@ function initDlpActionToggle() {
 
   // Load initial state
   loadDlpActionSetting();
+
+  const storageSelect = document.getElementById('detection-storage-select');
+  if (storageSelect) {
+    storageSelect.addEventListener('change', () => saveDetectionStorageMode(storageSelect.value));
+  }
+  loadDetectionStorageMode();
+
+  const vaultSelect = document.getElementById('dlp-vault-ttl-select');
+  if (vaultSelect) {
+    vaultSelect.addEventListener('change', () => saveDlpVaultTtl(vaultSelect.value));
+  }
+  const vaultClearBtn = document.getElementById('dlp-vault-clear-btn');
+  if (vaultClearBtn) {
+    vaultClearBtn.addEventListener('click', clearDlpVault);
+  }
+  loadDlpVaultTtl();
 }
 
 // ============ DLP Settings ============
@@ -285,6 +479,7 @@ async function loadDlpSettings() {
     const settings = await invoke('get_dlp_settings');
     dlpPatterns = settings.patterns || [];
     renderPatterns(dlpPatterns);
+    renderRetroScanPatternOptions(dlpPatterns);
   } catch (error) {
     console.error('Failed to load DLP settings:', error);
     const container = document.getElementById('dlp-patterns');
@@ -309,13 +504,27 @@ function renderPatterns(patterns) {
       <input type="checkbox" class="dlp-checkbox dlp-pattern-toggle" data-id="${pattern.id}" ${pattern.enabled ? 'checked' : ''} />
       <span class="dlp-pattern-name">${escapeHtml(pattern.name)}</span>
       <span class="dlp-pattern-badge ${pattern.is_builtin ? 'builtin' : pattern.pattern_type}">${pattern.is_builtin ? 'Built-in' : pattern.pattern_type}</span>
+      ${pattern.category ? `<span class="dlp-pattern-meta">${escapeHtml(pattern.category)}</span>` : ''}
+      ${pattern.user_modified ? `<span class="dlp-pattern-meta" title="Edited; library updates are not applied">Modified</span>` : ''}
+      ${pattern.import_id ? `<span class="dlp-pattern-meta" title="Updated when its rule file is re-synced">Imported</span>` : ''}
       ${pattern.min_unique_chars > 0 ? `<span class="dlp-pattern-meta">Unique chars >= ${pattern.min_unique_chars}</span>` : ''}
       <span class="dlp-pattern-meta">Occurrence >= ${pattern.min_occurrences}</span>
+      ${pattern.shadow ? `<span class="dlp-pattern-meta" title="Matches are recorded but not enforced">Shadow</span>` : ''}
+      ${pattern.action ? `<span class="dlp-pattern-meta">Action: ${pattern.action}</span>` : ''}
+      ${pattern.placeholder_strategy ? `<span class="dlp-pattern-meta">Placeholder: ${pattern.placeholder_strategy}</span>` : ''}
+      ${pattern.validator ? `<span class="dlp-pattern-meta">Check: ${pattern.validator}</span>` : ''}
+      ${pattern.redact_group ? `<span class="dlp-pattern-meta">Group: ${escapeHtml(pattern.redact_group)}</span>` : ''}
+      ${pattern.pattern_type === 'dictionary' ? `<span class="dlp-pattern-meta">${pattern.dictionary_size} entries</span>` : ''}
       <div class="dlp-pattern-actions">
         <button class="dlp-pattern-edit" data-id="${pattern.id}" title="Edit pattern">
           <i data-lucide="pencil"></i>
         </button>
-        ${!pattern.is_builtin ? `
+        ${pattern.user_modified ? `
+          <button class="dlp-pattern-reset" data-id="${pattern.id}" title="Reset to built-in definition">
+            <i data-lucide="rotate-ccw"></i>
+          </button>
+        ` : ''}
+        ${!pattern.is_builtin && !pattern.import_id ? `
           <button class="dlp-pattern-delete" data-id="${pattern.id}" title="Delete pattern">
             <i data-lucide="trash-2"></i>
           </button>
@@ -353,6 +562,20 @@ function renderPatterns(patterns) {
     });
   });
 
+  // Add event listeners for reset buttons (edited builtins)
+  container.querySelectorAll('.dlp-pattern-reset').forEach(btn => {
+    btn.addEventListener('click', async (e) => {
+      e.stopPropagation();
+      const id = parseInt(btn.dataset.id);
+      try {
+        await invoke('reset_builtin_dlp_pattern', { id });
+        await loadDlpSettings();
+      } catch (error) {
+        console.error('Failed to reset pattern:', error);
+      }
+    });
+  });
+
   // Add event listeners for delete buttons
   container.querySelectorAll('.dlp-pattern-delete').forEach(btn => {
     btn.addEventListener('click', async (e) => {
@@ -368,6 +591,519 @@ function renderPatterns(patterns) {
   });
 }
 
+// ============ Rule Imports ============
+
+// Load imported gitleaks / trufflehog rule files
+async function loadRuleImports() {
+  try {
+    const imports = await invoke('get_dlp_rule_imports');
+    renderRuleImports(imports);
+  } catch (error) {
+    console.error('Failed to load rule imports:', error);
+  }
+}
+
+function renderRuleImports(imports) {
+  const container = document.getElementById('dlp-rule-imports');
+  if (!container) return;
+
+  if (imports.length === 0) {
+    container.innerHTML = '<p class="empty-text">No rule files imported</p>';
+    return;
+  }
+
+  container.innerHTML = imports.map(ruleImport => {
+    const skippedTitle = ruleImport.skipped.map(s => `${s.rule_id}: ${s.reason}`).join('\n');
+    return `
+    <div class="dlp-pattern-item" data-id="${ruleImport.id}">
+      <span class="dlp-pattern-name" title="${escapeHtml(ruleImport.path)}">${escapeHtml(ruleImport.path.split(/[\\/]/).pop())}</span>
+      <span class="dlp-pattern-badge regex">${ruleImport.format}</span>
+      <span class="dlp-pattern-meta">${ruleImport.rule_count} rules</span>
+      ${ruleImport.skipped.length > 0 ? `<span class="dlp-pattern-meta" title="${escapeHtml(skippedTitle)}">${ruleImport.skipped.length} skipped</span>` : ''}
+      <div class="dlp-pattern-actions">
+        <button class="dlp-pattern-edit dlp-import-resync" data-id="${ruleImport.id}" title="Re-sync from file">
+          <i data-lucide="refresh-cw"></i>
+        </button>
+        <button class="dlp-pattern-delete dlp-import-delete" data-id="${ruleImport.id}" title="Remove import and its patterns">
+          <i data-lucide="trash-2"></i>
+        </button>
+      </div>
+    </div>
+  `;
+  }).join('');
+
+  lucide.createIcons();
+
+  container.querySelectorAll('.dlp-import-resync').forEach(btn => {
+    btn.addEventListener('click', async () => {
+      try {
+        const result = await invoke('resync_dlp_rule_import', { id: parseInt(btn.dataset.id) });
+        showImportResult(result);
+      } catch (error) {
+        showSettingsStatus(`Re-sync failed: ${error}`, 'error', 'dlp-import-status');
+      }
+      await Promise.all([loadRuleImports(), loadDlpSettings()]);
+    });
+  });
+
+  container.querySelectorAll('.dlp-import-delete').forEach(btn => {
+    btn.addEventListener('click', async () => {
+      try {
+        await invoke('delete_dlp_rule_import', { id: parseInt(btn.dataset.id) });
+      } catch (error) {
+        console.error('Failed to delete rule import:', error);
+      }
+      await Promise.all([loadRuleImports(), loadDlpSettings()]);
+    });
+  });
+}
+
+// Run every pattern's regression corpus and list the failures
+async function runCorpora() {
+  const container = document.getElementById('dlp-corpus-report');
+  let report;
+  try {
+    report = await invoke('run_dlp_pattern_corpora');
+  } catch (error) {
+    showSettingsStatus(`Corpus run failed: ${error}`, 'error', 'dlp-corpus-status');
+    return;
+  }
+
+  const summary = `${report.patterns_checked} patterns, ${report.samples} samples` +
+    (report.patterns_without_corpus > 0 ? ` (${report.patterns_without_corpus} patterns have no samples)` : '');
+  if (report.patterns_failed === 0) {
+    showSettingsStatus(`All passed: ${summary}`, 'success', 'dlp-corpus-status');
+  } else {
+    showSettingsStatus(`${report.patterns_failed} failed: ${summary}`, 'error', 'dlp-corpus-status');
+  }
+
+  container.innerHTML = report.results.filter(r => !r.passed).map(result => {
+    const failures = result.error
+      ? [escapeHtml(result.error)]
+      : result.failures.map(f => f.must_match
+        ? `No match: <code>${escapeHtml(f.sample)}</code>`
+        : `Matched <code>${escapeHtml(f.found.join(', '))}</code> in <code>${escapeHtml(f.sample)}</code>`);
+    return `
+    <div class="dlp-pattern-item" data-id="${result.pattern_id}">
+      <span class="dlp-pattern-name">${escapeHtml(result.pattern_name)}</span>
+      ${result.is_builtin ? '<span class="dlp-pattern-badge builtin">Built-in</span>' : ''}
+      ${result.enabled ? '' : '<span class="dlp-pattern-meta">Disabled</span>'}
+      <span class="dlp-pattern-meta">${failures.join('<br>')}</span>
+    </div>
+  `;
+  }).join('');
+}
+
+function showImportResult(result) {
+  let message = `Imported ${result.imported} rules`;
+  if (result.removed > 0) message += `, removed ${result.removed}`;
+  if (result.skipped.length > 0) message += `, ${result.skipped.length} could not be translated`;
+  if (result.notes.length > 0) {
+    console.info('Rule import notes:\n' + result.notes.join('\n'));
+  }
+  showSettingsStatus(message, result.skipped.length > 0 ? 'error' : 'success', 'dlp-import-status');
+}
+
+// Pick a rule file and import it
+async function importRules() {
+  const { open } = window.__TAURI__.dialog;
+  const path = await open({
+    multiple: false,
+    filters: [{ name: 'Rule files', extensions: ['toml', 'yaml', 'yml'] }]
+  });
+  if (!path) return;
+
+  try {
+    const result = await invoke('import_dlp_rules', { path });
+    showImportResult(result);
+  } catch (error) {
+    showSettingsStatus(`Import failed: ${error}`, 'error', 'dlp-import-status');
+  }
+  await Promise.all([loadRuleImports(), loadDlpSettings()]);
+}
+
+// ============ Known Secrets ============
+
+// Load known secret sources (.env / credential files)
+async function loadKnownSecretSources() {
+  try {
+    const sources = await invoke('get_dlp_known_secret_sources');
+    renderKnownSecretSources(sources);
+  } catch (error) {
+    console.error('Failed to load known secret sources:', error);
+  }
+}
+
+function renderKnownSecretSources(sources) {
+  const container = document.getElementById('dlp-known-secrets');
+  if (!container) return;
+
+  if (sources.length === 0) {
+    container.innerHTML = '<p class="empty-text">No known secret files</p>';
+    return;
+  }
+
+  container.innerHTML = sources.map(source => `
+    <div class="dlp-pattern-item" data-id="${source.id}">
+      <span class="dlp-pattern-name" title="${escapeHtml(source.path)}">${escapeHtml(source.path)}</span>
+      <span class="dlp-pattern-meta">${source.file_count} files</span>
+      <span class="dlp-pattern-meta">${source.value_count} values</span>
+      ${source.error ? `<span class="dlp-pattern-meta" title="${escapeHtml(source.error)}">read errors</span>` : ''}
+      <div class="dlp-pattern-actions">
+        <button class="dlp-pattern-edit dlp-known-secret-resync" data-id="${source.id}" title="Re-read files">
+          <i data-lucide="refresh-cw"></i>
+        </button>
+        <button class="dlp-pattern-delete dlp-known-secret-delete" data-id="${source.id}" title="Remove and forget its hashes">
+          <i data-lucide="trash-2"></i>
+        </button>
+      </div>
+    </div>
+  `).join('');
+
+  lucide.createIcons();
+
+  container.querySelectorAll('.dlp-known-secret-resync').forEach(btn => {
+    btn.addEventListener('click', async () => {
+      try {
+        const result = await invoke('resync_dlp_known_secret_source', { id: parseInt(btn.dataset.id) });
+        showKnownSecretResult(result);
+      } catch (error) {
+        showSettingsStatus(`Re-read failed: ${error}`, 'error', 'dlp-known-secret-status');
+      }
+      await loadKnownSecretSources();
+    });
+  });
+
+  container.querySelectorAll('.dlp-known-secret-delete').forEach(btn => {
+    btn.addEventListener('click', async () => {
+      try {
+        await invoke('delete_dlp_known_secret_source', { id: parseInt(btn.dataset.id) });
+      } catch (error) {
+        console.error('Failed to delete known secret source:', error);
+      }
+      await loadKnownSecretSources();
+    });
+  });
+}
+
+function showKnownSecretResult(result) {
+  let message = `Read ${result.value_count} values from ${result.file_count} files`;
+  if (result.errors.length > 0) {
+    message += `, ${result.errors.length} could not be read`;
+    console.info('Known secret read errors:\n' + result.errors.join('\n'));
+  }
+  showSettingsStatus(message, result.errors.length > 0 ? 'error' : 'success', 'dlp-known-secret-status');
+}
+
+// Add the file or glob typed in the input
+async function addKnownSecretSource() {
+  const input = document.getElementById('known-secret-path-input');
+  const path = input.value.trim();
+  if (!path) return;
+
+  try {
+    const result = await invoke('add_dlp_known_secret_source', { path });
+    showKnownSecretResult(result);
+    input.value = '';
+  } catch (error) {
+    showSettingsStatus(`Failed to add: ${error}`, 'error', 'dlp-known-secret-status');
+  }
+  await loadKnownSecretSources();
+}
+
+// ============ Exceptions ============
+
+// Load false-positive exceptions (revoked ones too if asked for)
+async function loadExceptions() {
+  const includeRevoked = document.getElementById('show-revoked-exceptions')?.checked ?? false;
+  try {
+    const exceptions = await invoke('get_dlp_exceptions', { includeRevoked });
+    renderExceptions(exceptions);
+  } catch (error) {
+    console.error('Failed to load exceptions:', error);
+  }
+}
+
+// What an exception covers, e.g. "API Keys · sk-t...0001 · cursor-hooks"
+function describeException(exception) {
+  const parts = [exception.pattern_name || 'Any pattern'];
+  if (exception.value_hash) parts.push(exception.value_display || `value #${exception.value_hash}`);
+  if (exception.value_regex) parts.push(`/${exception.value_regex}/`);
+  if (exception.file_path) parts.push(exception.file_path);
+  if (exception.backend) parts.push(exception.backend);
+  return parts.join(' · ');
+}
+
+function renderExceptions(exceptions) {
+  const container = document.getElementById('dlp-exceptions');
+  if (!container) return;
+
+  if (exceptions.length === 0) {
+    container.innerHTML = '<p class="empty-text">No exceptions</p>';
+    return;
+  }
+
+  container.innerHTML = exceptions.map(exception => {
+    const audit = [
+      `Created ${new Date(exception.created_at).toLocaleString()}`,
+      exception.detection_id ? `from detection #${exception.detection_id}` : 'by hand',
+      exception.reason ? `Reason: ${exception.reason}` : null,
+      exception.expires_at ? `Expires ${new Date(exception.expires_at).toLocaleString()}` : null,
+      exception.revoked_at ? `Revoked ${new Date(exception.revoked_at).toLocaleString()}` : null,
+      exception.last_hit ? `Last applied ${new Date(exception.last_hit).toLocaleString()}` : null
+    ].filter(Boolean).join('\n');
+    return `
+      <div class="dlp-pattern-item" data-id="${exception.id}" title="${escapeHtml(audit)}">
+        <span class="dlp-pattern-name">${escapeHtml(describeException(exception))}</span>
+        ${exception.status !== 'active' ? `<span class="dlp-pattern-meta">${exception.status}</span>` : ''}
+        <span class="dlp-pattern-meta">${exception.hit_count} hits</span>
+        <div class="dlp-pattern-actions">
+          ${exception.status === 'revoked' ? '' : `
+          <button class="dlp-pattern-delete dlp-exception-revoke" data-id="${exception.id}" title="Revoke (kept in the audit trail)">
+            <i data-lucide="x"></i>
+          </button>`}
+        </div>
+      </div>
+    `;
+  }).join('');
+
+  lucide.createIcons();
+
+  container.querySelectorAll('.dlp-exception-revoke').forEach(btn => {
+    btn.addEventListener('click', async () => {
+      try {
+        await invoke('revoke_dlp_exception', { id: parseInt(btn.dataset.id) });
+      } catch (error) {
+        showSettingsStatus(`Failed to revoke: ${error}`, 'error', 'dlp-exception-status');
+      }
+      await loadExceptions();
+    });
+  });
+}
+
+// Add the exception described by the form
+async function addException() {
+  const field = (id) => document.getElementById(id).value.trim() || null;
+  try {
+    await invoke('add_dlp_exception', {
+      patternName: field('exception-pattern-input'),
+      value: field('exception-value-input'),
+      valueRegex: field('exception-regex-input'),
+      filePath: field('exception-file-input'),
+      backend: field('exception-backend-input'),
+      expiresAt: field('exception-expires-input'),
+      reason: field('exception-reason-input')
+    });
+    ['exception-value-input', 'exception-regex-input', 'exception-file-input', 'exception-pattern-input',
+      'exception-backend-input', 'exception-expires-input', 'exception-reason-input']
+      .forEach(id => { document.getElementById(id).value = ''; });
+    showSettingsStatus('Exception added', 'success', 'dlp-exception-status');
+  } catch (error) {
+    showSettingsStatus(`Failed to add: ${error}`, 'error', 'dlp-exception-status');
+  }
+  await loadExceptions();
+}
+
+// ============ History Scan ============
+
+// Scan whose progress and results are shown
+let retroScanId = null;
+
+// Candidate pattern choices, keeping the current selection
+function renderRetroScanPatternOptions(patterns) {
+  const select = document.getElementById('retro-scan-patterns-select');
+  if (!select) return;
+  const selected = new Set(Array.from(select.selectedOptions).map(o => o.value));
+  select.innerHTML = patterns.map(pattern => `
+    <option value="${pattern.id}" ${selected.has(String(pattern.id)) ? 'selected' : ''}>
+      ${escapeHtml(pattern.name)}${pattern.enabled ? '' : ' (disabled)'}${pattern.shadow ? ' (shadow)' : ''}
+    </option>
+  `).join('');
+}
+
+async function loadRetroScanBackends() {
+  const select = document.getElementById('retro-scan-backend-select');
+  if (!select) return;
+  try {
+    const backends = await invoke('get_backends');
+    select.innerHTML = '<option value="all">All backends</option>' + backends
+      .map(backend => `<option value="${escapeHtml(backend)}">${escapeHtml(backend)}</option>`)
+      .join('');
+  } catch (error) {
+    console.error('Failed to load backends:', error);
+  }
+}
+
+function describeRetroScan(scan) {
+  const done = scan.requests_total > 0 ? Math.round(100 * scan.requests_scanned / scan.requests_total) : 100;
+  const parts = [
+    `${scan.requests_scanned} of ${scan.requests_total} requests`,
+    `${scan.detections} detections`
+  ];
+  if (scan.status === 'running') parts.unshift(`${done}%`);
+  if (scan.error) parts.push(scan.error);
+  return parts.join(' · ');
+}
+
+function setRetroScanRunning(running) {
+  const startBtn = document.getElementById('start-retro-scan-btn');
+  const cancelBtn = document.getElementById('cancel-retro-scan-btn');
+  if (startBtn) startBtn.disabled = running;
+  if (cancelBtn) cancelBtn.style.display = running ? '' : 'none';
+}
+
+// Load recent scans
+async function loadRetroScans() {
+  const container = document.getElementById('dlp-retro-scans');
+  if (!container) return;
+  let scans;
+  try {
+    scans = await invoke('get_dlp_retro_scans');
+  } catch (error) {
+    console.error('Failed to load history scans:', error);
+    return;
+  }
+
+  const running = scans.find(scan => scan.status === 'running');
+  setRetroScanRunning(!!running);
+  if (running) retroScanId = running.id;
+
+  if (scans.length === 0) {
+    container.innerHTML = '<p class="empty-text">No history scans</p>';
+    return;
+  }
+
+  container.innerHTML = scans.map(scan => {
+    const scope = [
+      scan.pattern_ids ? `${scan.pattern_ids.length} candidate patterns` : 'Current patterns',
+      scan.backend || 'all backends',
+      `since ${new Date(scan.since).toLocaleString()}`
+    ].join(' · ');
+    return `
+    <div class="dlp-pattern-item" data-id="${scan.id}" title="Started ${escapeHtml(new Date(scan.started_at).toLocaleString())}">
+      <span class="dlp-pattern-name">${escapeHtml(scope)}</span>
+      ${scan.status !== 'completed' ? `<span class="dlp-pattern-meta">${scan.status}</span>` : ''}
+      <span class="dlp-pattern-meta">${escapeHtml(describeRetroScan(scan))}</span>
+      <div class="dlp-pattern-actions">
+        <button class="btn btn-secondary btn-sm dlp-retro-scan-results" data-id="${scan.id}">Results</button>
+        ${scan.status === 'running' ? '' : `
+        <button class="dlp-pattern-delete dlp-retro-scan-delete" data-id="${scan.id}" title="Delete scan and its results">
+          <i data-lucide="trash-2"></i>
+        </button>
+        `}
+      </div>
+    </div>
+  `;
+  }).join('');
+
+  lucide.createIcons();
+
+  container.querySelectorAll('.dlp-retro-scan-results').forEach(btn => {
+    btn.addEventListener('click', () => loadRetroScanResults(parseInt(btn.dataset.id)));
+  });
+
+  container.querySelectorAll('.dlp-retro-scan-delete').forEach(btn => {
+    btn.addEventListener('click', async () => {
+      const id = parseInt(btn.dataset.id);
+      try {
+        await invoke('delete_dlp_retro_scan', { scanId: id });
+      } catch (error) {
+        showSettingsStatus(`Failed to delete: ${error}`, 'error', 'dlp-retro-scan-status');
+      }
+      if (retroScanId === id) {
+        retroScanId = null;
+        document.getElementById('dlp-retro-scan-results').innerHTML = '';
+      }
+      await loadRetroScans();
+    });
+  });
+}
+
+// Show what a scan found, per pattern and by request
+async function loadRetroScanResults(scanId) {
+  const container = document.getElementById('dlp-retro-scan-results');
+  let results;
+  try {
+    results = await invoke('get_dlp_retro_scan_results', { scanId });
+  } catch (error) {
+    showSettingsStatus(`Failed to load results: ${error}`, 'error', 'dlp-retro-scan-status');
+    return;
+  }
+  retroScanId = scanId;
+
+  if (results.patterns.length === 0) {
+    container.innerHTML = `<p class="empty-text">Scan #${scanId}: nothing found</p>`;
+    return;
+  }
+
+  const patterns = results.patterns.map(pattern => `
+    <div class="dlp-pattern-item">
+      <span class="dlp-pattern-name">${escapeHtml(pattern.pattern_name)}</span>
+      <span class="dlp-pattern-meta">${pattern.count} detections · ${pattern.unique_values} values · ${pattern.request_count} requests</span>
+      <span class="dlp-pattern-meta">${new Date(pattern.first_seen).toLocaleString()} – ${new Date(pattern.last_seen).toLocaleString()}</span>
+    </div>
+  `);
+  const detections = results.detections.map(detection => {
+    const notes = [
+      detection.backend,
+      detection.found_in,
+      detection.shadow ? 'shadow' : null,
+      detection.exception_id ? `excepted (#${detection.exception_id})` : null
+    ].filter(Boolean).join(' · ');
+    return `
+    <div class="dlp-pattern-item dlp-shadow-sample">
+      <span class="dlp-pattern-meta">#${detection.request_id} · ${new Date(detection.request_timestamp).toLocaleString()}</span>
+      <span class="dlp-pattern-name">${escapeHtml(detection.pattern_name)}</span>
+      <code>${escapeHtml(detection.original_value)}</code>
+      <span class="dlp-pattern-meta">${escapeHtml(notes)}</span>
+    </div>
+  `;
+  });
+  container.innerHTML = patterns.concat(detections).join('');
+}
+
+// Start a scan with the chosen range, backend and candidate patterns
+async function startRetroScan() {
+  const select = document.getElementById('retro-scan-patterns-select');
+  const patternIds = Array.from(select.selectedOptions).map(o => parseInt(o.value));
+  try {
+    retroScanId = await invoke('start_dlp_retro_scan', {
+      timeRange: document.getElementById('retro-scan-range-select').value,
+      backend: document.getElementById('retro-scan-backend-select').value,
+      patternIds: patternIds.length > 0 ? patternIds : null
+    });
+    document.getElementById('dlp-retro-scan-results').innerHTML = '';
+    showSettingsStatus('Scan started', 'info', 'dlp-retro-scan-status');
+  } catch (error) {
+    showSettingsStatus(`Failed to start: ${error}`, 'error', 'dlp-retro-scan-status');
+  }
+  await loadRetroScans();
+}
+
+async function cancelRetroScan() {
+  if (retroScanId === null) return;
+  try {
+    await invoke('cancel_dlp_retro_scan', { scanId: retroScanId });
+  } catch (error) {
+    showSettingsStatus(`Failed to cancel: ${error}`, 'error', 'dlp-retro-scan-status');
+  }
+}
+
+// Progress events arrive after each batch and once when the scan ends
+function handleRetroScanProgress(progress) {
+  const summary = describeRetroScan({ ...progress, error: null });
+  if (progress.status === 'running') {
+    setRetroScanRunning(true);
+    showSettingsStatus(`Scanning: ${summary}`, 'info', 'dlp-retro-scan-status');
+    return;
+  }
+
+  const message = progress.error ? `Scan failed: ${progress.error}` : `Scan ${progress.status}: ${summary}`;
+  showSettingsStatus(message, progress.status === 'failed' ? 'error' : 'success', 'dlp-retro-scan-status');
+  loadRetroScans();
+  loadRetroScanResults(progress.scan_id);
+}
+
 // Show pattern modal (add or edit)
 function showPatternModal(pattern = null) {
   const modal = document.getElementById('pattern-modal');
@@ -379,12 +1115,29 @@ function showPatternModal(pattern = null) {
 
   // Reset/populate form
   document.getElementById('pattern-id').value = pattern ? pattern.id : '';
+  document.getElementById('pattern-id').dataset.savedType = pattern?.pattern_type || '';
   nameInput.value = pattern ? pattern.name : '';
   nameInput.disabled = pattern?.is_builtin || false;
 
   // Pattern type
   const patternType = pattern?.pattern_type || 'keyword';
   document.querySelector(`input[name="pattern-type"][value="${patternType}"]`).checked = true;
+  updatePatternTypeFields();
+
+  // Entropy settings (empty = defaults)
+  document.getElementById('entropy-threshold').value = pattern?.entropy_threshold ?? '';
+  document.getElementById('entropy-min-length').value = pattern?.entropy_min_length ?? '';
+
+  // Checksum validator
+  document.getElementById('pattern-validator').value = pattern?.validator || 'luhn';
+
+  // Redact group (empty = whole match)
+  document.getElementById('pattern-redact-group').value = pattern?.redact_group || '';
+
+  // Dictionary options (entries of a saved dictionary are managed from files)
+  document.getElementById('dictionary-case-sensitive').checked = pattern?.dictionary_case_sensitive || false;
+  document.getElementById('dictionary-whole-words').checked = pattern?.dictionary_whole_words ?? true;
+  document.getElementById('dictionary-size').textContent = `${pattern?.dictionary_size || 0} entries`;
 
   // Patterns
   document.getElementById('pattern-values').value = pattern?.patterns?.join('\n') || '';
@@ -393,10 +1146,30 @@ function showPatternModal(pattern = null) {
   document.getElementById('min-unique-chars').value = pattern?.min_unique_chars || 0;
   document.getElementById('min-occurrences').value = pattern?.min_occurrences || 1;
 
+  // Action (empty = global Detection Action)
+  document.getElementById('pattern-action').value = pattern?.action || '';
+
+  // Shadow mode (record only)
+  document.getElementById('pattern-shadow').checked = pattern?.shadow || false;
+
+  // Regression corpus
+  document.getElementById('corpus-must-match').value = pattern?.must_match?.join('\n') || '';
+  document.getElementById('corpus-must-not-match').value = pattern?.must_not_match?.join('\n') || '';
+
+  // Placeholder strategy (empty = look-alike value)
+  document.getElementById('pattern-placeholder-strategy').value = pattern?.placeholder_strategy || '';
+
   // Negative patterns
   const negType = pattern?.negative_pattern_type || '';
   document.querySelector(`input[name="negative-pattern-type"][value="${negType}"]`).checked = true;
   document.getElementById('negative-pattern-values').value = pattern?.negative_patterns?.join('\n') || '';
+  document.getElementById('negative-scope').value = pattern?.negative_scope || 'context';
+
+  // Context window (empty = 30) and required context
+  document.getElementById('context-window').value = pattern?.context_window ?? '';
+  const requiredType = pattern?.required_context_type || 'keyword';
+  document.querySelector(`input[name="required-context-type"][value="${requiredType}"]`).checked = true;
+  document.getElementById('required-context-values').value = pattern?.required_context?.join('\n') || '';
 
   modal.classList.add('show');
 
@@ -406,6 +1179,55 @@ function showPatternModal(pattern = null) {
   }
 }
 
+// Check whether the modal edits a dictionary that is already saved
+function isSavedDictionary() {
+  const patternType = document.querySelector('input[name="pattern-type"]:checked').value;
+  return patternType === 'dictionary' && document.getElementById('pattern-id').dataset.savedType === 'dictionary';
+}
+
+// Show the entropy/validator/dictionary settings only for their pattern types
+function updatePatternTypeFields() {
+  const patternType = document.querySelector('input[name="pattern-type"]:checked').value;
+  const savedDictionary = isSavedDictionary();
+  document.getElementById('entropy-settings').style.display = patternType === 'entropy' ? '' : 'none';
+  document.getElementById('validator-settings').style.display = patternType === 'validator' ? '' : 'none';
+  document.getElementById('dictionary-settings').style.display = patternType === 'dictionary' ? '' : 'none';
+  document.getElementById('dictionary-file-actions').style.display = savedDictionary ? '' : 'none';
+  document.getElementById('pattern-values-group').style.display = savedDictionary ? 'none' : '';
+  document.getElementById('redact-group-settings').style.display =
+    patternType === 'regex' || patternType === 'validator' ? '' : 'none';
+  const placeholders = {
+    entropy: 'Enter alphabets to scan, one per line: base64, hex',
+    dictionary: 'Enter entries, one per line'
+  };
+  document.getElementById('pattern-values').placeholder =
+    placeholders[patternType] || 'Enter keywords or regex patterns, one per line';
+}
+
+// Read the type-specific settings (entropy 0 = use the default, validator/redact group '' = none)
+function readPatternTypeSettings() {
+  const patternType = document.querySelector('input[name="pattern-type"]:checked').value;
+  const usesRegex = patternType === 'regex' || patternType === 'validator';
+  return {
+    entropyThreshold: parseFloat(document.getElementById('entropy-threshold').value) || 0,
+    entropyMinLength: parseInt(document.getElementById('entropy-min-length').value) || 0,
+    validator: patternType === 'validator' ? document.getElementById('pattern-validator').value : '',
+    redactGroup: usesRegex ? document.getElementById('pattern-redact-group').value.trim() : '',
+    dictionaryCaseSensitive: document.getElementById('dictionary-case-sensitive').checked,
+    dictionaryWholeWords: document.getElementById('dictionary-whole-words').checked
+  };
+}
+
+// Read the context rules (window 0 = use the default, empty required context = none)
+function readContextSettings() {
+  return {
+    negativeScope: document.getElementById('negative-scope').value,
+    contextWindow: parseInt(document.getElementById('context-window').value) || 0,
+    requiredContext: parseLines(document.getElementById('required-context-values').value),
+    requiredContextType: document.querySelector('input[name="required-context-type"]:checked').value
+  };
+}
+
 // Hide pattern modal
 function hidePatternModal() {
   const modal = document.getElementById('pattern-modal');
@@ -441,13 +1263,15 @@ async function testPattern() {
   }
 
   const patternType = document.querySelector('input[name="pattern-type"]:checked').value;
-  const patterns = parseLines(document.getElementById('pattern-values').value);
+  // A saved dictionary is tested with its stored entries
+  const savedDictionary = isSavedDictionary();
+  const patterns = savedDictionary ? [] : parseLines(document.getElementById('pattern-values').value);
   const minUniqueChars = parseInt(document.getElementById('min-unique-chars').value) || 0;
   const minOccurrences = parseInt(document.getElementById('min-occurrences').value) || 1;
   const negativePatternType = document.querySelector('input[name="negative-pattern-type"]:checked').value || null;
   const negativePatterns = parseLines(document.getElementById('negative-pattern-values').value);
 
-  if (patterns.length === 0) {
+  if (patterns.length === 0 && !savedDictionary) {
     testResults.innerHTML = '<span class="test-error">Add at least one pattern first</span>';
     testResults.style.display = 'block';
     return;
@@ -464,16 +1288,29 @@ async function testPattern() {
       negativePatterns: negativePatterns.length > 0 ? negativePatterns : null,
       minOccurrences,
       minUniqueChars,
-      testText
+      testText,
+      patternId: savedDictionary ? parseInt(document.getElementById('pattern-id').value) : null,
+      ...readPatternTypeSettings(),
+      ...readContextSettings()
     });
 
     if (result.excluded) {
-      testResults.innerHTML = '<span class="test-excluded">Excluded by negative pattern</span>';
+      testResults.innerHTML = '<span class="test-excluded">Excluded by context rules</span>';
     } else if (result.matches.length === 0) {
       testResults.innerHTML = '<span class="test-none">No matches found</span>';
     } else {
+      // Highlighted part is what gets replaced; the rest of the match is kept
       testResults.innerHTML = `<span class="test-success">Matches (${result.matches.length}):</span> ` +
-        result.matches.map(m => `<code>${escapeHtml(m)}</code>`).join(', ');
+        result.highlights.map(h =>
+          `<code>${escapeHtml(h.before)}<mark>${escapeHtml(h.value)}</mark>${escapeHtml(h.after)}</code>`
+        ).join(', ');
+    }
+    // Why each candidate was kept or dropped
+    if (result.candidates.length > 0) {
+      testResults.innerHTML += '<ul class="test-candidates">' +
+        result.candidates.map(c =>
+          `<li class="${c.kept ? '' : 'test-dropped'}"><code>${escapeHtml(c.value)}</code> ${c.kept ? 'kept' : 'dropped'}: ${escapeHtml(c.reason)}</li>`
+        ).join('') + '</ul>';
     }
     testResults.style.display = 'block';
   } catch (error) {
@@ -490,12 +1327,19 @@ async function savePattern() {
   const id = document.getElementById('pattern-id').value;
   const name = document.getElementById('pattern-name').value.trim();
   const patternType = document.querySelector('input[name="pattern-type"]:checked').value;
+  // Entries of a saved dictionary are only changed from files
+  const savedDictionary = isSavedDictionary();
   const patterns = parseLines(document.getElementById('pattern-values').value);
   const minUniqueChars = parseInt(document.getElementById('min-unique-chars').value) || 0;
   const minOccurrences = parseInt(document.getElementById('min-occurrences').value) || 1;
 
   const negativePatternType = document.querySelector('input[name="negative-pattern-type"]:checked').value || null;
   const negativePatterns = parseLines(document.getElementById('negative-pattern-values').value);
+  const action = document.getElementById('pattern-action').value;
+  const placeholderStrategy = document.getElementById('pattern-placeholder-strategy').value;
+  const shadow = document.getElementById('pattern-shadow').checked;
+  const mustMatch = parseLines(document.getElementById('corpus-must-match').value);
+  const mustNotMatch = parseLines(document.getElementById('corpus-must-not-match').value);
 
   // Validation
   if (!name) {
@@ -503,7 +1347,7 @@ async function savePattern() {
     return;
   }
 
-  if (patterns.length === 0) {
+  if (patterns.length === 0 && patternType !== 'dictionary') {
     alert('Please enter at least one pattern');
     return;
   }
@@ -512,33 +1356,63 @@ async function savePattern() {
   saveBtn.disabled = true;
   saveBtn.textContent = 'Saving...';
 
-  try {
+  // Saves are refused when the regression corpus fails, unless allowed
+  const save = (allowCorpusFailures) => {
     if (id) {
       // Update existing pattern
-      await invoke('update_dlp_pattern', {
+      return invoke('update_dlp_pattern', {
         id: parseInt(id),
         name,
         patternType,
-        patterns,
+        patterns: savedDictionary ? null : patterns,
         negativePatternType: negativePatternType || '',
         negativePatterns: negativePatterns.length > 0 ? negativePatterns : [],
         minOccurrences,
-        minUniqueChars
-      });
-    } else {
-      // Add new pattern
-      await invoke('add_dlp_pattern', {
-        name,
-        patternType,
-        patterns,
-        negativePatternType,
-        negativePatterns: negativePatterns.length > 0 ? negativePatterns : null,
-        minOccurrences,
-        minUniqueChars
+        minUniqueChars,
+        action,
+        placeholderStrategy,
+        shadow,
+        mustMatch,
+        mustNotMatch,
+        allowCorpusFailures,
+        ...readPatternTypeSettings(),
+        ...readContextSettings()
       });
     }
+    // Add new pattern
+    return invoke('add_dlp_pattern', {
+      name,
+      patternType,
+      patterns,
+      negativePatternType,
+      negativePatterns: negativePatterns.length > 0 ? negativePatterns : null,
+      minOccurrences,
+      minUniqueChars,
+      action: action || null,
+      placeholderStrategy: placeholderStrategy || null,
+      shadow,
+      mustMatch,
+      mustNotMatch,
+      allowCorpusFailures,
+      ...readPatternTypeSettings(),
+      ...readContextSettings()
+    });
+  };
+
+  try {
+    let saved;
+    try {
+      saved = await save(false);
+    } catch (error) {
+      if (!String(error).startsWith('Regression corpus failed')) throw error;
+      if (!confirm(`${error}\n\nSave anyway?`)) return;
+      saved = await save(true);
+    }
     hidePatternModal();
     loadDlpSettings();
+    if (saved.corpus_warning) {
+      showSettingsStatus(`Saved with failing samples. ${saved.corpus_warning}`, 'error', 'dlp-corpus-status');
+    }
   } catch (error) {
     alert(`Failed to save: ${error}`);
   } finally {
@@ -547,6 +1421,37 @@ async function savePattern() {
   }
 }
 
+// Add a list file to the dictionary being edited, or replace its entries with it
+async function importDictionaryFile(replace) {
+  const id = parseInt(document.getElementById('pattern-id').value);
+  const { open } = window.__TAURI__.dialog;
+  const path = await open({
+    multiple: false,
+    filters: [{ name: 'Word lists', extensions: ['txt', 'csv', 'lst'] }]
+  });
+  if (!path) return;
+
+  try {
+    if (replace) {
+      // Show what would change before replacing the list
+      const diff = await invoke('diff_dlp_dictionary', { id, path });
+      const sample = (entries, total) =>
+        entries.join(', ') + (total > entries.length ? `, ... (${total - entries.length} more)` : '');
+      let message = `Replace the dictionary? ${diff.added} added, ${diff.removed} removed, ${diff.unchanged} unchanged.`;
+      if (diff.added > 0) message += `\n\nAdded: ${sample(diff.added_sample, diff.added)}`;
+      if (diff.removed > 0) message += `\n\nRemoved: ${sample(diff.removed_sample, diff.removed)}`;
+      if (!confirm(message)) return;
+    }
+
+    const result = await invoke('import_dlp_dictionary', { id, path, replace });
+    document.getElementById('dictionary-size').textContent = `${result.added + result.unchanged} entries`;
+    showSettingsStatus(`Added ${result.added}, removed ${result.removed}`, 'success', 'dictionary-status');
+  } catch (error) {
+    showSettingsStatus(`Import failed: ${error}`, 'error', 'dictionary-status');
+  }
+  await loadDlpSettings();
+}
+
 // Initialize DLP settings
 function initDlpSettings() {
   // Add pattern button
@@ -555,6 +1460,40 @@ function initDlpSettings() {
     addPatternBtn.addEventListener('click', () => showPatternModal());
   }
 
+  const importRulesBtn = document.getElementById('import-rules-btn');
+  if (importRulesBtn) {
+    importRulesBtn.addEventListener('click', importRules);
+  }
+
+  const runCorporaBtn = document.getElementById('run-corpora-btn');
+  if (runCorporaBtn) {
+    runCorporaBtn.addEventListener('click', runCorpora);
+  }
+
+  const addKnownSecretBtn = document.getElementById('add-known-secret-btn');
+  if (addKnownSecretBtn) {
+    addKnownSecretBtn.addEventListener('click', addKnownSecretSource);
+  }
+
+  const addExceptionBtn = document.getElementById('add-exception-btn');
+  if (addExceptionBtn) {
+    addExceptionBtn.addEventListener('click', addException);
+  }
+  const showRevokedExceptions = document.getElementById('show-revoked-exceptions');
+  if (showRevokedExceptions) {
+    showRevokedExceptions.addEventListener('change', loadExceptions);
+  }
+
+  const startRetroScanBtn = document.getElementById('start-retro-scan-btn');
+  if (startRetroScanBtn) {
+    startRetroScanBtn.addEventListener('click', startRetroScan);
+  }
+  const cancelRetroScanBtn = document.getElementById('cancel-retro-scan-btn');
+  if (cancelRetroScanBtn) {
+    cancelRetroScanBtn.addEventListener('click', cancelRetroScan);
+  }
+  listen('dlp-retro-scan-progress', (event) => handleRetroScanProgress(event.payload));
+
   // Modal close buttons
   const closeModalBtn = document.getElementById('close-pattern-modal');
   const cancelBtn = document.getElementById('cancel-pattern-btn');
@@ -567,6 +1506,21 @@ function initDlpSettings() {
     savePatternBtn.addEventListener('click', savePattern);
   }
 
+  // Pattern type radios
+  document.querySelectorAll('input[name="pattern-type"]').forEach(radio => {
+    radio.addEventListener('change', updatePatternTypeFields);
+  });
+
+  // Dictionary list files
+  const dictionaryAddBtn = document.getElementById('dictionary-add-file-btn');
+  if (dictionaryAddBtn) {
+    dictionaryAddBtn.addEventListener('click', () => importDictionaryFile(false));
+  }
+  const dictionaryReplaceBtn = document.getElementById('dictionary-replace-file-btn');
+  if (dictionaryReplaceBtn) {
+    dictionaryReplaceBtn.addEventListener('click', () => importDictionaryFile(true));
+  }
+
   // Test pattern button
   const testPatternBtn = document.getElementById('test-pattern-btn');
   if (testPatternBtn) {
@@ -590,6 +1544,11 @@ function initDlpSettings() {
 
   // Load DLP settings
   loadDlpSettings();
+  loadRuleImports();
+  loadKnownSecretSources();
+  loadExceptions();
+  loadRetroScanBackends();
+  loadRetroScans();
 }
 
 // ============ Initialize Settings ============
@@ -611,8 +1570,26 @@ export function initSettings() {
     });
   }
 
+  // Proxy access settings
+  const saveAccessBtn = document.getElementById('save-access-btn');
+  if (saveAccessBtn) {
+    saveAccessBtn.addEventListener('click', saveProxyAccessSettings);
+  }
+
+  const regenerateBtn = document.getElementById('regenerate-token-btn');
+  if (regenerateBtn) {
+    regenerateBtn.addEventListener('click', regenerateAccessToken);
+  }
+
+  const saveScrubBtn = document.getElementById('save-scrub-headers-btn');
+  if (saveScrubBtn) {
+    saveScrubBtn.addEventListener('click', saveHeaderScrubSettings);
+  }
+
   // Load settings
   loadPortSetting();
+  loadProxyAccessSettings();
+  loadHeaderScrubSettings();
 
   // Listen for proxy server events (for real-time updates after initial load)
   listen('proxy-started', (event) => {
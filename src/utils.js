@@ -81,6 +81,19 @@ export function setCurrentPort(port) {
   currentPort = port;
 }
 
+// Current proxy access token (carried in the base URL as /t/<token>)
+let currentAccessToken = '';
+
+export function setCurrentAccessToken(token) {
+  currentAccessToken = token || '';
+}
+
+// Base URL clients should use to reach the proxy
+export function getProxyBaseUrl() {
+  const base = `http://localhost:${currentPort}`;
+  return currentAccessToken ? `${base}/t/${currentAccessToken}` : base;
+}
+
 // ============ Color Palette ============
 
 export const colors = {